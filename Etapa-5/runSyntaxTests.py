@@ -1,48 +1,66 @@
 from os.path import exists
+import difflib
 import subprocess
 from colorama import Fore, Style
 
 errorDic = {
+    "ERR_ARGUMENT": 1,
+    "ERR_UNFORMATTED": 1,
+    "ERR_MAX_DEPTH": 1,
+    "ERR_WARNINGS": 1,
     "ERR_UNDECLARED": 10,
     "ERR_DECLARED": 11,
     "ERR_VARIABLE": 20,
     "ERR_VECTOR": 21,
     "ERR_FUNCTION": 22,
+    "ERR_ENUM": 23,
+    "ERR_ENUMERATOR": 24,
+    "ERR_STATIC_INIT": 25,
+    "ERR_CONST_WRITE": 26,
+    "ERR_CONST_INIT": 27,
     "ERR_WRONG_TYPE": 30,
     "ERR_STRING_TO_X": 31,
     "ERR_CHAR_TO_X": 32,
     "ERR_STRING_MAX": 33,
     "ERR_STRING_VECTOR": 34,
+    "ERR_INVALID_CAST": 35,
+    "ERR_OUT_OF_BOUNDS": 36,
+    "ERR_DIVISION_BY_ZERO": 37,
+    "ERR_OVERFLOW": 38,
     "ERR_MISSING_ARGS": 40,
     "ERR_EXCESS_ARGS": 41,
     "ERR_WRONG_TYPE_ARGS": 42,
     "ERR_FUNCTION_STRING": 43,
+    "ERR_NO_OVERLOAD": 44,
+    "ERR_AMBIGUOUS_CALL": 45,
     "ERR_WRONG_PAR_INPUT": 50,
     "ERR_WRONG_PAR_OUTPUT": 51,
     "ERR_WRONG_PAR_RETURN": 52,
     "ERR_WRONG_PAR_SHIFT": 53,
+    "ERR_OUTSIDE_LOOP": 55,
+    "ERR_ASM": 61,
 }
 
 failed = []
 
 
+# A test may start with the error it expects, then with a line like
+# "//ARGS --check" holding the arguments the compiler gets. When the test has
+# a ".out" or ".err" file next to it, stdout or stderr must match it too.
 def execute(fileName):
     file = open(fileName, "r")
-    first_line = file.readline().strip()
-    resultName = None
-    expectedCode = None
-    inputText = None
-    shouldReturnError = first_line.startswith("//")
-    if shouldReturnError:
-        resultName = first_line[2:]
+    lines = file.readlines()
+    resultName = "Success"
+    expectedCode = 0
+    arguments = []
+    if lines and lines[0].startswith("//") and not lines[0].startswith("//ARGS"):
+        resultName = lines.pop(0).strip()[2:]
         expectedCode = errorDic[resultName]
-        inputText = file.read()
-    else:
-        expectedCode = 0
-        resultName = "Success"
-        inputText = first_line + file.read()
+    if lines and lines[0].startswith("//ARGS"):
+        arguments = lines.pop(0).split()[1:]
+    inputText = "".join(lines)
     completedProcess = subprocess.run(
-        ['./etapa5'], input=inputText, text=True, capture_output=True)
+        ['./etapa5'] + arguments, input=inputText, text=True, capture_output=True)
     exitCode = completedProcess.returncode
 
     if expectedCode != exitCode:
@@ -56,6 +74,21 @@ def execute(fileName):
               completedProcess.stdout + '\n' + Style.RESET_ALL)
         return 0
 
+    for suffix, received in ((".out", completedProcess.stdout),
+                             (".err", completedProcess.stderr)):
+        if not exists(fileName + suffix):
+            continue
+        expected = open(fileName + suffix, "r").read()
+        if expected != received:
+            failed.append(fileName)
+            print(fileName + Fore.RED + " FAILED!" + Style.RESET_ALL)
+            print("Output differs from " + fileName + suffix + ":")
+            diff = difflib.unified_diff(expected.splitlines(keepends=True),
+                                        received.splitlines(keepends=True),
+                                        "expected", "received")
+            print(Fore.MAGENTA + "".join(diff) + Style.RESET_ALL)
+            return 0
+
     return 1
 
 
@@ -83,5 +116,13 @@ for i in range(1, 9):
     if exists(fileName):
         successes += execute(fileName)
 
+for i in range(0, 100):
+    fileName = "TestsE5/grl"
+    if i < 10:
+        fileName += "0"
+    fileName += str(i)
+    if exists(fileName):
+        successes += execute(fileName)
+
 print("SUCCESSES: " + str(successes))
 print("TESTS THAT FAILED:", failed)
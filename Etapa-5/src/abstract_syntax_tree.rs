@@ -3,41 +3,375 @@
 // Guilherme de Oliveira (00278301)
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
+use std::any::Any;
+use std::cell::RefCell;
 use std::ffi::c_void;
-use std::ptr::addr_of;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
-use lrpar::NonStreamingLexer;
+use lrpar::{NonStreamingLexer, Span};
 
-use super::ast_node::AstNode;
-use super::error::CompilerError;
-use super::instructions::IlocCode;
-use super::semantic_structures::ScopeStack;
+use super::ast_export::{self, ExportNode};
+use super::ast_node::{AstNode, NodeKind};
+use super::call_graph::CallGraph;
+use super::error::{CompilerError, CompilerWarning};
+use super::formatter;
+use super::instructions::{IlocCode, RuntimeChecks};
+use super::lexical_structures::{FnDef, GlobalVarDef, GlobalVecDef, LocalVarDef};
+use super::semantic_structures::{Annotations, OverflowMode, ScopeStack, SymbolType};
+use super::tree_printer;
+use super::trivia::Comment;
+use super::visitor::{walk_fn_def, walk_node, Visitor};
+
+// Index of a node inside the AstArena. The type parameter records which
+// node struct lives there, so typed children can be fetched without matching.
+pub struct NodeId<T: ?Sized = dyn AstNode> {
+    index: usize,
+    node_type: PhantomData<fn() -> Box<T>>,
+}
+
+impl<T: ?Sized> NodeId<T> {
+    fn new(index: usize) -> NodeId<T> {
+        NodeId {
+            index,
+            node_type: PhantomData,
+        }
+    }
+
+    pub fn erase(self) -> NodeId {
+        NodeId::new(self.index)
+    }
+
+    // Unchecked: the arena panics on access if the node isn't really a U.
+    pub fn cast<U: AstNode>(self) -> NodeId<U> {
+        NodeId::new(self.index)
+    }
+}
+
+impl<T: ?Sized> Clone for NodeId<T> {
+    fn clone(&self) -> NodeId<T> {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for NodeId<T> {}
+
+impl<T: ?Sized> PartialEq for NodeId<T> {
+    fn eq(&self, other: &NodeId<T>) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T: ?Sized> Eq for NodeId<T> {}
+
+impl<T: ?Sized> Hash for NodeId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for NodeId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeId({})", self.index)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AstArena {
+    nodes: Vec<Box<dyn AstNode>>,
+}
+
+impl AstArena {
+    pub fn new() -> AstArena {
+        AstArena { nodes: vec![] }
+    }
+
+    pub fn alloc<T: AstNode>(&mut self, node: T) -> NodeId<T> {
+        self.nodes.push(Box::new(node));
+        NodeId::new(self.nodes.len() - 1)
+    }
+
+    pub fn node<T: ?Sized>(&self, id: NodeId<T>) -> &dyn AstNode {
+        self.nodes[id.index].as_ref()
+    }
+
+    pub fn get<T: AstNode>(&self, id: NodeId<T>) -> &T {
+        let node: &dyn Any = self.nodes[id.index].as_ref();
+        match node.downcast_ref::<T>() {
+            Some(node) => node,
+            None => unreachable!("{:?} does not hold the type it was allocated with", id),
+        }
+    }
+
+    pub fn kind<T: ?Sized>(&self, id: NodeId<T>) -> NodeKind {
+        self.node(id).kind()
+    }
+
+    pub fn address<T: ?Sized>(&self, id: NodeId<T>) -> *const c_void {
+        self.node(id) as *const dyn AstNode as *const c_void
+    }
+
+    // First and last byte of everything the nodes were parsed from, type names
+    // included. Parentheses aren't nodes, so a grouping at either end is left out.
+    pub fn extent(&self, ids: &[NodeId]) -> (usize, usize) {
+        let mut collector = SpanCollector {
+            start: usize::MAX,
+            end: 0,
+        };
+        for id in ids {
+            collector.visit_node(self, *id);
+        }
+        (collector.start, collector.end)
+    }
+
+    pub fn check<T: ?Sized>(
+        &self,
+        id: NodeId<T>,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        // A failing node stands for an error type, so its parents keep going.
+        let type_value = match self.node(id).check_node(self, stack, lexer) {
+            Ok(type_value) => type_value,
+            Err(error) => {
+                stack.report_error(error)?;
+                Some(SymbolType::Error)
+            }
+        };
+        if let Some(type_value) = &type_value {
+            stack.annotate_type(NodeId::new(id.index), type_value.clone());
+        }
+        Ok(type_value)
+    }
+
+    pub fn generate<T: ?Sized>(
+        &self,
+        id: NodeId<T>,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        // Operations on constants were folded by the checker and need no code.
+        if matches!(self.kind(id), NodeKind::Binary | NodeKind::Unary) {
+            if let Some(folded) = annotations.folded(NodeId::new(id.index)) {
+                return Ok(Some(folded));
+            }
+        }
+        self.node(id).generate_node(self, code, annotations, lexer)
+    }
+}
+
+// grmtools actions can't receive extra arguments, so the parser allocates into
+// a per-thread arena that AbstractSyntaxTree::new() takes over once parsing ends.
+thread_local! {
+    static PARSER_ARENA: RefCell<AstArena> = RefCell::new(AstArena::new());
+}
+
+pub fn alloc_node<T: AstNode>(node: T) -> NodeId<T> {
+    PARSER_ARENA.with(|arena| arena.borrow_mut().alloc(node))
+}
+
+fn take_parser_arena() -> AstArena {
+    PARSER_ARENA.with(|arena| arena.replace(AstArena::new()))
+}
 
 pub struct AbstractSyntaxTree {
-    top_node: Option<Box<dyn AstNode>>,
+    arena: AstArena,
+    top_level: Vec<NodeId>,
 }
 
 impl AbstractSyntaxTree {
-    pub fn new(top_node: Option<Box<dyn AstNode>>) -> AbstractSyntaxTree {
-        AbstractSyntaxTree { top_node }
+    pub fn new(top_level: Vec<NodeId>) -> AbstractSyntaxTree {
+        AbstractSyntaxTree {
+            arena: take_parser_arena(),
+            top_level,
+        }
     }
 
     #[allow(dead_code)]
     pub fn print_tree(&self, lexer: &dyn NonStreamingLexer<u32>) {
-        if let Some(node) = &self.top_node {
-            let address = addr_of!(node) as *const c_void;
-            node.print_dependencies(address, false);
-            node.print_labels(lexer, address);
+        tree_printer::print_tree(&self.arena, &self.top_level, lexer);
+    }
+
+    pub fn dot(&self, lexer: &dyn NonStreamingLexer<u32>) -> String {
+        tree_printer::dot_tree(&self.arena, &self.top_level, lexer)
+    }
+
+    pub fn export(&self, lexer: &dyn NonStreamingLexer<u32>) -> Vec<ExportNode> {
+        ast_export::export_tree(&self.arena, &self.top_level, lexer)
+    }
+
+    pub fn format(
+        &self,
+        lexer: &dyn NonStreamingLexer<u32>,
+        comments: &[Comment],
+        split_declarations: bool,
+    ) -> String {
+        formatter::format_tree(
+            &self.arena,
+            &self.top_level,
+            lexer,
+            comments,
+            split_declarations,
+        )
+    }
+
+    // The source with the casts the checker asked for spelled out.
+    pub fn insert_casts(&self, annotations: &Annotations, source: &str) -> String {
+        formatter::insert_casts(&self.arena, source, annotations.casts())
+    }
+
+    // Every pass after this one recurses once per level, so trees deeper
+    // than the limit are rejected before they can overflow the stack. The
+    // parse actions only push nodes into the arena, so nothing before this
+    // recurses, however deep the tree is.
+    pub fn check_depth(
+        &self,
+        limit: usize,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        let mut pending: Vec<(NodeId, usize)> = vec![];
+        pending.extend(self.top_level.iter().map(|id| (*id, 1)));
+        while let Some((id, depth)) = pending.pop() {
+            if depth > limit {
+                let span = self.arena.node(id).get_span();
+                let ((line, col), (_, _)) = lexer.line_col(span);
+                return Err(CompilerError::NestingTooDeep {
+                    limit,
+                    line,
+                    col,
+                    highlight: ScopeStack::form_string_highlight(span, lexer),
+                });
+            }
+            let mut children = ChildIds::default();
+            walk_node(&mut children, &self.arena, id);
+            pending.extend(children.ids.into_iter().map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+
+    pub fn check(
+        &self,
+        overflow: OverflowMode,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(Annotations, Vec<CompilerWarning>), CompilerError> {
+        let mut stack = ScopeStack::new();
+        stack.set_overflow(overflow);
+        // Enums and function signatures go first, so a body can call any
+        // function, wherever it's defined.
+        for id in &self.top_level {
+            match self.arena.kind(*id) {
+                NodeKind::EnumDef => {
+                    self.arena.check(*id, &mut stack, lexer)?;
+                }
+                NodeKind::FnDef => {
+                    let definition = self.arena.get(id.cast::<FnDef>());
+                    if let Err(error) = definition.declare(&mut stack, lexer) {
+                        stack.report_error(error)?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        for id in &self.top_level {
+            if self.arena.kind(*id) != NodeKind::EnumDef {
+                self.arena.check(*id, &mut stack, lexer)?;
+            }
+        }
+        stack.warn_unused_functions(lexer);
+        let mut errors = stack.take_errors();
+        match errors.len() {
+            0 => {
+                let mut annotations = stack.take_annotations();
+                let mut warnings = stack.take_warnings();
+                let call_graph = CallGraph::build(&self.arena, &self.top_level, &annotations)?;
+                warnings.extend(call_graph.warn_unreachable(lexer));
+                warnings.extend(call_graph.warn_recursion(lexer));
+                annotations.set_call_graph(call_graph);
+                Ok((annotations, warnings))
+            }
+            1 => Err(errors.remove(0)),
+            _ => Err(CompilerError::SemanticErrors(errors)),
         }
     }
 
-    pub fn evaluate(&self, lexer: &dyn NonStreamingLexer<u32>) -> Result<IlocCode, CompilerError> {
+    pub fn generate(
+        &self,
+        annotations: &Annotations,
+        checks: RuntimeChecks,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<IlocCode, CompilerError> {
         let mut code = IlocCode::new();
-        if let Some(node) = &self.top_node {
-            let mut stack = ScopeStack::new();
-            node.evaluate_node(&mut code, &mut stack, lexer)?;
-        };
+        code.set_checks(checks);
+        for id in &self.top_level {
+            self.arena.generate(*id, &mut code, annotations, lexer)?;
+        }
         code.collect_promises()?;
         Ok(code)
     }
 }
+
+// Collects the direct children of a node without descending any further.
+#[derive(Default)]
+struct ChildIds {
+    ids: Vec<NodeId>,
+}
+
+impl Visitor for ChildIds {
+    fn visit_node(&mut self, _arena: &AstArena, id: NodeId) {
+        self.ids.push(id);
+    }
+}
+
+struct SpanCollector {
+    start: usize,
+    end: usize,
+}
+
+impl SpanCollector {
+    fn add(&mut self, span: Span) {
+        self.start = self.start.min(span.start());
+        self.end = self.end.max(span.end());
+    }
+}
+
+impl Visitor for SpanCollector {
+    fn visit_node(&mut self, arena: &AstArena, id: NodeId) {
+        self.add(arena.node(id).get_span());
+        walk_node(self, arena, id);
+    }
+
+    fn visit_global_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<GlobalVarDef>,
+        node: &GlobalVarDef,
+    ) {
+        self.add(node.var_type());
+    }
+
+    fn visit_global_vec_def(
+        &mut self,
+        arena: &AstArena,
+        _id: NodeId<GlobalVecDef>,
+        node: &GlobalVecDef,
+    ) {
+        self.add(node.var_type());
+        self.visit_node(arena, node.vec_size().erase());
+    }
+
+    fn visit_fn_def(&mut self, arena: &AstArena, id: NodeId<FnDef>, node: &FnDef) {
+        self.add(node.return_type());
+        walk_fn_def(self, arena, id, node);
+    }
+
+    fn visit_local_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LocalVarDef>,
+        node: &LocalVarDef,
+    ) {
+        self.add(node.var_type());
+    }
+}
@@ -0,0 +1,284 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use lrpar::{NonStreamingLexer, Span};
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::lexical_structures::{
+    Binary, Cast, EnumDef, FnDef, GlobalVarDef, GlobalVecDef, LocalVarDef, Unary,
+};
+use super::visitor::{
+    walk_binary, walk_cast, walk_fn_def, walk_global_vec_def, walk_node, walk_unary, Visitor,
+};
+
+// Pointer-free copy of the tree, so dumps are stable between runs and can be
+// read back. Parameters and enumerators aren't arena nodes, but are exported
+// as children of their definition all the same.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportNode {
+    pub kind: String,
+    pub span: (usize, usize),
+    pub line: usize,
+    pub col: usize,
+    pub text: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<ExportNode>,
+}
+
+impl ExportNode {
+    fn new(kind: &str, span: Span, lexer: &dyn NonStreamingLexer<u32>) -> ExportNode {
+        let ((line, col), (_, _)) = lexer.line_col(span);
+        ExportNode {
+            kind: kind.to_string(),
+            span: (span.start(), span.end()),
+            line,
+            col,
+            text: lexer.span_str(span).to_string(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+}
+
+pub fn export_tree(
+    arena: &AstArena,
+    top_level: &[NodeId],
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Vec<ExportNode> {
+    top_level
+        .iter()
+        .map(|id| export_node(arena, *id, lexer))
+        .collect()
+}
+
+fn export_node(arena: &AstArena, id: NodeId, lexer: &dyn NonStreamingLexer<u32>) -> ExportNode {
+    let kind = format!("{:?}", arena.kind(id));
+    let mut node = ExportNode::new(&kind, arena.node(id).get_span(), lexer);
+
+    let mut exporter = NodeExporter {
+        lexer,
+        attributes: vec![],
+        leading_children: vec![],
+        children: vec![],
+    };
+    walk_node(&mut exporter, arena, id);
+
+    node.attributes = exporter.attributes;
+    node.children = exporter.leading_children;
+    for child in exporter.children {
+        node.children.push(export_node(arena, child, lexer));
+    }
+    node
+}
+
+// Gathers the attributes and direct children of a single node.
+struct NodeExporter<'a, 'input> {
+    lexer: &'a dyn NonStreamingLexer<'input, u32>,
+    attributes: Vec<(String, String)>,
+    leading_children: Vec<ExportNode>,
+    children: Vec<NodeId>,
+}
+
+impl NodeExporter<'_, '_> {
+    fn attribute(&mut self, key: &str, value: String) {
+        self.attributes.push((key.to_string(), value));
+    }
+
+    fn type_attribute(&mut self, key: &str, span: Span) {
+        let value = self.lexer.span_str(span).to_string();
+        self.attribute(key, value);
+    }
+}
+
+impl Visitor for NodeExporter<'_, '_> {
+    fn visit_node(&mut self, _arena: &AstArena, id: NodeId) {
+        self.children.push(id);
+    }
+
+    fn visit_global_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<GlobalVarDef>,
+        node: &GlobalVarDef,
+    ) {
+        self.attribute("static", node.is_static().to_string());
+        self.type_attribute("type", node.var_type());
+    }
+
+    fn visit_global_vec_def(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<GlobalVecDef>,
+        node: &GlobalVecDef,
+    ) {
+        self.attribute("static", node.is_static().to_string());
+        self.type_attribute("type", node.var_type());
+        walk_global_vec_def(self, arena, id, node);
+    }
+
+    fn visit_enum_def(&mut self, _arena: &AstArena, _id: NodeId<EnumDef>, node: &EnumDef) {
+        for enumerator in node.enumerators() {
+            let mut child = ExportNode::new("Enumerator", enumerator.node_id, self.lexer);
+            if let Some(value) = enumerator.value {
+                let sign = if enumerator.is_negative { "-" } else { "" };
+                let value = format!("{}{}", sign, self.lexer.span_str(value));
+                child.attributes.push(("value".to_string(), value));
+            }
+            self.leading_children.push(child);
+        }
+    }
+
+    fn visit_fn_def(&mut self, arena: &AstArena, id: NodeId<FnDef>, node: &FnDef) {
+        self.attribute("static", node.is_static().to_string());
+        self.type_attribute("type", node.return_type());
+        for param in node.params() {
+            let mut child = ExportNode::new("Parameter", param.node_id, self.lexer);
+            child
+                .attributes
+                .push(("const".to_string(), param.is_const.to_string()));
+            let param_type = self.lexer.span_str(param.param_type).to_string();
+            child.attributes.push(("type".to_string(), param_type));
+            self.leading_children.push(child);
+        }
+        walk_fn_def(self, arena, id, node);
+    }
+
+    fn visit_local_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LocalVarDef>,
+        node: &LocalVarDef,
+    ) {
+        self.attribute("static", node.is_static().to_string());
+        self.attribute("const", node.is_const().to_string());
+        self.type_attribute("type", node.var_type());
+    }
+
+    fn visit_binary(&mut self, arena: &AstArena, id: NodeId<Binary>, node: &Binary) {
+        self.attribute("op", format!("{:?}", node.op_type()));
+        walk_binary(self, arena, id, node);
+    }
+
+    fn visit_unary(&mut self, arena: &AstArena, id: NodeId<Unary>, node: &Unary) {
+        self.attribute("op", format!("{:?}", node.op_type()));
+        walk_unary(self, arena, id, node);
+    }
+
+    fn visit_cast(&mut self, arena: &AstArena, id: NodeId<Cast>, node: &Cast) {
+        self.type_attribute("type", node.cast_type());
+        walk_cast(self, arena, id, node);
+    }
+}
+
+pub fn to_json(nodes: &[ExportNode]) -> String {
+    let mut out = String::new();
+    if nodes.is_empty() {
+        out.push_str("[]\n");
+        return out;
+    }
+    out.push_str("[\n");
+    write_json_list(nodes, 1, &mut out);
+    out.push_str("]\n");
+    out
+}
+
+fn write_json_list(nodes: &[ExportNode], depth: usize, out: &mut String) {
+    for (i, node) in nodes.iter().enumerate() {
+        write_json_node(node, depth, out);
+        if i + 1 < nodes.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+}
+
+fn write_json_node(node: &ExportNode, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let field_indent = "  ".repeat(depth + 1);
+    let attributes: Vec<String> = node
+        .attributes
+        .iter()
+        .map(|(key, value)| format!("{}: {}", quote(key), quote(value)))
+        .collect();
+
+    out.push_str(&format!("{}{{\n", indent));
+    out.push_str(&format!(
+        "{}\"kind\": {},\n",
+        field_indent,
+        quote(&node.kind)
+    ));
+    out.push_str(&format!(
+        "{}\"span\": [{}, {}],\n",
+        field_indent, node.span.0, node.span.1
+    ));
+    out.push_str(&format!("{}\"line\": {},\n", field_indent, node.line));
+    out.push_str(&format!("{}\"col\": {},\n", field_indent, node.col));
+    out.push_str(&format!(
+        "{}\"text\": {},\n",
+        field_indent,
+        quote(&node.text)
+    ));
+    out.push_str(&format!(
+        "{}\"attributes\": {{{}}},\n",
+        field_indent,
+        attributes.join(", ")
+    ));
+    if node.children.is_empty() {
+        out.push_str(&format!("{}\"children\": []\n", field_indent));
+    } else {
+        out.push_str(&format!("{}\"children\": [\n", field_indent));
+        write_json_list(&node.children, depth + 2, out);
+        out.push_str(&format!("{}]\n", field_indent));
+    }
+    out.push_str(&format!("{}}}", indent));
+}
+
+pub fn to_sexp(nodes: &[ExportNode]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        write_sexp_node(node, 0, &mut out);
+        out.push('\n');
+    }
+    out
+}
+
+fn write_sexp_node(node: &ExportNode, depth: usize, out: &mut String) {
+    out.push_str(&format!(
+        "({} :span {} {} :line {} :col {} :text {}",
+        node.kind,
+        node.span.0,
+        node.span.1,
+        node.line,
+        node.col,
+        quote(&node.text)
+    ));
+    for (key, value) in &node.attributes {
+        out.push_str(&format!(" :{} {}", key, quote(value)));
+    }
+    for child in &node.children {
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth + 1));
+        write_sexp_node(child, depth + 1, out);
+    }
+    out.push(')');
+}
+
+// Both formats share the same string escapes.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,296 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use super::ast_export::ExportNode;
+use super::error::CompilerError;
+
+pub fn from_json(text: &str) -> Result<Vec<ExportNode>, CompilerError> {
+    let mut cursor = Cursor::new(text);
+    let value = cursor.read_json_value()?;
+    cursor.skip_whitespace();
+    if cursor.peek().is_some() {
+        return Err(cursor.error("trailing characters after the tree"));
+    }
+    match value {
+        JsonValue::Array(nodes) => nodes.iter().map(json_to_node).collect(),
+        _ => Err(CompilerError::MalformedAstDump(
+            "the top level should be a list of nodes".to_string(),
+        )),
+    }
+}
+
+pub fn from_sexp(text: &str) -> Result<Vec<ExportNode>, CompilerError> {
+    let mut cursor = Cursor::new(text);
+    let mut nodes = vec![];
+    cursor.skip_whitespace();
+    while cursor.peek().is_some() {
+        nodes.push(cursor.read_sexp_node()?);
+        cursor.skip_whitespace();
+    }
+    Ok(nodes)
+}
+
+enum JsonValue {
+    Number(i64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+fn json_to_node(value: &JsonValue) -> Result<ExportNode, CompilerError> {
+    let fields = match value {
+        JsonValue::Object(fields) => fields,
+        _ => return Err(malformed_field("node")),
+    };
+    let span = match json_field(fields, "span")? {
+        JsonValue::Array(bounds) if bounds.len() == 2 => (
+            json_number(&bounds[0], "span")?,
+            json_number(&bounds[1], "span")?,
+        ),
+        _ => return Err(malformed_field("span")),
+    };
+    let attributes = match json_field(fields, "attributes")? {
+        JsonValue::Object(attributes) => attributes
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), json_string(value, key)?)))
+            .collect::<Result<Vec<(String, String)>, CompilerError>>()?,
+        _ => return Err(malformed_field("attributes")),
+    };
+    let children = match json_field(fields, "children")? {
+        JsonValue::Array(children) => children
+            .iter()
+            .map(json_to_node)
+            .collect::<Result<Vec<ExportNode>, CompilerError>>()?,
+        _ => return Err(malformed_field("children")),
+    };
+    Ok(ExportNode {
+        kind: json_string(json_field(fields, "kind")?, "kind")?,
+        span,
+        line: json_number(json_field(fields, "line")?, "line")?,
+        col: json_number(json_field(fields, "col")?, "col")?,
+        text: json_string(json_field(fields, "text")?, "text")?,
+        attributes,
+        children,
+    })
+}
+
+fn json_field<'a>(
+    fields: &'a [(String, JsonValue)],
+    key: &str,
+) -> Result<&'a JsonValue, CompilerError> {
+    match fields.iter().find(|(field, _)| field == key) {
+        Some((_, value)) => Ok(value),
+        None => Err(CompilerError::MalformedAstDump(format!(
+            "node without \"{}\"",
+            key
+        ))),
+    }
+}
+
+fn json_number(value: &JsonValue, key: &str) -> Result<usize, CompilerError> {
+    match value {
+        JsonValue::Number(number) if *number >= 0 => Ok(*number as usize),
+        _ => Err(malformed_field(key)),
+    }
+}
+
+fn json_string(value: &JsonValue, key: &str) -> Result<String, CompilerError> {
+    match value {
+        JsonValue::String(string) => Ok(string.clone()),
+        _ => Err(malformed_field(key)),
+    }
+}
+
+fn malformed_field(key: &str) -> CompilerError {
+    CompilerError::MalformedAstDump(format!("unexpected value for \"{}\"", key))
+}
+
+struct Cursor {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Cursor {
+    fn new(text: &str) -> Cursor {
+        Cursor {
+            chars: text.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn error(&self, message: &str) -> CompilerError {
+        CompilerError::MalformedAstDump(format!("{} (at character {})", message, self.position))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), CompilerError> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || "()[]{},\"".contains(c) {
+                break;
+            }
+            word.push(c);
+            self.position += 1;
+        }
+        word
+    }
+
+    fn read_number(&mut self) -> Result<i64, CompilerError> {
+        self.skip_whitespace();
+        let word = self.read_word();
+        word.parse::<i64>()
+            .map_err(|_| self.error(&format!("invalid number \"{}\"", word)))
+    }
+
+    fn read_string(&mut self) -> Result<String, CompilerError> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => {
+                        let digits: String = (0..4).filter_map(|_| self.next()).collect();
+                        match u32::from_str_radix(&digits, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                        {
+                            Some(c) => string.push(c),
+                            None => return Err(self.error("invalid unicode escape")),
+                        }
+                    }
+                    _ => return Err(self.error("invalid escape")),
+                },
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn read_json_value(&mut self) -> Result<JsonValue, CompilerError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => Ok(JsonValue::String(self.read_string()?)),
+            Some('[') => {
+                self.position += 1;
+                let mut values = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                loop {
+                    values.push(self.read_json_value()?);
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(JsonValue::Array(values)),
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some('{') => {
+                self.position += 1;
+                let mut fields = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    let key = self.read_string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.read_json_value()?));
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(JsonValue::Object(fields)),
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            // Dumps hold no other scalars than numbers.
+            Some(_) => Ok(JsonValue::Number(self.read_number()?)),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn read_sexp_number(&mut self) -> Result<usize, CompilerError> {
+        match self.read_number()? {
+            number if number >= 0 => Ok(number as usize),
+            _ => Err(self.error("negative position")),
+        }
+    }
+
+    fn read_sexp_node(&mut self) -> Result<ExportNode, CompilerError> {
+        self.expect('(')?;
+        let mut node = ExportNode {
+            kind: self.read_word(),
+            span: (0, 0),
+            line: 0,
+            col: 0,
+            text: String::new(),
+            attributes: vec![],
+            children: vec![],
+        };
+        if node.kind.is_empty() {
+            return Err(self.error("node without kind"));
+        }
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(')') => {
+                    self.position += 1;
+                    return Ok(node);
+                }
+                Some('(') => node.children.push(self.read_sexp_node()?),
+                Some(':') => {
+                    self.position += 1;
+                    let key = self.read_word();
+                    match key.as_str() {
+                        "span" => node.span = (self.read_sexp_number()?, self.read_sexp_number()?),
+                        "line" => node.line = self.read_sexp_number()?,
+                        "col" => node.col = self.read_sexp_number()?,
+                        "text" => node.text = self.read_string()?,
+                        _ => node.attributes.push((key, self.read_string()?)),
+                    }
+                }
+                _ => return Err(self.error("expected a keyword, a child or ')'")),
+            }
+        }
+    }
+}
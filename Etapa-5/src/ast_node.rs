@@ -5,53 +5,73 @@
 
 use lrpar::NonStreamingLexer;
 use lrpar::Span;
-use std::ffi::c_void;
+use std::any::Any;
 use std::fmt::Debug;
 
+use super::abstract_syntax_tree::AstArena;
 use super::error::CompilerError;
 use super::instructions::IlocCode;
-use super::semantic_structures::{ScopeStack, SymbolType};
+use super::semantic_structures::{Annotations, ScopeStack, SymbolType};
 
-pub trait AstNode: Debug {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool);
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void);
-    fn is_tree_member(&self) -> bool;
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>);
-    fn evaluate_node(
+// check_node does scoping and type checking only; generate_node emits ILOC
+// from what the checker recorded and is never run on a tree that failed it.
+pub trait AstNode: Any + Debug {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError>;
-    fn get_span(&self) -> Span;
-    fn get_next(&self) -> &Option<Box<dyn AstNode>>;
-}
-
-impl AstNode for Box<dyn AstNode> {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool) {
-        self.as_ref().print_dependencies(own_address, ripple)
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.as_ref().print_labels(lexer, own_address)
-    }
-    fn is_tree_member(&self) -> bool {
-        self.as_ref().is_tree_member()
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.as_mut().append_to_next(new_last)
-    }
-    fn evaluate_node(
+    fn generate_node(
         &self,
+        arena: &AstArena,
         code: &mut IlocCode,
-        stack: &mut ScopeStack,
+        annotations: &Annotations,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        self.as_ref().evaluate_node(code, stack, lexer)
-    }
-    fn get_span(&self) -> Span {
-        self.as_ref().get_span()
-    }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        self.as_ref().get_next()
-    }
+    ) -> Result<Option<SymbolType>, CompilerError>;
+    fn get_span(&self) -> Span;
+    fn kind(&self) -> NodeKind;
+}
+
+// One variant per node struct, used to dispatch visitors on arena entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    GlobalVarDef,
+    GlobalVecDef,
+    EnumDef,
+    FnDef,
+    LocalVarDef,
+    VarDefInitId,
+    VarDefInitLit,
+    VarLeftShift,
+    VarRightShift,
+    VecLeftShift,
+    VecRightShift,
+    VarSet,
+    VecSet,
+    Input,
+    OutputId,
+    OutputLit,
+    Continue,
+    Break,
+    Asm,
+    Return,
+    FnCall,
+    If,
+    IfElse,
+    For,
+    While,
+    CommandBlock,
+    Ternary,
+    Binary,
+    Unary,
+    Cast,
+    VecAccess,
+    VarInvoke,
+    VecInvoke,
+    LiteralInt,
+    LiteralFloat,
+    LiteralBool,
+    LiteralChar,
+    LiteralString,
 }
@@ -7,17 +7,20 @@ use lrpar::Span;
 
 use crate::lexical_structures::LiteralInt;
 
-use super::ast_node::AstNode;
+use super::abstract_syntax_tree::{alloc_node, NodeId};
 use super::error::CompilerError;
 use super::lexical_structures::{
-    CommandBlock, GlobalVarDef, GlobalVecDef, LocalVarDef, Parameter, VarDefInitId, VarDefInitLit,
-    VarInvoke,
+    CommandBlock, FnDef, GlobalVarDef, GlobalVecDef, LocalVarDef, Parameter, VarDefInitId,
+    VarDefInitLit, VarInvoke,
 };
 
 #[derive(Debug)]
 pub enum AuxVarOrVecName {
     Var(Span),
-    Vec { name: Span, size: Box<LiteralInt> },
+    Vec {
+        name: Span,
+        size: NodeId<LiteralInt>,
+    },
 }
 
 #[derive(Debug)]
@@ -31,7 +34,7 @@ pub enum AuxLocalNameDef {
     InitWithLit {
         var_name: Span,
         op_name: Span,
-        var_value: Box<dyn AstNode>,
+        var_value: NodeId,
     },
 }
 
@@ -39,48 +42,65 @@ pub enum AuxLocalNameDef {
 pub enum AuxTopDefEnd {
     FnDefEnd {
         params: Vec<Parameter>,
-        commands: CommandBlock,
+        commands: NodeId<CommandBlock>,
     },
     SingleGlob,
     GlobList(Vec<AuxVarOrVecName>),
-    VecAndGlobList(Box<LiteralInt>, Vec<AuxVarOrVecName>),
+    VecAndGlobList(NodeId<LiteralInt>, Vec<AuxVarOrVecName>),
 }
 
 pub fn top_level_def_assembler(
     is_static: bool,
     var_type: Span,
-    mut var_or_vec: Vec<AuxVarOrVecName>,
-) -> Result<Box<dyn AstNode>, CompilerError> {
-    var_or_vec.reverse();
+    var_or_vec: Vec<AuxVarOrVecName>,
+) -> Result<Vec<NodeId>, CompilerError> {
+    if var_or_vec.is_empty() {
+        return Err(CompilerError::TreeBuildingError(
+            "top_level_def_assembler() with empty length var_or_vec".to_string(),
+        ));
+    }
 
-    let mut last_step: Option<Box<dyn AstNode>> = None;
-    match loop {
-        let next_step = match var_or_vec.pop() {
-            Some(def) => def,
-            None => break last_step,
-        };
-        last_step = {
-            let last_node = match last_step {
-                Some(node) => Some(node as Box<dyn AstNode>),
-                None => None,
-            };
-            Some(match next_step {
-                AuxVarOrVecName::Var(var_name) => {
-                    Box::new(GlobalVarDef::new(is_static, var_type, var_name, last_node))
-                }
-                AuxVarOrVecName::Vec { name, size } => Box::new(GlobalVecDef::new(
-                    is_static, var_type, name, size, last_node,
-                )),
-            })
+    // Definitions are evaluated last to first, matching the order they were linked in.
+    Ok(var_or_vec
+        .into_iter()
+        .rev()
+        .map(|def| match def {
+            AuxVarOrVecName::Var(var_name) => {
+                alloc_node(GlobalVarDef::new(is_static, var_type, var_name)).erase()
+            }
+            AuxVarOrVecName::Vec { name, size } => {
+                alloc_node(GlobalVecDef::new(is_static, var_type, name, size)).erase()
+            }
+        })
+        .collect())
+}
+
+pub fn mount_top_level_def(
+    is_static: bool,
+    var_type: Span,
+    name: Span,
+    def_end: AuxTopDefEnd,
+) -> Result<Vec<NodeId>, CompilerError> {
+    Ok(match def_end {
+        AuxTopDefEnd::FnDefEnd { params, commands } => {
+            vec![alloc_node(FnDef::new(is_static, var_type, name, params, commands)).erase()]
         }
-    } {
-        None => {
-            return Err(CompilerError::TreeBuildingError(
-                "top_level_def_assembler() with empty length var_or_vec".to_string(),
-            ))
+        AuxTopDefEnd::SingleGlob => {
+            vec![alloc_node(GlobalVarDef::new(is_static, var_type, name)).erase()]
         }
-        Some(def) => Ok(def),
-    }
+        AuxTopDefEnd::GlobList(mut var_or_vec) => {
+            var_or_vec.insert(0, AuxVarOrVecName::Var(name));
+            top_level_def_assembler(is_static, var_type, var_or_vec)?
+        }
+        AuxTopDefEnd::VecAndGlobList(vec_size, var_or_vec) => {
+            let mut defs =
+                vec![alloc_node(GlobalVecDef::new(is_static, var_type, name, vec_size)).erase()];
+            if !var_or_vec.is_empty() {
+                defs.extend(top_level_def_assembler(is_static, var_type, var_or_vec)?);
+            }
+            defs
+        }
+    })
 }
 
 pub fn mount_local_def(
@@ -88,34 +108,35 @@ pub fn mount_local_def(
     is_const: bool,
     var_type: Span,
     name_def: AuxLocalNameDef,
-) -> Box<dyn AstNode> {
+) -> NodeId {
     match name_def {
-        AuxLocalNameDef::Def(var_name) => Box::new(LocalVarDef::new(
-            is_static, is_const, var_type, var_name, false, None,
-        )),
+        AuxLocalNameDef::Def(var_name) => alloc_node(LocalVarDef::new(
+            is_static, is_const, var_type, var_name, false,
+        ))
+        .erase(),
         AuxLocalNameDef::InitWithVar {
             var_name,
             op_name,
             var_value,
-        } => Box::new(VarDefInitId::new(
+        } => alloc_node(VarDefInitId::new(
             op_name,
-            Box::new(LocalVarDef::new(
-                is_static, is_const, var_type, var_name, true, None,
+            alloc_node(LocalVarDef::new(
+                is_static, is_const, var_type, var_name, true,
             )),
-            Box::new(VarInvoke::new(var_value, None)),
-            None,
-        )),
+            alloc_node(VarInvoke::new(var_value)).erase(),
+        ))
+        .erase(),
         AuxLocalNameDef::InitWithLit {
             var_name,
             op_name,
             var_value,
-        } => Box::new(VarDefInitLit::new(
+        } => alloc_node(VarDefInitLit::new(
             op_name,
-            Box::new(LocalVarDef::new(
-                is_static, is_const, var_type, var_name, true, None,
+            alloc_node(LocalVarDef::new(
+                is_static, is_const, var_type, var_name, true,
             )),
             var_value,
-            None,
-        )),
+        ))
+        .erase(),
     }
 }
@@ -0,0 +1,230 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use std::collections::HashMap;
+
+use lrpar::{NonStreamingLexer, Span};
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::ast_node::NodeKind;
+use super::error::{CompilerError, CompilerWarning};
+use super::lexical_structures::{FnCall, FnDef};
+use super::semantic_structures::{Annotations, ScopeStack};
+use super::tree_printer::dot_escape;
+use super::visitor::{walk_fn_call, Visitor};
+
+// Which function calls which, with overloads told apart by their signature.
+// Calls are resolved through the checker's annotations, so the program has to
+// be free of semantic errors.
+#[derive(Default)]
+pub struct CallGraph {
+    functions: Vec<Function>,
+}
+
+struct Function {
+    signature: String,
+    span: Span,
+    // Indices into functions, in the order of the first call to each.
+    callees: Vec<usize>,
+}
+
+impl CallGraph {
+    pub fn build(
+        arena: &AstArena,
+        top_level: &[NodeId],
+        annotations: &Annotations,
+    ) -> Result<CallGraph, CompilerError> {
+        let definitions: Vec<NodeId<FnDef>> = top_level
+            .iter()
+            .filter(|id| arena.kind(**id) == NodeKind::FnDef)
+            .map(|id| id.cast::<FnDef>())
+            .collect();
+
+        let mut functions = vec![];
+        let mut indices = HashMap::new();
+        for id in &definitions {
+            let span = arena.node(*id).get_span();
+            indices.insert((span.start(), span.end()), functions.len());
+            functions.push(Function {
+                signature: annotations.fn_signature(annotations.symbol(span)?)?,
+                span,
+                callees: vec![],
+            });
+        }
+
+        for (function, id) in functions.iter_mut().zip(&definitions) {
+            let mut collector = CallCollector::default();
+            collector.visit_node(arena, id.erase());
+            for call in collector.calls {
+                let callee = annotations.symbol(call)?.span;
+                let index = match indices.get(&(callee.start(), callee.end())) {
+                    Some(index) => *index,
+                    None => {
+                        return Err(CompilerError::SanityError(format!(
+                            "call at {:?} resolved to no function definition",
+                            call
+                        )))
+                    }
+                };
+                if !function.callees.contains(&index) {
+                    function.callees.push(index);
+                }
+            }
+        }
+
+        Ok(CallGraph { functions })
+    }
+
+    // Only functions something calls but that still can't run, because every
+    // caller is out of reach from main. The ones nobody calls are already
+    // reported as unused, and without a main there is nothing to start from.
+    pub fn warn_unreachable(&self, lexer: &dyn NonStreamingLexer<u32>) -> Vec<CompilerWarning> {
+        let reached = match self.reached_from_main() {
+            Some(reached) => reached,
+            None => return vec![],
+        };
+        let called: Vec<usize> = self
+            .functions
+            .iter()
+            .flat_map(|function| function.callees.iter().cloned())
+            .collect();
+
+        let mut warnings = vec![];
+        for (index, function) in self.functions.iter().enumerate() {
+            if reached[index] || !called.contains(&index) {
+                continue;
+            }
+            let ((line, col), (_, _)) = lexer.line_col(function.span);
+            warnings.push(CompilerWarning::UnreachableFunction {
+                id: lexer.span_str(function.span).to_string(),
+                line,
+                col,
+                highlight: ScopeStack::form_string_highlight(function.span, lexer),
+            });
+        }
+        warnings
+    }
+
+    // One warning for each group of functions that reach each other through
+    // their calls, at the first one defined.
+    pub fn warn_recursion(&self, lexer: &dyn NonStreamingLexer<u32>) -> Vec<CompilerWarning> {
+        let mut reported = vec![false; self.functions.len()];
+        let mut warnings = vec![];
+        for (index, function) in self.functions.iter().enumerate() {
+            if reported[index] || !self.is_recursive(&function.signature) {
+                continue;
+            }
+            let reached = self.reached_from(index);
+            let mut members = vec![];
+            for other in 0..self.functions.len() {
+                if other == index || (reached[other] && self.reached_from(other)[index]) {
+                    reported[other] = true;
+                    members.push(format!("\"{}\"", self.functions[other].signature));
+                }
+            }
+            let ((line, col), (_, _)) = lexer.line_col(function.span);
+            warnings.push(CompilerWarning::Recursion {
+                functions: members.join(", "),
+                line,
+                col,
+                highlight: ScopeStack::form_string_highlight(function.span, lexer),
+            });
+        }
+        warnings
+    }
+
+    // Directly, or through any number of other functions.
+    pub fn is_recursive(&self, signature: &str) -> bool {
+        match self.index_of(signature) {
+            Some(index) => self.reached_from(index)[index],
+            None => false,
+        }
+    }
+
+    // Calls nothing, so its frame never has to make room for a callee.
+    pub fn is_leaf(&self, signature: &str) -> bool {
+        match self.index_of(signature) {
+            Some(index) => self.functions[index].callees.is_empty(),
+            None => false,
+        }
+    }
+
+    // Functions out of reach from main are dashed, and the ones taking part
+    // in a recursion are drawn in red along with the calls that close it.
+    pub fn dot(&self) -> String {
+        let mut out = String::from("digraph callgraph {\n");
+        out.push_str("  node [fontname=\"monospace\", shape=box];\n");
+
+        let reached = self
+            .reached_from_main()
+            .unwrap_or_else(|| vec![true; self.functions.len()]);
+        let cycles: Vec<Vec<bool>> = (0..self.functions.len())
+            .map(|index| self.reached_from(index))
+            .collect();
+
+        for (index, function) in self.functions.iter().enumerate() {
+            let mut attributes = format!("label=\"{}\"", dot_escape(&function.signature));
+            if !reached[index] {
+                attributes.push_str(", style=dashed");
+            }
+            if cycles[index][index] {
+                attributes.push_str(", color=red");
+            }
+            out.push_str(&format!("  n{} [{}];\n", index, attributes));
+        }
+        for (caller, function) in self.functions.iter().enumerate() {
+            for callee in &function.callees {
+                let attributes = if cycles[*callee][caller] {
+                    " [color=red]"
+                } else {
+                    ""
+                };
+                out.push_str(&format!("  n{} -> n{}{};\n", caller, callee, attributes));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn index_of(&self, signature: &str) -> Option<usize> {
+        self.functions
+            .iter()
+            .position(|function| function.signature == signature)
+    }
+
+    fn reached_from_main(&self) -> Option<Vec<bool>> {
+        let main = self.index_of("main()")?;
+        let mut reached = self.reached_from(main);
+        reached[main] = true;
+        Some(reached)
+    }
+
+    // Everything start calls through at least one edge, so start itself only
+    // shows up when it's recursive.
+    fn reached_from(&self, start: usize) -> Vec<bool> {
+        let mut reached = vec![false; self.functions.len()];
+        let mut pending = self.functions[start].callees.clone();
+        while let Some(index) = pending.pop() {
+            if reached[index] {
+                continue;
+            }
+            reached[index] = true;
+            pending.extend(&self.functions[index].callees);
+        }
+        reached
+    }
+}
+
+#[derive(Default)]
+struct CallCollector {
+    calls: Vec<Span>,
+}
+
+impl Visitor for CallCollector {
+    fn visit_fn_call(&mut self, arena: &AstArena, id: NodeId<FnCall>, node: &FnCall) {
+        self.calls.push(arena.node(id).get_span());
+        walk_fn_call(self, arena, id, node)
+    }
+}
@@ -3,6 +3,7 @@
 // Guilherme de Oliveira (00278301)
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -41,12 +42,36 @@ pub enum CompilerError {
     #[error("Error in scope, this should not happen")]
     FailedScoping,
 
-    #[error("Usage of undeclared identifier: \"{id}\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    #[error("Unknown command line argument: {0}")]
+    UnknownArgument(String),
+
+    #[error("Malformed AST dump: {0}")]
+    MalformedAstDump(String),
+
+    #[error("Not formatted: {0}")]
+    UnformattedSource(String),
+
+    #[error("{}", join_errors(.0))]
+    SemanticErrors(Vec<CompilerError>),
+
+    #[error("-Werror: {0} warning(s) treated as errors")]
+    WarningsAsErrors(usize),
+
+    #[error("Nesting deeper than {limit} levels, see --max-depth\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    NestingTooDeep {
+        limit: usize,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Usage of undeclared identifier: \"{id}\"\nOccurrence at line {line}, column {col}:\n{highlight}{suggestion}")]
     SemanticErrorUndeclared {
         id: String,
         line: usize,
         col: usize,
         highlight: String,
+        suggestion: String,
     },
 
     #[error("Same-scope identifier redeclaration: \"{id}\"\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
@@ -60,37 +85,43 @@ pub enum CompilerError {
         second_highlight: String,
     },
 
-    #[error("Variable identifier used as {second_class}: \"{id}\"\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
-    SemanticErrorVariable {
+    #[error("Variable identifier used as {0}")]
+    SemanticErrorVariable(Box<ClassMismatch>),
+
+    #[error("Vector identifier used as {0}\nDid you mean \"{}[...]\"?", .0.id)]
+    SemanticErrorVector(Box<ClassMismatch>),
+
+    #[error("Function identifier used as {0}\nDid you mean \"{}(...)\"?", .0.id)]
+    SemanticErrorFunction(Box<ClassMismatch>),
+
+    #[error("Enum identifier used as {0}")]
+    SemanticErrorEnum(Box<ClassMismatch>),
+
+    #[error("Enumerator identifier used as {0}")]
+    SemanticErrorEnumerator(Box<ClassMismatch>),
+
+    #[error("Static variable initialized with non-constant identifier: \"{id}\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorStaticInit {
         id: String,
-        first_line: usize,
-        first_col: usize,
-        first_highlight: String,
-        second_class: String,
-        second_line: usize,
-        second_col: usize,
-        second_highlight: String,
+        line: usize,
+        col: usize,
+        highlight: String,
     },
 
-    #[error("Vector identifier used as {second_class}: \"{id}\"\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
-    SemanticErrorVector {
+    #[error("Const variable declared without a value: \"{id}\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorConstInit {
         id: String,
-        first_line: usize,
-        first_col: usize,
-        first_highlight: String,
-        second_class: String,
-        second_line: usize,
-        second_col: usize,
-        second_highlight: String,
+        line: usize,
+        col: usize,
+        highlight: String,
     },
 
-    #[error("Function identifier used as {second_class}: \"{id}\"\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
-    SemanticErrorFunction {
+    #[error("Invalid write to const variable: \"{id}\"\nDeclared at line {first_line}, column {first_col}:\n{first_highlight}\nWritten at line {second_line}, column {second_col}:\n{second_highlight}")]
+    SemanticErrorConstWrite {
         id: String,
         first_line: usize,
         first_col: usize,
         first_highlight: String,
-        second_class: String,
         second_line: usize,
         second_col: usize,
         second_highlight: String,
@@ -179,6 +210,26 @@ pub enum CompilerError {
         col: usize,
     },
 
+    #[error("No overload of \"{id}\" takes ({arg_types})\nCandidates:{candidates}\nCalled at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorNoOverload {
+        id: String,
+        arg_types: String,
+        candidates: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Ambiguous call to \"{id}\" with ({arg_types})\nCandidates:{candidates}\nCalled at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorAmbiguousCall {
+        id: String,
+        arg_types: String,
+        candidates: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
     #[error("Invalid argument for \"input\" command; expected variable of type \"int\" or \"float\", found \"{received_type}\";\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
     SemanticErrorWrongParInput {
         received_type: String,
@@ -226,8 +277,64 @@ pub enum CompilerError {
         col: usize,
     },
 
+    #[error(
+        "\"{keyword}\" outside of a loop\nOccurrence at line {line}, column {col}:\n{highlight}"
+    )]
+    SemanticErrorOutsideLoop {
+        keyword: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Invalid cast from \"{from_type}\" to \"{to_type}\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorInvalidCast {
+        from_type: String,
+        to_type: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Index {index} out of bounds for vector \"{id}\" of length {length}\nDeclared at line {first_line}, column {first_col}:\n{first_highlight}\nAccessed at line {second_line}, column {second_col}:\n{second_highlight}")]
+    SemanticErrorIndexOutOfBounds {
+        id: String,
+        index: i32,
+        length: u32,
+        first_line: usize,
+        first_col: usize,
+        first_highlight: String,
+        second_line: usize,
+        second_col: usize,
+        second_highlight: String,
+    },
+
+    #[error("Division by literal zero\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorDivisionByZero {
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Constant expression overflows \"int\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    SemanticErrorOverflow {
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
     #[error("Undefined behavior error: {0}")]
     IlocErrorUndefinedBehavior(String),
+
+    #[error(
+        "Invalid inline assembly: {message}\nOccurrence at line {line}, column {col}:\n{highlight}"
+    )]
+    IlocErrorAsm {
+        message: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
 }
 
 impl CompilerError {
@@ -240,27 +347,284 @@ impl CompilerError {
             | CompilerError::SemanticError(_)
             | CompilerError::TreeBuildingError(_)
             | CompilerError::EvalParserFailure
-            | CompilerError::FailedScoping => 1,
+            | CompilerError::FailedScoping
+            | CompilerError::UnknownArgument(_)
+            | CompilerError::MalformedAstDump(_)
+            | CompilerError::UnformattedSource(_)
+            | CompilerError::NestingTooDeep { .. }
+            | CompilerError::WarningsAsErrors(_) => 1,
+            CompilerError::SemanticErrors(ref errors) => match errors.first() {
+                Some(first_error) => first_error.error_code(),
+                None => 1,
+            },
             CompilerError::SemanticErrorUndeclared { .. } => 10,
             CompilerError::SemanticErrorDeclared { .. } => 11,
-            CompilerError::SemanticErrorVariable { .. } => 20,
-            CompilerError::SemanticErrorVector { .. } => 21,
-            CompilerError::SemanticErrorFunction { .. } => 22,
+            CompilerError::SemanticErrorVariable(_) => 20,
+            CompilerError::SemanticErrorVector(_) => 21,
+            CompilerError::SemanticErrorFunction(_) => 22,
+            CompilerError::SemanticErrorEnum(_) => 23,
+            CompilerError::SemanticErrorEnumerator(_) => 24,
+            CompilerError::SemanticErrorStaticInit { .. } => 25,
+            CompilerError::SemanticErrorConstWrite { .. } => 26,
+            CompilerError::SemanticErrorConstInit { .. } => 27,
             CompilerError::SemanticErrorWrongType { .. } => 30,
             CompilerError::SemanticErrorStringToX { .. } => 31,
             CompilerError::SemanticErrorCharToX { .. } => 32,
             CompilerError::SemanticErrorStringMax { .. } => 33,
             CompilerError::SemanticErrorStringVector { .. } => 34,
+            CompilerError::SemanticErrorInvalidCast { .. } => 35,
+            CompilerError::SemanticErrorIndexOutOfBounds { .. } => 36,
+            CompilerError::SemanticErrorDivisionByZero { .. } => 37,
+            CompilerError::SemanticErrorOverflow { .. } => 38,
             CompilerError::SemanticErrorMissingArgs { .. } => 40,
             CompilerError::SemanticErrorExcessArgs { .. } => 41,
             CompilerError::SemanticErrorWrongTypeArgs { .. } => 42,
             CompilerError::SemanticErrorFunctionString { .. } => 43,
+            CompilerError::SemanticErrorNoOverload { .. } => 44,
+            CompilerError::SemanticErrorAmbiguousCall { .. } => 45,
             CompilerError::SemanticErrorWrongParInput { .. } => 50,
             CompilerError::SemanticErrorWrongParOutputLit { .. }
             | CompilerError::SemanticErrorWrongParOutputId { .. } => 51,
             CompilerError::SemanticErrorWrrongParReturn { .. } => 52,
             CompilerError::SemanticErrorWrongParShift { .. } => 53,
+            CompilerError::SemanticErrorOutsideLoop { .. } => 55,
             CompilerError::IlocErrorUndefinedBehavior(_) => 60,
+            CompilerError::IlocErrorAsm { .. } => 61,
+        }
+    }
+
+    // Where the error occurs in the source, for reporting errors in order.
+    // Errors that point at two places occur at the second one.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            CompilerError::SemanticErrorVariable(mismatch)
+            | CompilerError::SemanticErrorVector(mismatch)
+            | CompilerError::SemanticErrorFunction(mismatch)
+            | CompilerError::SemanticErrorEnum(mismatch)
+            | CompilerError::SemanticErrorEnumerator(mismatch) => {
+                Some((mismatch.second_line, mismatch.second_col))
+            }
+            CompilerError::SemanticErrorDeclared {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorConstWrite {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorMissingArgs {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorExcessArgs {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorWrongTypeArgs {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorWrongParInput {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorWrongParOutputId {
+                second_line,
+                second_col,
+                ..
+            }
+            | CompilerError::SemanticErrorIndexOutOfBounds {
+                second_line,
+                second_col,
+                ..
+            } => Some((*second_line, *second_col)),
+            CompilerError::NestingTooDeep { line, col, .. }
+            | CompilerError::SemanticErrorUndeclared { line, col, .. }
+            | CompilerError::SemanticErrorStaticInit { line, col, .. }
+            | CompilerError::SemanticErrorConstInit { line, col, .. }
+            | CompilerError::SemanticErrorWrongType { line, col, .. }
+            | CompilerError::SemanticErrorStringToX { line, col, .. }
+            | CompilerError::SemanticErrorCharToX { line, col, .. }
+            | CompilerError::SemanticErrorStringMax { line, col, .. }
+            | CompilerError::SemanticErrorStringVector { line, col, .. }
+            | CompilerError::SemanticErrorFunctionString { line, col, .. }
+            | CompilerError::SemanticErrorNoOverload { line, col, .. }
+            | CompilerError::SemanticErrorAmbiguousCall { line, col, .. }
+            | CompilerError::SemanticErrorWrongParOutputLit { line, col, .. }
+            | CompilerError::SemanticErrorWrrongParReturn { line, col, .. }
+            | CompilerError::SemanticErrorWrongParShift { line, col, .. }
+            | CompilerError::SemanticErrorOutsideLoop { line, col, .. }
+            | CompilerError::SemanticErrorInvalidCast { line, col, .. }
+            | CompilerError::SemanticErrorDivisionByZero { line, col, .. }
+            | CompilerError::SemanticErrorOverflow { line, col, .. }
+            | CompilerError::IlocErrorAsm { line, col, .. } => Some((*line, *col)),
+            _ => None,
         }
     }
+
+    // Generic errors are internal failures or about the command line, the
+    // rest describe the program and don't stop the checker.
+    pub fn is_recoverable(&self) -> bool {
+        self.error_code() != 1
+    }
+}
+
+// An identifier used as a class other than the one it was declared with.
+// Boxed, since it's the largest payload and would size every error by it.
+#[derive(Debug)]
+pub struct ClassMismatch {
+    pub id: String,
+    pub first_line: usize,
+    pub first_col: usize,
+    pub first_highlight: String,
+    pub second_class: String,
+    pub second_line: usize,
+    pub second_col: usize,
+    pub second_highlight: String,
+}
+
+impl fmt::Display for ClassMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: \"{}\"\nFirst occurrence at line {}, column {}:\n{}\nAnd again at line {}, column {}:\n{}",
+            self.second_class,
+            self.id,
+            self.first_line,
+            self.first_col,
+            self.first_highlight,
+            self.second_line,
+            self.second_col,
+            self.second_highlight
+        )
+    }
+}
+
+fn join_errors(errors: &[CompilerError]) -> String {
+    let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+    messages.join("\n\n")
+}
+
+#[derive(Error, Debug)]
+pub enum CompilerWarning {
+    #[error("Redundant cast, expression is already of type \"{cast_type}\"\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    RedundantCast {
+        cast_type: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Unused variable: \"{id}\"\nDeclared at line {line}, column {col}:\n{highlight}")]
+    UnusedVariable {
+        id: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Unused parameter: \"{id}\"\nDeclared at line {line}, column {col}:\n{highlight}")]
+    UnusedParameter {
+        id: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Unused function: \"{id}\"\nDefined at line {line}, column {col}:\n{highlight}")]
+    UnusedFunction {
+        id: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Function is never reached from \"main\": \"{id}\"\nDefined at line {line}, column {col}:\n{highlight}")]
+    UnreachableFunction {
+        id: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Recursive call cycle through {functions}\nFirst defined at line {line}, column {col}:\n{highlight}")]
+    Recursion {
+        functions: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Declaration shadows an outer one: \"{id}\"\nFirst occurrence at line {first_line}, column {first_col}:\n{first_highlight}\nAnd again at line {second_line}, column {second_col}:\n{second_highlight}")]
+    Shadowing {
+        id: String,
+        first_line: usize,
+        first_col: usize,
+        first_highlight: String,
+        second_line: usize,
+        second_col: usize,
+        second_highlight: String,
+    },
+
+    #[error(
+        "Unreachable code after return\nOccurrence at line {line}, column {col}:\n{highlight}"
+    )]
+    UnreachableCode {
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Constant expression overflows \"int\", wrapped to {value}\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    Overflow {
+        value: i32,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Implicit conversion from \"{from_type}\" to \"{to_type}\" may lose information\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    LossyConversion {
+        from_type: String,
+        to_type: String,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
+
+    #[error("Read of a variable that may be unassigned: \"{id}\"\nDeclared at line {first_line}, column {first_col}:\n{first_highlight}\nRead at line {second_line}, column {second_col}:\n{second_highlight}")]
+    MaybeUnassigned {
+        id: String,
+        first_line: usize,
+        first_col: usize,
+        first_highlight: String,
+        second_line: usize,
+        second_col: usize,
+        second_highlight: String,
+    },
+
+    #[error("Not all paths return a value in function \"{id}\"\nDefined at line {first_line}, column {first_col}:\n{first_highlight}\nEnd reached at line {second_line}, column {second_col}:\n{second_highlight}")]
+    MissingReturn {
+        id: String,
+        first_line: usize,
+        first_col: usize,
+        first_highlight: String,
+        second_line: usize,
+        second_col: usize,
+        second_highlight: String,
+    },
+
+    #[error("Condition is always {value}\nOccurrence at line {line}, column {col}:\n{highlight}")]
+    ConstantCondition {
+        value: bool,
+        line: usize,
+        col: usize,
+        highlight: String,
+    },
 }
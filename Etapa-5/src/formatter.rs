@@ -0,0 +1,699 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use std::cell::Cell;
+
+use lrpar::{NonStreamingLexer, Span};
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::ast_node::{AstNode, NodeKind};
+use super::lexical_structures::{
+    Binary, BinaryType, Cast, CommandBlock, EnumDef, FnCall, FnDef, For, GlobalVarDef,
+    GlobalVecDef, If, IfElse, Input, LocalVarDef, OutputId, OutputLit, Return, Ternary, Unary,
+    UnaryType, VarDefInitId, VarDefInitLit, VarLeftShift, VarRightShift, VarSet, VecAccess,
+    VecLeftShift, VecRightShift, VecSet, While,
+};
+use super::trivia::Comment;
+
+const INDENT: &str = "  ";
+const TYPE_KEYWORDS: [&str; 5] = ["int", "float", "bool", "char", "string"];
+
+// Prints the tree back as source code in the canonical style. Parentheses
+// are regenerated from operator precedence, and names that were declared
+// together (they share the type token) are kept on the same line unless
+// split_declarations is set. Comments are printed before the token they
+// precede, on a line of their own when that token starts one.
+pub fn format_tree(
+    arena: &AstArena,
+    top_level: &[NodeId],
+    lexer: &dyn NonStreamingLexer<u32>,
+    comments: &[Comment],
+    split_declarations: bool,
+) -> String {
+    let mut formatter = Formatter {
+        arena,
+        lexer,
+        comments,
+        next_comment: Cell::new(0),
+        split_declarations,
+        lines: vec![],
+        depth: 0,
+        last_lines: None,
+        at_block_start: false,
+    };
+    formatter.top_level(top_level);
+    formatter.flush_comments(usize::MAX);
+
+    let mut source = formatter.lines.join("\n");
+    if !source.is_empty() {
+        source.push('\n');
+    }
+    source
+}
+
+struct Formatter<'a, 'input> {
+    arena: &'a AstArena,
+    lexer: &'a dyn NonStreamingLexer<'input, u32>,
+    comments: &'a [Comment],
+    // Expressions are printed through &self, so the cursor is a Cell.
+    next_comment: Cell<usize>,
+    split_declarations: bool,
+    lines: Vec<String>,
+    depth: usize,
+    // Source lines the last printed line came from, to keep blank lines and
+    // to tell trailing comments from the ones on a line of their own.
+    last_lines: Option<(usize, usize)>,
+    at_block_start: bool,
+}
+
+impl Formatter<'_, '_> {
+    fn lines_of(&self, span: Span) -> (usize, usize) {
+        let ((start_line, _), (end_line, _)) = self.lexer.line_col(span);
+        (start_line, end_line)
+    }
+
+    // A token from the source, after the comments that come before it.
+    fn text(&self, span: Span) -> String {
+        let mut text = String::new();
+        while let Some(comment) = self.next_comment_before(span.start()) {
+            text.push_str(self.lexer.span_str(comment.span));
+            if self.lexer.span_str(comment.span).starts_with("//") {
+                text.push('\n');
+                text.push_str(&INDENT.repeat(self.depth + 2));
+            } else {
+                text.push(' ');
+            }
+        }
+        text.push_str(self.lexer.span_str(span));
+        text
+    }
+
+    fn next_comment_before(&self, token: usize) -> Option<Comment> {
+        let comment = self.comments.get(self.next_comment.get()).copied()?;
+        if comment.token > token {
+            return None;
+        }
+        self.next_comment.set(self.next_comment.get() + 1);
+        Some(comment)
+    }
+
+    fn type_name(&self, span: Span) -> String {
+        let name = self.text(span);
+        if TYPE_KEYWORDS.contains(&self.lexer.span_str(span)) {
+            name
+        } else {
+            format!("enum {}", name)
+        }
+    }
+
+    fn push_line(&mut self, text: String, start_line: usize, end_line: usize) {
+        if let Some((_, last_line)) = self.last_lines {
+            if start_line > last_line + 1 && !self.at_block_start {
+                self.lines.push(String::new());
+            }
+        }
+        self.lines
+            .push(format!("{}{}", INDENT.repeat(self.depth), text));
+        self.last_lines = Some((start_line, end_line));
+        self.at_block_start = false;
+    }
+
+    fn open_line(&mut self, text: String, start_line: usize, block: NodeId<CommandBlock>) {
+        let (block_line, _) = self.lines_of(self.arena.node(block).get_span());
+        self.push_line(text, start_line, block_line);
+        self.at_block_start = true;
+    }
+
+    fn close_line(&mut self, text: &str, block: NodeId<CommandBlock>) {
+        let (_, block_line) = self.lines_of(self.arena.node(block).get_span());
+        self.at_block_start = true;
+        self.push_line(text.to_string(), block_line, block_line);
+    }
+
+    fn flush_comments(&mut self, before: usize) {
+        while let Some(comment) = self.next_comment_before(before) {
+            let (start_line, end_line) = self.lines_of(comment.span);
+            let text = self.lexer.span_str(comment.span).to_string();
+            match (self.lines.last_mut(), self.last_lines) {
+                (Some(last), Some((first_line, last_line)))
+                    if (first_line..=last_line).contains(&start_line) =>
+                {
+                    last.push(' ');
+                    last.push_str(&text);
+                    self.last_lines = Some((first_line, last_line.max(end_line)));
+                }
+                _ => self.push_line(text, start_line, end_line),
+            }
+        }
+    }
+
+    fn top_level(&mut self, top_level: &[NodeId]) {
+        let mut index = 0;
+        while index < top_level.len() {
+            let id = top_level[index];
+            let (start, _) = self.arena.extent(&[id]);
+            self.flush_comments(start);
+            match self.arena.kind(id) {
+                NodeKind::GlobalVarDef | NodeKind::GlobalVecDef => {
+                    let group = self.declaration_group(&top_level[index..]);
+                    self.global_declaration(group);
+                    index += group.len();
+                    continue;
+                }
+                NodeKind::EnumDef => self.enum_def(self.arena.get(id.cast::<EnumDef>())),
+                NodeKind::FnDef => self.fn_def(id.cast::<FnDef>()),
+                _ => self.statement(id),
+            }
+            index += 1;
+        }
+    }
+
+    // Type span shared by the names of one declaration, if id is a declaration.
+    fn declaration_type(&self, id: NodeId) -> Option<Span> {
+        match self.arena.kind(id) {
+            NodeKind::GlobalVarDef => Some(self.arena.get(id.cast::<GlobalVarDef>()).var_type()),
+            NodeKind::GlobalVecDef => Some(self.arena.get(id.cast::<GlobalVecDef>()).var_type()),
+            NodeKind::LocalVarDef => Some(self.arena.get(id.cast::<LocalVarDef>()).var_type()),
+            NodeKind::VarDefInitId => {
+                let var_def = self.arena.get(id.cast::<VarDefInitId>()).var_def();
+                Some(self.arena.get(var_def).var_type())
+            }
+            NodeKind::VarDefInitLit => {
+                let var_def = self.arena.get(id.cast::<VarDefInitLit>()).var_def();
+                Some(self.arena.get(var_def).var_type())
+            }
+            _ => None,
+        }
+    }
+
+    fn declaration_group<'l>(&self, list: &'l [NodeId]) -> &'l [NodeId] {
+        let var_type = self.declaration_type(list[0]);
+        let length = list
+            .iter()
+            .take_while(|id| self.declaration_type(**id) == var_type)
+            .count();
+        &list[..length]
+    }
+
+    fn global_declaration(&mut self, group: &[NodeId]) {
+        // Global lists are built last to first, print them in source order.
+        let mut group = group.to_vec();
+        group.sort_by_key(|id| self.arena.node(*id).get_span().start());
+
+        let (is_static, var_type) = match self.arena.kind(group[0]) {
+            NodeKind::GlobalVarDef => {
+                let node = self.arena.get(group[0].cast::<GlobalVarDef>());
+                (node.is_static(), node.var_type())
+            }
+            _ => {
+                let node = self.arena.get(group[0].cast::<GlobalVecDef>());
+                (node.is_static(), node.var_type())
+            }
+        };
+        let names: Vec<(NodeId, String)> = group
+            .iter()
+            .map(|id| {
+                let name = self.text(self.arena.node(*id).get_span());
+                match self.arena.kind(*id) {
+                    NodeKind::GlobalVecDef => {
+                        let size = self.arena.get(id.cast::<GlobalVecDef>()).vec_size();
+                        (*id, format!("{}[{}]", name, self.expression(size.erase())))
+                    }
+                    _ => (*id, name),
+                }
+            })
+            .collect();
+
+        let static_prefix = if is_static { "static " } else { "" };
+        let prefix = format!("{}{} ", static_prefix, self.type_name(var_type));
+        self.declaration(prefix, names);
+    }
+
+    fn declaration(&mut self, prefix: String, names: Vec<(NodeId, String)>) {
+        if self.split_declarations {
+            for (id, name) in names {
+                let name_start = self.declared_name(id).start();
+                self.flush_comments(name_start);
+                self.push_extent_line(format!("{}{};", prefix, name), &[id]);
+            }
+        } else {
+            let ids: Vec<NodeId> = names.iter().map(|(id, _)| *id).collect();
+            let names: Vec<String> = names.into_iter().map(|(_, name)| name).collect();
+            self.push_extent_line(format!("{}{};", prefix, names.join(", ")), &ids);
+        }
+    }
+
+    fn declared_name(&self, id: NodeId) -> Span {
+        let arena = self.arena;
+        match arena.kind(id) {
+            NodeKind::VarDefInitId => arena.node(arena.get(id.cast::<VarDefInitId>()).var_def()),
+            NodeKind::VarDefInitLit => arena.node(arena.get(id.cast::<VarDefInitLit>()).var_def()),
+            _ => arena.node(id),
+        }
+        .get_span()
+    }
+
+    fn enum_def(&mut self, node: &EnumDef) {
+        let enumerators: Vec<String> = node
+            .enumerators()
+            .iter()
+            .map(|enumerator| {
+                let name = self.text(enumerator.node_id);
+                match enumerator.value {
+                    Some(value) if enumerator.is_negative => {
+                        format!("{} = -{}", name, self.text(value))
+                    }
+                    Some(value) => format!("{} = {}", name, self.text(value)),
+                    None => name,
+                }
+            })
+            .collect();
+        let text = format!(
+            "enum {} {{ {} }};",
+            self.text(node.get_span()),
+            enumerators.join(", ")
+        );
+        let last = node
+            .enumerators()
+            .last()
+            .map_or(node.get_span(), |e| e.value.unwrap_or(e.node_id));
+        let (start_line, _) = self.lines_of(node.get_span());
+        let (_, end_line) = self.lines_of(last);
+        self.push_line(text, start_line, end_line);
+    }
+
+    fn fn_def(&mut self, id: NodeId<FnDef>) {
+        let node = self.arena.get(id);
+        // Tokens are printed in source order, so each takes its comments.
+        let static_prefix = if node.is_static() { "static " } else { "" };
+        let return_type = self.type_name(node.return_type());
+        let name = self.text(node.get_span());
+        let params: Vec<String> = node
+            .params()
+            .iter()
+            .map(|param| {
+                let const_prefix = if param.is_const { "const " } else { "" };
+                format!(
+                    "{}{} {}",
+                    const_prefix,
+                    self.type_name(param.param_type),
+                    self.text(param.node_id)
+                )
+            })
+            .collect();
+        let text = format!(
+            "{}{} {}({}) {{",
+            static_prefix,
+            return_type,
+            name,
+            params.join(", ")
+        );
+        let (start_line, _) = self.lines_of(node.return_type());
+        self.open_line(text, start_line, node.body());
+        self.block_body(node.body());
+        self.close_line("}", node.body());
+    }
+
+    fn block_body(&mut self, block: NodeId<CommandBlock>) {
+        self.depth += 1;
+        self.statements(self.arena.get(block).commands());
+        self.flush_comments(self.arena.node(block).get_span().end() - 1);
+        self.depth -= 1;
+    }
+
+    fn statements(&mut self, list: &[NodeId]) {
+        let mut index = 0;
+        while index < list.len() {
+            let (start, _) = self.arena.extent(&list[index..=index]);
+            self.flush_comments(start);
+            match self.declaration_type(list[index]) {
+                Some(_) => {
+                    let group = self.declaration_group(&list[index..]);
+                    self.local_declaration(group);
+                    index += group.len();
+                }
+                None => {
+                    self.statement(list[index]);
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    fn local_declaration(&mut self, group: &[NodeId]) {
+        let mut var_def = None;
+        let names: Vec<(NodeId, String)> = group
+            .iter()
+            .map(|id| match self.arena.kind(*id) {
+                NodeKind::VarDefInitId => {
+                    let node = self.arena.get(id.cast::<VarDefInitId>());
+                    var_def = Some(node.var_def());
+                    let name = self.text(self.arena.node(node.var_def()).get_span());
+                    (
+                        *id,
+                        format!("{} <= {}", name, self.expression(node.var_value())),
+                    )
+                }
+                NodeKind::VarDefInitLit => {
+                    let node = self.arena.get(id.cast::<VarDefInitLit>());
+                    var_def = Some(node.var_def());
+                    let name = self.text(self.arena.node(node.var_def()).get_span());
+                    (
+                        *id,
+                        format!("{} <= {}", name, self.expression(node.var_value())),
+                    )
+                }
+                _ => {
+                    var_def = Some(id.cast::<LocalVarDef>());
+                    (*id, self.text(self.arena.node(*id).get_span()))
+                }
+            })
+            .collect();
+
+        let var_def = match var_def {
+            Some(var_def) => self.arena.get(var_def),
+            None => return,
+        };
+        let static_prefix = if var_def.is_static() { "static " } else { "" };
+        let const_prefix = if var_def.is_const() { "const " } else { "" };
+        let prefix = format!(
+            "{}{}{} ",
+            static_prefix,
+            const_prefix,
+            self.type_name(var_def.var_type())
+        );
+        self.declaration(prefix, names);
+    }
+
+    fn push_extent_line(&mut self, text: String, ids: &[NodeId]) {
+        let (start, end) = self.arena.extent(ids);
+        let (start_line, _) = self.lines_of(Span::new(start, start));
+        let (_, end_line) = self.lines_of(Span::new(end, end));
+        self.push_line(text, start_line, end_line);
+    }
+
+    fn statement(&mut self, id: NodeId) {
+        let arena = self.arena;
+        let text = match arena.kind(id) {
+            NodeKind::VarLeftShift => {
+                let node = arena.get(id.cast::<VarLeftShift>());
+                self.shift(node.var_name().erase(), "<<", node.shift_amount().erase())
+            }
+            NodeKind::VarRightShift => {
+                let node = arena.get(id.cast::<VarRightShift>());
+                self.shift(node.var_name().erase(), ">>", node.shift_amount().erase())
+            }
+            NodeKind::VecLeftShift => {
+                let node = arena.get(id.cast::<VecLeftShift>());
+                self.shift(node.vec_access().erase(), "<<", node.shift_amount())
+            }
+            NodeKind::VecRightShift => {
+                let node = arena.get(id.cast::<VecRightShift>());
+                self.shift(node.vec_access().erase(), ">>", node.shift_amount())
+            }
+            NodeKind::VarSet | NodeKind::VecSet => self.assignment(id),
+            NodeKind::Input => {
+                let node = arena.get(id.cast::<Input>());
+                format!("input {}", self.expression(node.var_name()))
+            }
+            NodeKind::OutputId => {
+                let node = arena.get(id.cast::<OutputId>());
+                format!("output {}", self.expression(node.var_name()))
+            }
+            NodeKind::OutputLit => {
+                let node = arena.get(id.cast::<OutputLit>());
+                format!("output {}", self.expression(node.lit_value()))
+            }
+            NodeKind::Return => {
+                let node = arena.get(id.cast::<Return>());
+                format!("return {}", self.expression(node.ret_value()))
+            }
+            NodeKind::Continue | NodeKind::Break => self.text(arena.node(id).get_span()),
+            NodeKind::Asm => self.asm(arena.node(id).get_span()),
+            NodeKind::FnCall => self.expression(id),
+            NodeKind::If => {
+                let node = arena.get(id.cast::<If>());
+                let text = format!("if ({}) {{", self.expression(node.condition()));
+                return self.block_statement(id, text, node.consequence());
+            }
+            NodeKind::IfElse => {
+                let node = arena.get(id.cast::<IfElse>());
+                let text = format!("if ({}) {{", self.expression(node.condition()));
+                let (start_line, _) = self.lines_of(arena.node(id).get_span());
+                self.open_line(text, start_line, node.if_true());
+                self.block_body(node.if_true());
+                self.close_line("} else {", node.if_true());
+                self.at_block_start = true;
+                self.block_body(node.if_false());
+                self.close_line("};", node.if_false());
+                return;
+            }
+            NodeKind::For => {
+                let node = arena.get(id.cast::<For>());
+                let text = format!(
+                    "for ({} : {} : {}) {{",
+                    self.assignment(node.count_init()),
+                    self.expression(node.count_check()),
+                    self.assignment(node.count_iter())
+                );
+                return self.block_statement(id, text, node.actions());
+            }
+            NodeKind::While => {
+                let node = arena.get(id.cast::<While>());
+                let text = format!("while ({}) do {{", self.expression(node.condition()));
+                return self.block_statement(id, text, node.consequence());
+            }
+            NodeKind::CommandBlock => {
+                return self.block_statement(id, "{".to_string(), id.cast());
+            }
+            _ => self.expression(id),
+        };
+        self.push_extent_line(format!("{};", text), &[id]);
+    }
+
+    fn block_statement(&mut self, id: NodeId, text: String, block: NodeId<CommandBlock>) {
+        let (start, _) = self.arena.extent(&[id]);
+        let (start_line, _) = self.lines_of(Span::new(start, start));
+        self.open_line(text, start_line, block);
+        self.block_body(block);
+        self.close_line("};", block);
+    }
+
+    // ILOC ignores leading whitespace, so the body is reindented line by line.
+    fn asm(&self, span: Span) -> String {
+        let text = self.text(span);
+        let lines: Vec<&str> = text.lines().collect();
+        if lines.len() < 2 {
+            return text;
+        }
+        let body_indent = INDENT.repeat(self.depth + 1);
+        let mut reindented = vec![lines[0].trim_end().to_string()];
+        for line in &lines[1..lines.len() - 1] {
+            match line.trim() {
+                "" => reindented.push(String::new()),
+                line => reindented.push(format!("{}{}", body_indent, line)),
+            }
+        }
+        match lines[lines.len() - 1].trim() {
+            "}" => reindented.push(format!("{}}}", INDENT.repeat(self.depth))),
+            last => reindented.push(format!("{}{}", body_indent, last)),
+        }
+        reindented.join("\n")
+    }
+
+    fn shift(&self, target: NodeId, operator: &str, amount: NodeId) -> String {
+        format!(
+            "{} {} {}",
+            self.expression(target),
+            operator,
+            self.expression(amount)
+        )
+    }
+
+    fn assignment(&self, id: NodeId) -> String {
+        let (target, value) = match self.arena.kind(id) {
+            NodeKind::VarSet => {
+                let node = self.arena.get(id.cast::<VarSet>());
+                (node.var_name().erase(), node.new_value())
+            }
+            _ => {
+                let node = self.arena.get(id.cast::<VecSet>());
+                (node.vec_access().erase(), node.new_value())
+            }
+        };
+        format!("{} = {}", self.expression(target), self.expression(value))
+    }
+
+    fn expression(&self, id: NodeId) -> String {
+        let arena = self.arena;
+        match arena.kind(id) {
+            NodeKind::Ternary => {
+                let node = arena.get(id.cast::<Ternary>());
+                format!(
+                    "{} ? {} : {}",
+                    self.operand(node.condition(), 1),
+                    self.expression(node.if_true()),
+                    self.expression(node.if_false())
+                )
+            }
+            NodeKind::Binary => {
+                let node = arena.get(id.cast::<Binary>());
+                let precedence = binary_precedence(node.op_type());
+                format!(
+                    "{} {} {}",
+                    self.operand(node.lhs(), precedence),
+                    self.text(node.get_span()),
+                    self.operand(node.rhs(), precedence + 1)
+                )
+            }
+            NodeKind::Unary => {
+                let node = arena.get(id.cast::<Unary>());
+                let operand = self.operand(node.operand(), UNARY_PRECEDENCE);
+                // "& &a" must not be read back as "&&".
+                let separator = match (node.op_type(), arena.kind(node.operand())) {
+                    (UnaryType::Address, NodeKind::Unary) if operand.starts_with('&') => " ",
+                    _ => "",
+                };
+                format!("{}{}{}", self.text(node.get_span()), separator, operand)
+            }
+            NodeKind::Cast => {
+                let node = arena.get(id.cast::<Cast>());
+                format!(
+                    "({}) {}",
+                    self.type_name(node.cast_type()),
+                    self.operand(node.operand(), UNARY_PRECEDENCE)
+                )
+            }
+            NodeKind::VecAccess => {
+                let node = arena.get(id.cast::<VecAccess>());
+                format!(
+                    "{}[{}]",
+                    self.expression(node.vec_name().erase()),
+                    self.expression(node.vec_index())
+                )
+            }
+            NodeKind::FnCall => {
+                let node = arena.get(id.cast::<FnCall>());
+                let args: Vec<String> = node
+                    .args()
+                    .iter()
+                    .map(|arg| self.expression(*arg))
+                    .collect();
+                format!("{}({})", self.text(node.get_span()), args.join(", "))
+            }
+            _ => self.text(arena.node(id).get_span()),
+        }
+    }
+
+    // Wraps the expression in parentheses when it binds looser than required.
+    fn operand(&self, id: NodeId, required: u32) -> String {
+        let expression = self.expression(id);
+        if precedence(self.arena, id) < required {
+            format!("({})", expression)
+        } else {
+            expression
+        }
+    }
+}
+
+// Writes out the casts the checker would otherwise do implicitly, leaving the
+// rest of the source as it was.
+pub fn insert_casts(arena: &AstArena, source: &str, casts: &[(NodeId, String)]) -> String {
+    let mut insertions = vec![];
+    for (id, cast_type) in casts {
+        let (start, end) = balanced_extent(source, arena.extent(&[*id]));
+        if let Some(open) = grouping(source, start, end) {
+            insertions.push((open, format!("({}) ", cast_type)));
+        } else if precedence(arena, *id) < UNARY_PRECEDENCE {
+            insertions.push((start, format!("({}) (", cast_type)));
+            insertions.push((end, ")".to_string()));
+        } else {
+            insertions.push((start, format!("({}) ", cast_type)));
+        }
+    }
+    // From the end, so earlier positions stay valid.
+    insertions.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let mut fixed = source.to_string();
+    for (position, text) in insertions {
+        fixed.insert_str(position, &text);
+    }
+    fixed
+}
+
+// Grows an extent over the parentheses and brackets it leaves unmatched, like
+// the closing one of a call or a grouping that starts the expression.
+fn balanced_extent(source: &str, (mut start, mut end): (usize, usize)) -> (usize, usize) {
+    let mut depth: i32 = 0;
+    let mut lowest = 0;
+    for character in source[start..end].chars() {
+        match character {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ => continue,
+        }
+        lowest = lowest.min(depth);
+    }
+    for _ in 0..-lowest {
+        match source[..start].trim_end().strip_suffix('(') {
+            Some(before) => start = before.len(),
+            None => break,
+        }
+    }
+    for _ in 0..depth - lowest {
+        let rest = &source[end..];
+        let skipped = rest.len() - rest.trim_start().len();
+        match rest.trim_start().chars().next() {
+            Some(')') | Some(']') => end += skipped + 1,
+            _ => break,
+        }
+    }
+    (start, end)
+}
+
+// Where the parentheses around an extent open, unless there are none or they
+// belong to a call.
+fn grouping(source: &str, start: usize, end: usize) -> Option<usize> {
+    let before = source[..start].trim_end().strip_suffix('(')?;
+    if !source[end..].trim_start().starts_with(')') {
+        return None;
+    }
+    let word = before.trim_end();
+    let word = &word[word
+        .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_')
+        .len()..];
+    if !word.is_empty() && word != "return" {
+        return None;
+    }
+    Some(before.len())
+}
+
+fn precedence(arena: &AstArena, id: NodeId) -> u32 {
+    match arena.kind(id) {
+        NodeKind::Ternary => 0,
+        NodeKind::Binary => binary_precedence(arena.get(id.cast::<Binary>()).op_type()),
+        NodeKind::Unary | NodeKind::Cast => UNARY_PRECEDENCE,
+        _ => UNARY_PRECEDENCE + 1,
+    }
+}
+
+const UNARY_PRECEDENCE: u32 = 10;
+
+// Mirrors the precedence levels of parser.y, loosest first.
+fn binary_precedence(op_type: &BinaryType) -> u32 {
+    match op_type {
+        BinaryType::BoolOr => 1,
+        BinaryType::BoolAnd => 2,
+        BinaryType::BitOr => 3,
+        BinaryType::BitXor => 4,
+        BinaryType::BitAnd => 5,
+        BinaryType::Equal | BinaryType::NotEqual => 6,
+        BinaryType::Lesser
+        | BinaryType::Greater
+        | BinaryType::LesserEqual
+        | BinaryType::GreaterEqual => 7,
+        BinaryType::Add | BinaryType::Sub => 8,
+        BinaryType::Mult | BinaryType::Div | BinaryType::Mod => 9,
+    }
+}
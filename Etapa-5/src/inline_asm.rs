@@ -0,0 +1,472 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use std::collections::{HashMap, HashSet};
+
+use lrpar::{NonStreamingLexer, Span};
+
+use super::error::CompilerError;
+use super::instructions::{CodeLine, IlocCode, Instruction, Label, Operation, Register};
+use super::semantic_structures::{Annotations, ScopeStack, SymbolClass};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AsmToken {
+    Word,
+    Number(i32),
+    Comma,
+    Colon,
+    FatArrow,
+    ThinArrow,
+    Separator,
+}
+
+// Expected operand shapes, as printed by Operation::to_string().
+#[derive(Clone, Copy, Debug)]
+enum AsmPattern {
+    Reg,
+    Num,
+    Label,
+    Address,
+    Comma,
+    FatArrow,
+    ThinArrow,
+}
+
+struct AsmStatement {
+    label: Option<Span>,
+    opcode: Option<Span>,
+    operands: Vec<(AsmToken, Span)>,
+}
+
+// An operand that matched its expected shape, not yet turned into registers.
+#[derive(Clone, Copy, Debug)]
+enum AsmOperand {
+    Reg(Span),
+    Num(i32),
+    Label(Span),
+    Var(Span),
+}
+
+struct AsmContext {
+    labels: HashMap<String, Label>,
+    registers: HashMap<u32, Register>,
+}
+
+// Validates the block and resolves the variables it names, without emitting anything.
+pub fn check(
+    asm_span: Span,
+    stack: &mut ScopeStack,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<(), CompilerError> {
+    let statements = parse(asm_span, lexer)?;
+
+    let mut labels = HashSet::new();
+    for statement in &statements {
+        if let Some(label_span) = statement.label {
+            if !labels.insert(lexer.span_str(label_span)) {
+                return Err(asm_error("duplicated label", label_span, lexer));
+            }
+        }
+    }
+
+    for statement in &statements {
+        if let Some(opcode) = statement.opcode {
+            let operands = match_operands(opcode, &statement.operands, &labels, lexer)?;
+            // Variables only appear as addresses, which storeAI writes and
+            // loadAI reads.
+            let writes = lexer.span_str(opcode) == "storeAI";
+            for operand in operands {
+                if let AsmOperand::Var(span) = operand {
+                    let symbol = stack.get_previous_def(span, lexer, SymbolClass::default_var())?;
+                    if writes {
+                        stack.check_writable(span, lexer)?;
+                        stack.mark_assigned(&symbol);
+                    } else {
+                        stack.check_assigned(&symbol, span, lexer);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn assemble(
+    asm_span: Span,
+    code: &mut IlocCode,
+    annotations: &Annotations,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<(), CompilerError> {
+    let statements = parse(asm_span, lexer)?;
+
+    let mut context = AsmContext {
+        labels: HashMap::new(),
+        registers: HashMap::new(),
+    };
+    for statement in &statements {
+        if let Some(label_span) = statement.label {
+            let name = lexer.span_str(label_span).to_string();
+            context.labels.insert(name, code.new_label());
+        }
+    }
+    let labels = statements
+        .iter()
+        .filter_map(|statement| statement.label)
+        .map(|label_span| lexer.span_str(label_span))
+        .collect();
+
+    for statement in &statements {
+        let operation = match statement.opcode {
+            Some(opcode) => {
+                let operands = match_operands(opcode, &statement.operands, &labels, lexer)?;
+                build_operation(opcode, &operands, &mut context, code, annotations, lexer)?
+            }
+            None => Operation::Nop,
+        };
+        let instruction = match statement.label {
+            Some(label_span) => {
+                let label = context.labels[lexer.span_str(label_span)];
+                Instruction::Labeled(label, operation)
+            }
+            None => Instruction::Unlabeled(operation),
+        };
+        code.push_code(CodeLine::Deliver(instruction));
+    }
+
+    Ok(())
+}
+
+fn parse(
+    asm_span: Span,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Vec<AsmStatement>, CompilerError> {
+    let text = lexer.span_str(asm_span);
+    let (body_start, body_end) = match (text.find('{'), text.rfind('}')) {
+        (Some(open), Some(close)) => (asm_span.start() + open + 1, asm_span.start() + close),
+        _ => {
+            return Err(CompilerError::SanityError(format!(
+                "parse() received a block without braces: {}",
+                text
+            )))
+        }
+    };
+    let tokens = tokenize(Span::new(body_start, body_end), lexer)?;
+    split_statements(tokens, lexer)
+}
+
+fn tokenize(
+    body: Span,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Vec<(AsmToken, Span)>, CompilerError> {
+    let text = lexer.span_str(body).as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < text.len() {
+        let start = i;
+        let token = match text[i] {
+            b'\n' | b';' => {
+                i += 1;
+                AsmToken::Separator
+            }
+            b' ' | b'\t' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'/' if text.get(i + 1) == Some(&b'/') => {
+                while i < text.len() && text[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b',' => {
+                i += 1;
+                AsmToken::Comma
+            }
+            b':' => {
+                i += 1;
+                AsmToken::Colon
+            }
+            b'=' if text.get(i + 1) == Some(&b'>') => {
+                i += 2;
+                AsmToken::FatArrow
+            }
+            b'-' if text.get(i + 1) == Some(&b'>') => {
+                i += 2;
+                AsmToken::ThinArrow
+            }
+            b'-' | b'0'..=b'9' => {
+                i += 1;
+                while i < text.len() && text[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let span = Span::new(body.start() + start, body.start() + i);
+                match lexer.span_str(span).parse::<i32>() {
+                    Ok(number) => AsmToken::Number(number),
+                    Err(_) => return Err(asm_error("invalid number", span, lexer)),
+                }
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                while i < text.len() && (text[i].is_ascii_alphanumeric() || text[i] == b'_') {
+                    i += 1;
+                }
+                AsmToken::Word
+            }
+            _ => {
+                let span = Span::new(body.start() + i, body.start() + i + 1);
+                return Err(asm_error("unexpected character", span, lexer));
+            }
+        };
+        tokens.push((token, Span::new(body.start() + start, body.start() + i)));
+    }
+    tokens.push((AsmToken::Separator, Span::new(body.end(), body.end())));
+    Ok(tokens)
+}
+
+fn split_statements(
+    tokens: Vec<(AsmToken, Span)>,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Vec<AsmStatement>, CompilerError> {
+    let mut statements = vec![];
+    let mut current: Vec<(AsmToken, Span)> = vec![];
+    for token in tokens {
+        if token.0 != AsmToken::Separator {
+            current.push(token);
+            continue;
+        }
+        if current.is_empty() {
+            continue;
+        }
+        let mut rest = &current[..];
+        let label = match rest {
+            [(AsmToken::Word, label), (AsmToken::Colon, _), ..] => {
+                rest = &rest[2..];
+                Some(*label)
+            }
+            _ => None,
+        };
+        let opcode = match rest.split_first() {
+            Some(((AsmToken::Word, opcode), operands)) => {
+                rest = operands;
+                Some(*opcode)
+            }
+            Some(((_, span), _)) => return Err(asm_error("expected an opcode", *span, lexer)),
+            None => None,
+        };
+        statements.push(AsmStatement {
+            label,
+            opcode,
+            operands: rest.to_vec(),
+        });
+        current.clear();
+    }
+    Ok(statements)
+}
+
+fn match_operands(
+    opcode: Span,
+    operands: &[(AsmToken, Span)],
+    labels: &HashSet<&str>,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Vec<AsmOperand>, CompilerError> {
+    use AsmPattern::*;
+    let pattern: &[AsmPattern] = match lexer.span_str(opcode) {
+        "halt" | "nop" => &[],
+        "load" | "i2i" | "i2c" | "c2i" | "i2f" | "f2i" | "and" | "or" => &[Reg, FatArrow, Reg],
+        "loadI" => &[Num, FatArrow, Reg],
+        "loadAI" => &[Address, FatArrow, Reg],
+        "loadAO" => &[Reg, Comma, Reg, FatArrow, Reg],
+        "storeAI" => &[Reg, FatArrow, Address],
+        "storeAO" => &[Reg, FatArrow, Reg, Comma, Reg],
+        "jump" | "not" => &[ThinArrow, Reg],
+        "jumpI" => &[ThinArrow, Label],
+        "cbr" => &[Reg, ThinArrow, Label, Comma, Label],
+        "cmp_LT" | "cmp_LE" | "cmp_EQ" | "cmp_GE" | "cmp_GT" | "cmp_NE" => {
+            &[Reg, Comma, Reg, ThinArrow, Reg]
+        }
+        "add" | "sub" | "mult" | "div" => &[Reg, Comma, Reg, FatArrow, Reg],
+        "addI" | "subI" | "multI" | "divI" => &[Reg, Comma, Num, FatArrow, Reg],
+        _ => return Err(asm_error("unknown opcode", opcode, lexer)),
+    };
+
+    let mut matched = vec![];
+    let mut position = 0;
+    for expected in pattern {
+        let (token, span) = match operands.get(position) {
+            Some(token) => *token,
+            None => {
+                let span = match operands.last() {
+                    Some((_, last)) => *last,
+                    None => opcode,
+                };
+                return Err(asm_error("missing operand", span, lexer));
+            }
+        };
+        position += 1;
+        match (expected, token) {
+            (Reg, AsmToken::Word) => matched.push(AsmOperand::Reg(check_register(span, lexer)?)),
+            (Num, AsmToken::Number(number)) => matched.push(AsmOperand::Num(number)),
+            (Label, AsmToken::Word) => {
+                if !labels.contains(lexer.span_str(span)) {
+                    return Err(asm_error("undefined label", span, lexer));
+                }
+                matched.push(AsmOperand::Label(span));
+            }
+            (Address, AsmToken::Word) => match operands.get(position) {
+                Some((AsmToken::Comma, _)) => {
+                    matched.push(AsmOperand::Reg(check_register(span, lexer)?));
+                    match operands.get(position + 1) {
+                        Some((AsmToken::Number(number), _)) => {
+                            matched.push(AsmOperand::Num(*number))
+                        }
+                        Some((_, span)) => {
+                            return Err(asm_error("expected an offset", *span, lexer))
+                        }
+                        None => return Err(asm_error("missing operand", span, lexer)),
+                    }
+                    position += 2;
+                }
+                // A bare name stands for the base register and offset of a variable.
+                _ => matched.push(AsmOperand::Var(span)),
+            },
+            (Comma, AsmToken::Comma)
+            | (FatArrow, AsmToken::FatArrow)
+            | (ThinArrow, AsmToken::ThinArrow) => (),
+            (expected, _) => {
+                let message = format!("expected {}", expected.to_str());
+                return Err(asm_error(&message, span, lexer));
+            }
+        }
+    }
+    if let Some((_, span)) = operands.get(position) {
+        return Err(asm_error("unexpected operand", *span, lexer));
+    }
+
+    Ok(matched)
+}
+
+fn check_register(span: Span, lexer: &dyn NonStreamingLexer<u32>) -> Result<Span, CompilerError> {
+    let name = lexer.span_str(span);
+    match name {
+        "rfp" | "rsp" | "rbss" | "rpc" => Ok(span),
+        _ => match name.strip_prefix('r').map(|number| number.parse::<u32>()) {
+            Some(Ok(_)) => Ok(span),
+            _ => Err(asm_error("invalid register", span, lexer)),
+        },
+    }
+}
+
+fn build_operation(
+    opcode: Span,
+    operands: &[AsmOperand],
+    context: &mut AsmContext,
+    code: &mut IlocCode,
+    annotations: &Annotations,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Operation, CompilerError> {
+    // Operands are collected by kind, in the order they appear.
+    let mut regs = vec![];
+    let mut nums = vec![];
+    let mut labels = vec![];
+    for operand in operands {
+        match *operand {
+            AsmOperand::Reg(span) => regs.push(context.register(span, code, lexer)?),
+            AsmOperand::Num(number) => nums.push(number),
+            AsmOperand::Label(span) => labels.push(context.labels[lexer.span_str(span)]),
+            AsmOperand::Var(span) => {
+                let def_symbol = annotations.symbol(span)?;
+                regs.push(def_symbol.offset_source);
+                nums.push(def_symbol.offset as i32);
+            }
+        }
+    }
+
+    Ok(match lexer.span_str(opcode) {
+        "halt" => Operation::Halt,
+        "nop" => Operation::Nop,
+        "load" => Operation::Load(regs[0], regs[1]),
+        "i2i" => Operation::I2i(regs[0], regs[1]),
+        "i2c" => Operation::I2c(regs[0], regs[1]),
+        "c2i" => Operation::C2i(regs[0], regs[1]),
+        "i2f" => Operation::I2f(regs[0], regs[1]),
+        "f2i" => Operation::F2i(regs[0], regs[1]),
+        "and" => Operation::And(regs[0], regs[1]),
+        "or" => Operation::Or(regs[0], regs[1]),
+        "loadI" => Operation::LoadI(nums[0], regs[0]),
+        "loadAI" => Operation::LoadAI(regs[0], nums[0], regs[1]),
+        "loadAO" => Operation::LoadAO(regs[0], regs[1], regs[2]),
+        "storeAI" => Operation::StoreAI(regs[0], regs[1], nums[0]),
+        "storeAO" => Operation::StoreAO(regs[0], regs[1], regs[2]),
+        "jump" => Operation::Jump(regs[0]),
+        "not" => Operation::Not(regs[0]),
+        "jumpI" => Operation::JumpI(labels[0]),
+        "cbr" => Operation::Cbr(regs[0], labels[0], labels[1]),
+        "cmp_LT" => Operation::CmpLT(regs[0], regs[1], regs[2]),
+        "cmp_LE" => Operation::CmpLE(regs[0], regs[1], regs[2]),
+        "cmp_EQ" => Operation::CmpEQ(regs[0], regs[1], regs[2]),
+        "cmp_GE" => Operation::CmpGE(regs[0], regs[1], regs[2]),
+        "cmp_GT" => Operation::CmpGT(regs[0], regs[1], regs[2]),
+        "cmp_NE" => Operation::CmpNE(regs[0], regs[1], regs[2]),
+        "add" => Operation::Add(regs[0], regs[1], regs[2]),
+        "sub" => Operation::Sub(regs[0], regs[1], regs[2]),
+        "mult" => Operation::Mult(regs[0], regs[1], regs[2]),
+        "div" => Operation::Div(regs[0], regs[1], regs[2]),
+        "addI" => Operation::AddI(regs[0], nums[0], regs[1]),
+        "subI" => Operation::SubI(regs[0], nums[0], regs[1]),
+        "multI" => Operation::MultI(regs[0], nums[0], regs[1]),
+        "divI" => Operation::DivI(regs[0], nums[0], regs[1]),
+        _ => return Err(asm_error("unknown opcode", opcode, lexer)),
+    })
+}
+
+impl AsmContext {
+    // Numbered registers are renamed so the block never clobbers the compiler's temporaries.
+    fn register(
+        &mut self,
+        span: Span,
+        code: &mut IlocCode,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Register, CompilerError> {
+        let name = lexer.span_str(span);
+        Ok(match name {
+            "rfp" => Register::Rfp,
+            "rsp" => Register::Rsp,
+            "rbss" => Register::Rbss,
+            "rpc" => Register::Rpc,
+            _ => match name.strip_prefix('r').map(|number| number.parse::<u32>()) {
+                Some(Ok(number)) => *self
+                    .registers
+                    .entry(number)
+                    .or_insert_with(|| code.new_register()),
+                _ => return Err(asm_error("invalid register", span, lexer)),
+            },
+        })
+    }
+}
+
+impl AsmPattern {
+    fn to_str(self) -> &'static str {
+        match self {
+            AsmPattern::Reg => "a register",
+            AsmPattern::Num => "a number",
+            AsmPattern::Label => "a label",
+            AsmPattern::Address => "a variable or \"register, offset\"",
+            AsmPattern::Comma => "\",\"",
+            AsmPattern::FatArrow => "\"=>\"",
+            AsmPattern::ThinArrow => "\"->\"",
+        }
+    }
+}
+
+fn asm_error(message: &str, span: Span, lexer: &dyn NonStreamingLexer<u32>) -> CompilerError {
+    let ((line, col), (_, _)) = lexer.line_col(span);
+    let highlight = ScopeStack::form_string_highlight(span, lexer);
+    CompilerError::IlocErrorAsm {
+        message: message.to_string(),
+        line,
+        col,
+        highlight,
+    }
+}
@@ -4,15 +4,16 @@
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
 use std::collections::HashMap;
+use std::fmt;
 
 use super::error::CompilerError;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Label(u32);
 
-impl Label {
-    pub fn to_string(&self) -> String {
-        format!("L{}", &self.0)
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "L{}", self.0)
     }
 }
 
@@ -25,14 +26,14 @@ pub enum Register {
     R(u32),
 }
 
-impl Register {
-    pub fn to_string(&self) -> String {
-        match &self {
-            Register::Rfp => "rfp".to_string(),
-            Register::Rsp => "rsp".to_string(),
-            Register::Rbss => "rbss".to_string(),
-            Register::Rpc => "rpc".to_string(),
-            Register::R(num) => format!("r{}", num),
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Register::Rfp => write!(f, "rfp"),
+            Register::Rsp => write!(f, "rsp"),
+            Register::Rbss => write!(f, "rbss"),
+            Register::Rpc => write!(f, "rpc"),
+            Register::R(num) => write!(f, "r{}", num),
         }
     }
 }
@@ -58,6 +59,10 @@ pub enum Operation {
     Halt,
     Nop,
     I2i(Register, Register),
+    I2c(Register, Register),
+    C2i(Register, Register),
+    I2f(Register, Register),
+    F2i(Register, Register),
     Add(Register, Register, Register),
     AddI(Register, i32, Register),
     Sub(Register, Register, Register),
@@ -75,161 +80,173 @@ impl Operation {
     pub fn to_string(&self) -> Result<String, CompilerError> {
         Ok(match &self {
             Operation::Load(reg_a, reg_b) => {
-                format!("load {} => {}", reg_a.to_string(), reg_b.to_string())
+                format!("load {} => {}", reg_a, reg_b)
             }
             Operation::LoadI(addr, reg) => {
-                format!("loadI {} => {}", addr, reg.to_string())
+                format!("loadI {} => {}", addr, reg)
             }
             Operation::LoadAI(reg_a, num, reg_b) => {
                 format!(
                     "loadAI {}, {} => {}",
-                    reg_a.to_string(),
+                    reg_a,
                     num,
-                    reg_b.to_string(),
+                    reg_b,
                 )
             }
             Operation::LoadAO(reg_a, reg_b, reg_c) => {
                 format!(
                     "loadAO {}, {} => {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
             Operation::StoreAI(reg_a, reg_b, addr) => format!(
                 "storeAI {} => {}, {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
+                reg_a,
+                reg_b,
                 addr,
             ),
             Operation::StoreAO(reg_a, reg_b, reg_c) => {
                 format!(
                     "storeAO {} => {}, {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
-            Operation::Jump(reg) => format!("jump -> {}", reg.to_string()),
-            Operation::JumpI(label) => format!("jumpI -> {}", label.to_string()),
+            Operation::Jump(reg) => format!("jump -> {}", reg),
+            Operation::JumpI(label) => format!("jumpI -> {}", label),
             Operation::Cbr(reg, label_a, label_b) => format!(
                 "cbr {} -> {}, {}",
-                reg.to_string(),
-                label_a.to_string(),
-                label_b.to_string()
+                reg,
+                label_a,
+                label_b
             ),
             Operation::CmpLT(reg_a, reg_b, reg_c) => format!(
                 "cmp_LT {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
             Operation::CmpLE(reg_a, reg_b, reg_c) => format!(
                 "cmp_LE {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
             Operation::CmpEQ(reg_a, reg_b, reg_c) => format!(
                 "cmp_EQ {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
             Operation::CmpGE(reg_a, reg_b, reg_c) => format!(
                 "cmp_GE {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
             Operation::CmpGT(reg_a, reg_b, reg_c) => format!(
                 "cmp_GT {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
             Operation::CmpNE(reg_a, reg_b, reg_c) => format!(
                 "cmp_NE {}, {} -> {}",
-                reg_a.to_string(),
-                reg_b.to_string(),
-                reg_c.to_string(),
+                reg_a,
+                reg_b,
+                reg_c,
             ),
-            Operation::Halt => format!("halt"),
-            Operation::Nop => format!("nop"),
+            Operation::Halt => "halt".to_string(),
+            Operation::Nop => "nop".to_string(),
             Operation::I2i(reg_a, reg_b) => {
-                format!("i2i {} => {}", reg_a.to_string(), reg_b.to_string())
+                format!("i2i {} => {}", reg_a, reg_b)
+            }
+            Operation::I2c(reg_a, reg_b) => {
+                format!("i2c {} => {}", reg_a, reg_b)
+            }
+            Operation::C2i(reg_a, reg_b) => {
+                format!("c2i {} => {}", reg_a, reg_b)
+            }
+            Operation::I2f(reg_a, reg_b) => {
+                format!("i2f {} => {}", reg_a, reg_b)
+            }
+            Operation::F2i(reg_a, reg_b) => {
+                format!("f2i {} => {}", reg_a, reg_b)
             }
             Operation::Add(reg_a, reg_b, reg_c) => {
                 format!(
                     "add {}, {} => {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
             Operation::AddI(reg_a, num, reg_b) => {
                 format!(
                     "addI {}, {} => {}",
-                    reg_a.to_string(),
+                    reg_a,
                     num,
-                    reg_b.to_string(),
+                    reg_b,
                 )
             }
             Operation::Sub(reg_a, reg_b, reg_c) => {
                 format!(
                     "sub {}, {} => {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
             Operation::SubI(reg_a, num, reg_b) => {
                 format!(
                     "subI {}, {} => {}",
-                    reg_a.to_string(),
+                    reg_a,
                     num,
-                    reg_b.to_string(),
+                    reg_b,
                 )
             }
             Operation::Mult(reg_a, reg_b, reg_c) => {
                 format!(
                     "mult {}, {} => {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
             Operation::MultI(reg_a, num, reg_b) => {
                 format!(
                     "multI {}, {} => {}",
-                    reg_a.to_string(),
+                    reg_a,
                     num,
-                    reg_b.to_string(),
+                    reg_b,
                 )
             }
             Operation::Div(reg_a, reg_b, reg_c) => {
                 format!(
                     "div {}, {} => {}",
-                    reg_a.to_string(),
-                    reg_b.to_string(),
-                    reg_c.to_string(),
+                    reg_a,
+                    reg_b,
+                    reg_c,
                 )
             }
             Operation::DivI(reg_a, num, reg_b) => {
                 format!(
                     "divI {}, {} => {}",
-                    reg_a.to_string(),
+                    reg_a,
                     num,
-                    reg_b.to_string(),
+                    reg_b,
                 )
             }
             Operation::And(reg_a, reg_b) => {
-                format!("and {} => {}", reg_a.to_string(), reg_b.to_string())
+                format!("and {} => {}", reg_a, reg_b)
             }
             Operation::Or(reg_a, reg_b) => {
-                format!("or {} => {}", reg_a.to_string(), reg_b.to_string())
+                format!("or {} => {}", reg_a, reg_b)
             }
-            Operation::Not(reg) => format!("not -> {}", reg.to_string()),
+            Operation::Not(reg) => format!("not -> {}", reg),
         })
     }
 }
@@ -254,14 +271,43 @@ impl Instruction {
         match &self {
             Instruction::Unlabeled(operation) => operation.to_string(),
             Instruction::Labeled(label, operation) => {
-                Ok(format!("{}: {}", label.to_string(), operation.to_string()?))
+                Ok(format!("{}: {}", label, operation.to_string()?))
             }
         }
     }
 }
 
+// Checks compiled into the program with --checks, both off by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RuntimeChecks {
+    pub bounds: bool,
+    pub div: bool,
+}
+
+// A failed runtime check stores its code in a slot of its own, right below
+// the globals, and halts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trap {
+    IndexOutOfBounds,
+    DivisionByZero,
+}
+
+impl Trap {
+    pub fn code(&self) -> i32 {
+        match self {
+            Trap::IndexOutOfBounds => 1,
+            Trap::DivisionByZero => 2,
+        }
+    }
+}
+
+// The trap code goes in the word right below rbss.
+const TRAP_OFFSET: i32 = -4;
+
 const SIZE_PROMISE: Voucher = Voucher(0);
 const MAIN_PROMISE: Voucher = Voucher(1);
+const STATIC_PROMISE: Voucher = Voucher(2);
+const HALT_ADDRESS_PROMISE: Voucher = Voucher(3);
 
 pub struct IlocCode {
     code_lines: Vec<CodeLine>,
@@ -270,6 +316,9 @@ pub struct IlocCode {
     register_counter: u32,
     promise_counter: u32,
     payment_map: HashMap<Voucher, Vec<Instruction>>,
+    static_init: Vec<Instruction>,
+    checks: RuntimeChecks,
+    traps: Vec<(Trap, Label)>,
 }
 
 impl IlocCode {
@@ -277,7 +326,7 @@ impl IlocCode {
         let starting_register = Register::R(0);
         let register_counter = 1;
         let label_counter = 0;
-        let promise_counter = 2;
+        let promise_counter = 4;
 
         let code_lines = vec![
             CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
@@ -289,10 +338,7 @@ impl IlocCode {
                 Register::Rsp,
             ))),
             CodeLine::Promise(SIZE_PROMISE),
-            CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
-                8,
-                starting_register,
-            ))),
+            CodeLine::Promise(HALT_ADDRESS_PROMISE),
             CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
                 starting_register,
                 Register::Rsp,
@@ -308,12 +354,14 @@ impl IlocCode {
                 Register::Rsp,
                 8,
             ))),
+            CodeLine::Promise(STATIC_PROMISE),
             CodeLine::Promise(MAIN_PROMISE),
             CodeLine::Deliver(Instruction::Unlabeled(Operation::Halt)),
         ];
 
         let label_map = HashMap::new();
         let payment_map = HashMap::new();
+        let static_init = vec![];
 
         IlocCode {
             code_lines,
@@ -322,9 +370,60 @@ impl IlocCode {
             register_counter,
             promise_counter,
             payment_map,
+            static_init,
+            checks: RuntimeChecks::default(),
+            traps: vec![],
         }
     }
 
+    pub fn set_checks(&mut self, checks: RuntimeChecks) {
+        self.checks = checks;
+    }
+
+    pub fn checks(&self) -> RuntimeChecks {
+        self.checks
+    }
+
+    // Jumps to the routine for trap when condition holds, each routine is
+    // only emitted once at the end of the program.
+    pub fn trap_if(&mut self, condition: Register, trap: Trap) {
+        let trap_label = match self.traps.iter().find(|(other, _label)| *other == trap) {
+            Some((_trap, label)) => *label,
+            None => {
+                let label = self.new_label();
+                self.traps.push((trap, label));
+                label
+            }
+        };
+        let continue_label = self.new_label();
+        self.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Cbr(
+            condition,
+            trap_label,
+            continue_label,
+        ))));
+        self.push_code(CodeLine::Deliver(Instruction::Labeled(
+            continue_label,
+            Operation::Nop,
+        )));
+    }
+
+    fn trap_routines(&mut self) -> Vec<CodeLine> {
+        let mut routines = vec![];
+        for (trap, label) in self.traps.clone() {
+            let code_register = self.new_register();
+            routines.extend(vec![
+                Instruction::Labeled(label, Operation::LoadI(trap.code(), code_register)),
+                Instruction::Unlabeled(Operation::StoreAI(
+                    code_register,
+                    Register::Rbss,
+                    TRAP_OFFSET,
+                )),
+                Instruction::Unlabeled(Operation::Halt),
+            ]);
+        }
+        routines.into_iter().map(CodeLine::Deliver).collect()
+    }
+
     pub fn generate_promise(&mut self) -> Voucher {
         let promise_number = self.promise_counter;
         self.promise_counter += 1;
@@ -336,20 +435,34 @@ impl IlocCode {
     }
 
     pub fn collect_promises(&mut self) -> Result<(), CompilerError> {
+        let trap_routines = self.trap_routines();
         let mut new_code = vec![];
         for code_line in &self.code_lines {
             match code_line {
                 size_promise @ CodeLine::Promise(SIZE_PROMISE) => {
                     new_code.push(size_promise.clone())
                 } // leave this promise for last.
-                CodeLine::Promise(MAIN_PROMISE) => match self.label_map.get(&"main".to_string()) {
+                CodeLine::Promise(HALT_ADDRESS_PROMISE) => {
+                    // main() returns to the halt right after the static initializers.
+                    let halt_address = 8 + self.static_init.len() as i32;
+                    new_code.push(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                        halt_address,
+                        Register::R(0),
+                    ))))
+                }
+                CodeLine::Promise(STATIC_PROMISE) => {
+                    for instruction in &self.static_init {
+                        new_code.push(CodeLine::Deliver(instruction.clone()))
+                    }
+                }
+                CodeLine::Promise(MAIN_PROMISE) => match self.label_map.get("main()") {
                     Some(promised_label) => new_code.push(CodeLine::Deliver(
                         Instruction::Unlabeled(Operation::JumpI(*promised_label)),
                     )),
                     None => {
-                        return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                            "No main() function found."
-                        )))
+                        return Err(CompilerError::IlocErrorUndefinedBehavior(
+                            "No main() function found.".to_string(),
+                        ))
                     }
                 },
                 CodeLine::Promise(voucher) => {
@@ -366,20 +479,21 @@ impl IlocCode {
                 good @ CodeLine::Deliver(_) => new_code.push(good.clone()),
             }
         }
+        // With traps, the data area starts one word later to make room for
+        // the trap code.
+        let trap_slot = if trap_routines.is_empty() { 0 } else { 4 };
+        new_code.extend(trap_routines);
         let code_len = new_code.len() as i32;
         let size_promise_position = new_code
             .iter()
-            .position(|x| match x {
-                CodeLine::Promise(SIZE_PROMISE) => true,
-                _ => false,
-            })
+            .position(|x| matches!(x, CodeLine::Promise(SIZE_PROMISE)))
             .ok_or(CompilerError::SanityError(
                 "failed to find SIZE_PROMISE position".to_string(),
             ))?;
         new_code.splice(
             size_promise_position..size_promise_position + 1,
             [CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
-                code_len,
+                code_len + trap_slot,
                 Register::Rbss,
             )))],
         );
@@ -411,17 +525,23 @@ impl IlocCode {
         Register::R(new_register_value)
     }
 
-    pub fn add_fn_label(&mut self, fn_name: String) -> Label {
+    // A call can come before the definition it jumps to, so whichever of
+    // them comes first creates the label.
+    pub fn fn_label(&mut self, fn_name: String) -> Label {
+        if let Some(label) = self.label_map.get(&fn_name) {
+            return *label;
+        }
         let new_label = self.new_label();
         self.label_map.insert(fn_name, new_label);
         new_label
     }
 
-    pub fn get_fn_label(&self, fn_name: &String) -> Option<&Label> {
-        self.label_map.get(fn_name)
-    }
-
     pub fn push_code(&mut self, code: CodeLine) {
         self.code_lines.push(code);
     }
+
+    // Runs once before main(), used to initialize static locals.
+    pub fn push_static_init(&mut self, instruction: Instruction) {
+        self.static_init.push(instruction);
+    }
 }
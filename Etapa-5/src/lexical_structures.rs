@@ -4,14 +4,16 @@
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
 use lrpar::{NonStreamingLexer, Span};
-use std::ffi::c_void;
-use std::ptr::addr_of;
-
-use super::ast_node::AstNode;
-use super::error::CompilerError;
-use super::instructions::{CodeLine, IlocCode, Instruction, Operation, Register};
-use super::semantic_structures::{ BoolValue,
-    CallSymbol, DefSymbol, IntValue, ScopeStack, SymbolClass, SymbolType, INT_SIZE,
+use std::convert::TryFrom;
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::ast_node::{AstNode, NodeKind};
+use super::error::{CompilerError, CompilerWarning};
+use super::inline_asm;
+use super::instructions::{CodeLine, IlocCode, Instruction, Operation, Register, Trap};
+use super::semantic_structures::{
+    signature, Annotations, BoolValue, CallSymbol, CharValue, DefSymbol, FloatValue, FlowState,
+    IntValue, ScopeStack, SymbolClass, SymbolType, INT_SIZE,
 };
 
 #[derive(Debug)]
@@ -19,45 +21,30 @@ pub struct GlobalVarDef {
     is_static: bool,
     var_type: Span,
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl GlobalVarDef {
-    pub fn new(
-        is_static: bool,
-        var_type: Span,
-        node_id: Span,
-        next: Option<Box<dyn AstNode>>,
-    ) -> GlobalVarDef {
+    pub fn new(is_static: bool, var_type: Span, node_id: Span) -> GlobalVarDef {
         GlobalVarDef {
             is_static,
             var_type,
             node_id,
-            next,
         }
     }
-}
 
-impl AstNode for GlobalVarDef {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_ripple(next_node.as_ref(), own_address, ripple)
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        false
+    pub fn is_static(&self) -> bool {
+        self.is_static
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn var_type(&self) -> Span {
+        self.var_type
     }
-    fn evaluate_node(
+}
+
+impl AstNode for GlobalVarDef {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
@@ -65,23 +52,16 @@ impl AstNode for GlobalVarDef {
         stack.check_duplicate(span, lexer)?;
 
         let id = lexer.span_str(self.node_id).to_string();
-        let var_type = SymbolType::from_str(lexer.span_str(self.var_type))?;
+        let var_type = stack.resolve_type(self.var_type, lexer)?;
         let offset = stack.get_offset()?;
         let var_type = match var_type {
             SymbolType::Int(IntValue::Undefined) => {
                 SymbolType::Int(IntValue::Memory(Register::Rbss, offset))
             }
-            _ => {
-                return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                    "Global variable of unsuported type declared: {} ({})",
-                    id,
-                    lexer.span_str(self.var_type)
-                )))
-            }
+            other => other,
         };
         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
-        let is_global = true;
-        let class = SymbolClass::Var { is_global, offset };
+        let class = SymbolClass::Var;
         let size = var_type.get_symbol_type_size();
 
         let our_symbol = DefSymbol::new(
@@ -99,17 +79,29 @@ impl AstNode for GlobalVarDef {
         stack.add_def_symbol(our_symbol)?;
         stack.add_offset(size)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        match annotations.symbol(self.node_id)?.type_value {
+            SymbolType::Int(_) => Ok(None),
+            _ => Err(CompilerError::IlocErrorUndefinedBehavior(format!(
+                "Global variable of unsuported type declared: {} ({})",
+                lexer.span_str(self.node_id),
+                lexer.span_str(self.var_type)
+            ))),
+        }
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::GlobalVarDef
     }
 }
 
@@ -118,8 +110,7 @@ pub struct GlobalVecDef {
     is_static: bool,
     var_type: Span,
     node_id: Span,
-    vec_size: Box<LiteralInt>,
-    next: Option<Box<dyn AstNode>>,
+    vec_size: NodeId<LiteralInt>,
 }
 
 impl GlobalVecDef {
@@ -127,39 +118,33 @@ impl GlobalVecDef {
         is_static: bool,
         var_type: Span,
         node_id: Span,
-        vec_size: Box<LiteralInt>,
-        next: Option<Box<dyn AstNode>>,
+        vec_size: NodeId<LiteralInt>,
     ) -> GlobalVecDef {
         GlobalVecDef {
             is_static,
             var_type,
             node_id,
             vec_size,
-            next,
         }
     }
-}
 
-impl AstNode for GlobalVecDef {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_ripple(next_node.as_ref(), own_address, ripple)
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    pub fn vec_size(&self) -> NodeId<LiteralInt> {
+        self.vec_size
     }
-    fn is_tree_member(&self) -> bool {
-        false
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn var_type(&self) -> Span {
+        self.var_type
     }
-    fn evaluate_node(
+}
+
+impl AstNode for GlobalVecDef {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
@@ -168,14 +153,14 @@ impl AstNode for GlobalVecDef {
         let id = lexer.span_str(self.node_id).to_string();
         let offset = stack.get_offset()?;
 
-        let var_type = match SymbolType::from_str(lexer.span_str(self.var_type))? {
+        let var_type = match stack.resolve_type(self.var_type, lexer)? {
             SymbolType::String(_) => {
                 let start = self.var_type.start();
-                let end = self.vec_size.get_span().end() + 1;
+                let end = arena.node(self.vec_size).get_span().end() + 1;
                 if end < start {
                     return Err(CompilerError::SanityError(format!(
-                        "evaluate_node() found unlawful spans on GlobalVecDef for \"{}\"",
-                        id.to_string(),
+                        "check_node() found unlawful spans on GlobalVecDef for \"{}\"",
+                        id,
                     )));
                 };
                 let span = Span::new(start, end);
@@ -191,24 +176,18 @@ impl AstNode for GlobalVecDef {
             SymbolType::Int(IntValue::Undefined) => {
                 SymbolType::Int(IntValue::Memory(Register::Rbss, offset))
             }
-            _ => {
-                return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                    "Global variable of unsuported type declared: {} ({})",
-                    id,
-                    lexer.span_str(self.var_type)
-                )))
-            }
+            other => other,
         };
         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
-        let class = SymbolClass::Vec { offset };
+        let class = SymbolClass::Vec;
 
-        let vec_size = self.vec_size.evaluate_node(code, stack, lexer)?;
+        let vec_size = arena.check(self.vec_size, stack, lexer)?;
 
         let size_int = match vec_size {
             Some(SymbolType::Int(IntValue::Literal(size_int))) => size_int as u32,
             _ => {
                 return Err(CompilerError::SanityError(format!(
-                    "vec_size symbol invalid (on GlobalVecDef.evaluate_node()): {:?}",
+                    "vec_size symbol invalid (on GlobalVecDef.check_node()): {:?}",
                     vec_size
                 )))
             }
@@ -247,17 +226,145 @@ impl AstNode for GlobalVecDef {
         stack.add_def_symbol(our_symbol)?;
         stack.add_offset(size)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        match annotations.symbol(self.node_id)?.type_value {
+            SymbolType::Int(_) => Ok(None),
+            _ => Err(CompilerError::IlocErrorUndefinedBehavior(format!(
+                "Global variable of unsuported type declared: {} ({})",
+                lexer.span_str(self.node_id),
+                lexer.span_str(self.var_type)
+            ))),
+        }
+    }
+    fn get_span(&self) -> Span {
+        self.node_id
+    }
+    fn kind(&self) -> NodeKind {
+        NodeKind::GlobalVecDef
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Enumerator {
+    pub node_id: Span,
+    pub value: Option<Span>,
+    pub is_negative: bool,
+}
+
+#[derive(Debug)]
+pub struct EnumDef {
+    node_id: Span,
+    enumerators: Vec<Enumerator>,
+}
+
+impl EnumDef {
+    pub fn new(node_id: Span, enumerators: Vec<Enumerator>) -> EnumDef {
+        EnumDef {
+            node_id,
+            enumerators,
+        }
+    }
+
+    pub fn enumerators(&self) -> &[Enumerator] {
+        &self.enumerators
+    }
+}
+
+impl AstNode for EnumDef {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        stack.check_duplicate(span, lexer)?;
+
+        let id = lexer.span_str(self.node_id).to_string();
+        let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+
+        let our_symbol = DefSymbol::new(
+            id,
+            span,
+            line,
+            col,
+            SymbolType::Int(IntValue::Undefined),
+            SymbolClass::Enum,
+            None,
+            Register::Rbss,
+            0,
+        );
+        stack.add_def_symbol(our_symbol)?;
+
+        // Like C, enumerators without a value follow the previous one, starting at zero.
+        let mut next_value: i64 = 0;
+        for enumerator in &self.enumerators {
+            let span = enumerator.node_id;
+            stack.check_duplicate(span, lexer)?;
+
+            // Anything past i64 doesn't parse, and is just as far out of range.
+            if let Some(value_span) = enumerator.value {
+                let text = lexer.span_str(value_span);
+                next_value = match text.parse::<i64>() {
+                    Ok(value) if enumerator.is_negative => -value,
+                    Ok(value) => value,
+                    Err(_) => i64::MAX,
+                };
+            }
+            let type_value = match i32::try_from(next_value) {
+                Ok(value) => SymbolType::Int(IntValue::Literal(value)),
+                Err(_) => {
+                    let ((line, col), (_, _)) = lexer.line_col(span);
+                    stack.report_error(CompilerError::SemanticErrorOverflow {
+                        line,
+                        col,
+                        highlight: ScopeStack::form_string_highlight(span, lexer),
+                    })?;
+                    SymbolType::Error
+                }
+            };
+            next_value = next_value.saturating_add(1);
+
+            let id = lexer.span_str(span).to_string();
+            let ((line, col), (_, _)) = lexer.line_col(span);
+            let enumerator_symbol = DefSymbol::new(
+                id,
+                span,
+                line,
+                col,
+                type_value,
+                SymbolClass::Enumerator,
+                None,
+                Register::Rbss,
+                0,
+            );
+            stack.add_def_symbol(enumerator_symbol)?;
+        }
 
         Ok(None)
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        _lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(None)
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::EnumDef
     }
 }
 
@@ -267,11 +374,10 @@ pub struct FnDef {
     return_type: Span,
     node_id: Span,
     params: Vec<Parameter>,
-    first_command: Option<Box<dyn AstNode>>,
-    next: Option<Box<dyn AstNode>>,
+    body: NodeId<CommandBlock>,
 }
 
-const FN_OFFSET: u32 = 16;
+pub const FN_OFFSET: u32 = 16;
 
 impl FnDef {
     pub fn new(
@@ -279,84 +385,112 @@ impl FnDef {
         return_type: Span,
         node_id: Span,
         params: Vec<Parameter>,
-        first_command: Option<Box<dyn AstNode>>,
-        next: Option<Box<dyn AstNode>>,
+        body: NodeId<CommandBlock>,
     ) -> FnDef {
         FnDef {
             is_static,
             return_type,
             node_id,
             params,
-            first_command,
-            next,
+            body,
         }
     }
-}
 
-impl AstNode for FnDef {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(command) = &self.first_command {
-            print_dependencies_own(command.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(command) = &self.first_command {
-            print_dependencies_child(command.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
+    pub fn body(&self) -> NodeId<CommandBlock> {
+        self.body
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(command) = &self.first_command {
-            print_labels_child(command.as_ref(), lexer)
-        }
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
     }
-    fn is_tree_member(&self) -> bool {
-        true
+
+    pub fn return_type(&self) -> Span {
+        self.return_type
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn params(&self) -> &[Parameter] {
+        &self.params
     }
-    fn evaluate_node(
+
+    // Runs for every function before any body is checked, so calls can come
+    // before the definition they resolve to.
+    pub fn declare(
         &self,
-        code: &mut IlocCode,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
+    ) -> Result<(), CompilerError> {
         let span = self.node_id;
-        stack.check_duplicate(span, lexer)?;
+        stack.check_fn_duplicate(span, &self.params, lexer)?;
         let offset = stack.get_offset()?;
 
-        let return_type = SymbolType::from_str(lexer.span_str(self.return_type))?;
+        let return_type = stack.resolve_type(self.return_type, lexer)?;
         let id = lexer.span_str(self.node_id).to_string();
         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
         let class = SymbolClass::Fn(self.params.clone());
         let our_symbol = DefSymbol::new(
-            id.clone(),
+            id,
             span,
             line,
             col,
-            return_type.clone(),
+            return_type,
             class,
             None,
             Register::Rbss,
             offset,
         );
+        stack.add_fn_symbol(our_symbol, lexer)
+    }
+}
 
-        stack.add_scope(Some(return_type));
+impl AstNode for FnDef {
+    fn check_node(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        // When declare() failed its error is already reported.
+        let our_symbol = match stack.declaration(self.node_id) {
+            Some(symbol) => symbol,
+            None => return Ok(None),
+        };
+        stack.set_current_fn(&our_symbol);
+        stack.add_scope(Some(our_symbol.type_value));
         stack.add_offset(FN_OFFSET)?;
+        // Reported here, so the function scope is still removed afterwards.
+        for param in self.params.iter() {
+            if let Err(error) = param.check_param(stack, lexer) {
+                stack.report_error(error)?;
+            }
+        }
+
+        // The function scope already holds the parameters, so the body shares it.
+        stack.restore_flow(FlowState::default());
+        arena.get(self.body).check_commands(arena, stack, lexer)?;
+        stack.check_returns(self.node_id, arena.node(self.body).get_span(), lexer);
+
+        let scope = stack.remove_scope()?;
+        stack.warn_unused(&scope, &self.params, lexer);
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let id = lexer.span_str(self.node_id).to_string();
         let mut starting_size = FN_OFFSET;
+        let mut param_types = vec![];
         for param in self.params.iter() {
-            starting_size += param.evaluate_param(code, stack, lexer)?;
+            let param_type = annotations.symbol(param.node_id)?.type_value.clone();
+            starting_size += param_type.get_symbol_type_size();
+            param_types.push(param_type);
         }
 
-        let new_label = code.add_fn_label(id);
+        let new_label = code.fn_label(signature(&id, &param_types));
         code.push_code(CodeLine::Deliver(Instruction::Labeled(new_label, Operation::Nop)));
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::I2i(
             Register::Rsp,
@@ -368,13 +502,9 @@ impl AstNode for FnDef {
             Register::Rsp,
         ))));
 
-        if let Some(command) = &self.first_command {
-            command.evaluate_node(code, stack, lexer)?;
-        };
-
-        stack.remove_scope()?;
-
-        stack.add_def_symbol(our_symbol)?;
+        arena
+            .get(self.body)
+            .generate_commands(arena, code, annotations, lexer)?;
 
         let return_addr_reg = code.new_register();
         let restore_rsp_reg = code.new_register();
@@ -404,17 +534,13 @@ impl AstNode for FnDef {
         ))));
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Jump(return_addr_reg))));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::FnDef
     }
 }
 
@@ -426,17 +552,16 @@ pub struct Parameter {
 }
 
 impl Parameter {
-    fn evaluate_param(
+    fn check_param(
         &self,
-        _code: &mut IlocCode,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<u32, CompilerError> {
+    ) -> Result<(), CompilerError> {
         let span = self.node_id;
 
         stack.check_duplicate(span, lexer)?;
 
-        let var_type = SymbolType::from_str(lexer.span_str(self.param_type))?;
+        let var_type = stack.resolve_type(self.param_type, lexer)?;
 
         if let SymbolType::String(_) = var_type {
             let ((line, col), (_, _)) = lexer.line_col(span);
@@ -451,11 +576,10 @@ impl Parameter {
 
         let id = lexer.span_str(self.node_id).to_string();
         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
-        let is_global = false;
         let offset = stack.get_offset()?;
-        let class = SymbolClass::Var { is_global, offset };
+        let class = SymbolClass::Var;
         let size = var_type.get_symbol_type_size();
-        let our_symbol = DefSymbol::new(
+        let mut our_symbol = DefSymbol::new(
             id,
             span,
             line,
@@ -466,18 +590,21 @@ impl Parameter {
             Register::Rfp,
             offset,
         );
+        our_symbol.is_const = self.is_const;
 
+        stack.warn_shadowing(&our_symbol, lexer);
         stack.add_def_symbol(our_symbol)?;
         stack.add_offset(size)?;
 
-        Ok(size)
+        Ok(())
     }
 
     fn get_symbol_type(
         &self,
+        stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<SymbolType, CompilerError> {
-        SymbolType::from_str(lexer.span_str(self.param_type))
+        stack.resolve_type(self.param_type, lexer)
     }
 }
 
@@ -488,7 +615,6 @@ pub struct LocalVarDef {
     var_type: Span,
     node_id: Span,
     is_tree_node: bool,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LocalVarDef {
@@ -498,7 +624,6 @@ impl LocalVarDef {
         var_type: Span,
         node_id: Span,
         is_tree_node: bool,
-        next: Option<Box<dyn AstNode>>,
     ) -> LocalVarDef {
         LocalVarDef {
             is_static,
@@ -506,36 +631,30 @@ impl LocalVarDef {
             var_type,
             node_id,
             is_tree_node,
-            next,
         }
     }
-}
 
-impl AstNode for LocalVarDef {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool) {
-        if !self.is_tree_node {
-            if let Some(next_node) = &self.next {
-                print_dependencies_ripple(next_node.as_ref(), own_address, ripple)
-            }
-        }
+    pub fn is_tree_node(&self) -> bool {
+        self.is_tree_node
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        if self.is_tree_node {
-            print_label_self(self.node_id, lexer, own_address);
-        };
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
     }
-    fn is_tree_member(&self) -> bool {
-        self.is_tree_node
+
+    pub fn is_const(&self) -> bool {
+        self.is_const
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn var_type(&self) -> Span {
+        self.var_type
     }
-    fn evaluate_node(
+}
+
+impl AstNode for LocalVarDef {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
@@ -543,26 +662,41 @@ impl AstNode for LocalVarDef {
         stack.check_duplicate(span, lexer)?;
 
         let id = lexer.span_str(self.node_id).to_string();
-        let var_type = SymbolType::from_str(lexer.span_str(self.var_type))?;
-        let offset = stack.get_offset()?;
+        let var_type = stack.resolve_type(self.var_type, lexer)?;
+        let (offset_source, offset) = if self.is_static {
+            (Register::Rbss, stack.get_global_offset()?)
+        } else {
+            (Register::Rfp, stack.get_offset()?)
+        };
         let var_type = match var_type {
             SymbolType::Int(IntValue::Undefined) => {
-                SymbolType::Int(IntValue::Memory(Register::Rfp, offset))
-            }
-            _ => {
-                return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                    "Local variable of unsuported type declared: {} ({})",
-                    id,
-                    lexer.span_str(self.var_type)
-                )))
+                SymbolType::Int(IntValue::Memory(offset_source, offset))
             }
+            other => other,
         };
         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
-        let is_global = false;
-        let class = SymbolClass::Var { is_global, offset };
+        let class = SymbolClass::Var;
         let size = var_type.get_symbol_type_size();
 
-        let our_symbol = DefSymbol::new(
+        if self.is_static {
+            let static_symbol = DefSymbol::new(
+                stack.mangle_static(&id)?,
+                span,
+                line,
+                col,
+                var_type.clone(),
+                class.clone(),
+                Some(size),
+                offset_source,
+                offset,
+            );
+            stack.add_global_def_symbol(static_symbol)?;
+            stack.add_global_offset(size)?;
+        } else {
+            stack.add_offset(size)?;
+        }
+
+        let mut our_symbol = DefSymbol::new(
             id,
             span,
             line,
@@ -570,227 +704,315 @@ impl AstNode for LocalVarDef {
             var_type,
             class,
             Some(size),
-            Register::Rfp,
+            offset_source,
             offset,
         );
+        our_symbol.is_const = self.is_const;
 
+        if !self.is_static {
+            stack.mark_unassigned(&our_symbol);
+        }
+        stack.warn_shadowing(&our_symbol, lexer);
         stack.add_def_symbol(our_symbol)?;
-        stack.add_offset(size)?;
 
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::AddI(
-            Register::Rsp,
-            size as i32,
-            Register::Rsp,
-        ))));
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let symbol = annotations.symbol(self.node_id)?;
+        match symbol.type_value {
+            SymbolType::Int(_) => (),
+            _ => {
+                return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
+                    "Local variable of unsuported type declared: {} ({})",
+                    lexer.span_str(self.node_id),
+                    lexer.span_str(self.var_type)
+                )))
+            }
+        }
+        let size = symbol.type_value.get_symbol_type_size();
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        if self.is_static {
+            // Memory starts out undefined, so uninitialized statics start at zero like in C.
+            if !self.is_tree_node {
+                let new_register = code.new_register();
+                code.push_static_init(Instruction::Unlabeled(Operation::LoadI(0, new_register)));
+                code.push_static_init(Instruction::Unlabeled(Operation::StoreAI(
+                    new_register,
+                    symbol.offset_source,
+                    symbol.offset as i32,
+                )));
+            }
+        } else {
+            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::AddI(
+                Register::Rsp,
+                size as i32,
+                Register::Rsp,
+            ))));
+        }
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::LocalVarDef
     }
 }
 
 #[derive(Debug)]
 pub struct VarDefInitId {
     node_id: Span,
-    var_def: Box<dyn AstNode>,
-    var_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    var_def: NodeId<LocalVarDef>,
+    var_value: NodeId,
 }
 
 impl VarDefInitId {
-    pub fn new(
-        node_id: Span,
-        var_def: Box<dyn AstNode>,
-        var_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VarDefInitId {
+    pub fn new(node_id: Span, var_def: NodeId<LocalVarDef>, var_value: NodeId) -> VarDefInitId {
         VarDefInitId {
             node_id,
             var_def,
             var_value,
-            next,
         }
     }
-}
 
-impl AstNode for VarDefInitId {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_def.as_ref(), own_address);
-        print_dependencies_own(self.var_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_def.as_ref(), own_address);
-        print_dependencies_child(self.var_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_def.as_ref(), lexer);
-        print_labels_child(self.var_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    pub fn var_def(&self) -> NodeId<LocalVarDef> {
+        self.var_def
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn var_value(&self) -> NodeId {
+        self.var_value
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VarDefInitId {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_def.evaluate_node(code, stack, lexer)?;
-        self.var_value.evaluate_node(code, stack, lexer)?;
+        arena.check(self.var_def, stack, lexer)?;
+        arena.check(self.var_value, stack, lexer)?;
 
-        let def_symbol =
-            stack.get_previous_def(self.var_def.get_span(), lexer, SymbolClass::default_var())?;
-        let var_symbol =
-            stack.get_previous_def(self.var_value.get_span(), lexer, SymbolClass::default_var())?;
+        let def_symbol = stack.get_previous_def(
+            arena.node(self.var_def).get_span(),
+            lexer,
+            SymbolClass::default_var(),
+        )?;
+        stack.mark_assigned(&def_symbol);
+        let var_symbol = stack.get_value_def(arena.node(self.var_value).get_span(), lexer)?;
 
         let id_symbol_type = &var_symbol.type_value;
-        let _updated_symbol = def_symbol.cast_or_scream(id_symbol_type, self.node_id, lexer, false)?;
-
-        if let SymbolType::Int(IntValue::Memory(offset_source, offset)) = id_symbol_type {
-            let new_register = code.new_register();
-            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
-                *offset_source,
-                *offset as i32,
-                new_register,
-            ))));
-            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-                new_register,
-                def_symbol.offset_source,
-                def_symbol.offset as i32,
-            ))));
-        };
-
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        let updated_symbol =
+            def_symbol.cast_or_scream(id_symbol_type, self.node_id, lexer, false)?;
+        stack.set_size(&def_symbol, updated_symbol.size);
+        stack.warn_lossy(
+            arena,
+            self.var_value,
+            id_symbol_type,
+            &def_symbol.type_value,
+            false,
+            lexer,
+        );
 
-        Ok(None)
-    }
+        // Static initializers run before main, when only enumerators hold a value.
+        if let SymbolClass::Var = var_symbol.class {
+            if arena.get(self.var_def).is_static {
+                let span = arena.node(self.var_value).get_span();
+                let ((line, col), (_, _)) = lexer.line_col(span);
+                let highlight = ScopeStack::form_string_highlight(span, lexer);
+                return Err(CompilerError::SemanticErrorStaticInit {
+                    id: lexer.span_str(span).to_string(),
+                    line,
+                    col,
+                    highlight,
+                });
+            }
+        }
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_def, code, annotations, lexer)?;
+        arena.generate(self.var_value, code, annotations, lexer)?;
+
+        let def_symbol = annotations.symbol(arena.node(self.var_def).get_span())?;
+        let var_symbol = annotations.symbol(arena.node(self.var_value).get_span())?;
+
+        match &var_symbol.type_value {
+            SymbolType::Int(IntValue::Literal(number)) if arena.get(self.var_def).is_static => {
+                let new_register = code.new_register();
+                code.push_static_init(Instruction::Unlabeled(Operation::LoadI(
+                    *number,
+                    new_register,
+                )));
+                code.push_static_init(Instruction::Unlabeled(Operation::StoreAI(
+                    new_register,
+                    def_symbol.offset_source,
+                    def_symbol.offset as i32,
+                )));
+            }
+            SymbolType::Int(IntValue::Memory(offset_source, offset)) => {
+                let new_register = code.new_register();
+                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
+                    *offset_source,
+                    *offset as i32,
+                    new_register,
+                ))));
+                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+                    new_register,
+                    def_symbol.offset_source,
+                    def_symbol.offset as i32,
+                ))));
+            }
+            SymbolType::Int(IntValue::Literal(number)) => {
+                let new_register = code.new_register();
+                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                    *number,
+                    new_register,
+                ))));
+                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+                    new_register,
+                    def_symbol.offset_source,
+                    def_symbol.offset as i32,
+                ))));
+            }
+            _ => (),
+        };
+
+        Ok(None)
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarDefInitId
     }
 }
 
 #[derive(Debug)]
 pub struct VarDefInitLit {
     node_id: Span,
-    var_def: Box<LocalVarDef>,
-    var_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    var_def: NodeId<LocalVarDef>,
+    var_value: NodeId,
 }
 
 impl VarDefInitLit {
-    pub fn new(
-        node_id: Span,
-        var_def: Box<LocalVarDef>,
-        var_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VarDefInitLit {
+    pub fn new(node_id: Span, var_def: NodeId<LocalVarDef>, var_value: NodeId) -> VarDefInitLit {
         VarDefInitLit {
             node_id,
             var_def,
             var_value,
-            next,
         }
     }
+
+    pub fn var_def(&self) -> NodeId<LocalVarDef> {
+        self.var_def
+    }
+
+    pub fn var_value(&self) -> NodeId {
+        self.var_value
+    }
 }
 
 impl AstNode for VarDefInitLit {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_def.as_ref(), own_address);
-        print_dependencies_own(self.var_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_def.as_ref(), own_address);
-        print_dependencies_child(self.var_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_def.as_ref(), lexer);
-        print_labels_child(self.var_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
+    fn check_node(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.check(self.var_def, stack, lexer)?;
+
+        let lit_symbol_type = match arena.check(self.var_value, stack, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SanityError(
+                    "VarDefInitLit found no SymbolType (on self.var_value.check_node())"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let span = arena.node(self.var_def).get_span();
+        let def_symbol = stack.get_previous_def(span, lexer, SymbolClass::default_var())?;
+        stack.mark_assigned(&def_symbol);
+
+        let updated_symbol =
+            def_symbol.cast_or_scream(&lit_symbol_type, self.node_id, lexer, false)?;
+        stack.set_size(&def_symbol, updated_symbol.size);
+        stack.warn_lossy(
+            arena,
+            self.var_value,
+            &lit_symbol_type,
+            &def_symbol.type_value,
+            false,
+            lexer,
+        );
+
+        if let (true, SymbolType::Int(_), SymbolType::Int(IntValue::Literal(value))) =
+            (def_symbol.is_const, &def_symbol.type_value, lit_symbol_type)
+        {
+            stack.fold_constant(&def_symbol, value);
         }
+
+        Ok(None)
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn generate_node(
         &self,
+        arena: &AstArena,
         code: &mut IlocCode,
-        stack: &mut ScopeStack,
+        annotations: &Annotations,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_def.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.var_def, code, annotations, lexer)?;
 
-        let lit_symbol_type = match self.var_value.evaluate_node(code, stack, lexer)? {
+        let lit_symbol_type = match arena.generate(self.var_value, code, annotations, lexer)? {
             Some(good @ SymbolType::Int(IntValue::Literal(_))) => good,
             Some(bad) => {
                 return Err(CompilerError::SanityError(format!(
-                    "VarDefInitLit found bad SymbolType (on self.var_value.evaluate_node()): {:?}",
+                    "VarDefInitLit found bad SymbolType (on self.var_value.generate_node()): {:?}",
                     bad
                 )))
             }
             None => {
-                return Err(CompilerError::SanityError(format!(
-                    "VarDefInitLit found no SymbolType (on self.var_value.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "VarDefInitLit found no SymbolType (on self.var_value.generate_node())"
+                        .to_string(),
+                ))
             }
         };
 
-        let def_symbol = {
-            let span = self.var_def.get_span();
-            let def_symbol = stack.get_previous_def(span, lexer, SymbolClass::default_var())?;
-            def_symbol.clone()
-        };
-
-        let _updated_symbol =
-            def_symbol.cast_or_scream(&lit_symbol_type, self.node_id, lexer, false)?; // Legacy from previous version.
+        let def_symbol = annotations.symbol(arena.node(self.var_def).get_span())?;
 
         if let SymbolType::Int(IntValue::Literal(num)) = lit_symbol_type {
             let new_register = code.new_register();
-            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
-                num,
-                new_register,
-            ))));
-            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+            let load = Instruction::Unlabeled(Operation::LoadI(num, new_register));
+            let store = Instruction::Unlabeled(Operation::StoreAI(
                 new_register,
                 def_symbol.offset_source,
                 def_symbol.offset as i32,
-            ))));
-        };
-
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
+            ));
+            if arena.get(self.var_def).is_static {
+                code.push_static_init(load);
+                code.push_static_init(store);
+            } else {
+                code.push_code(CodeLine::Deliver(load));
+                code.push_code(CodeLine::Deliver(store));
+            }
         };
 
         Ok(None)
@@ -798,84 +1020,64 @@ impl AstNode for VarDefInitLit {
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarDefInitLit
     }
 }
 
 #[derive(Debug)]
 pub struct VarLeftShift {
     node_id: Span,
-    var_name: Box<VarInvoke>,
-    shift_amount: Box<LiteralInt>,
-    next: Option<Box<dyn AstNode>>,
+    var_name: NodeId<VarInvoke>,
+    shift_amount: NodeId<LiteralInt>,
 }
 
 impl VarLeftShift {
     pub fn new(
         node_id: Span,
-        var_name: Box<VarInvoke>,
-        shift_amount: Box<LiteralInt>,
-        next: Option<Box<dyn AstNode>>,
+        var_name: NodeId<VarInvoke>,
+        shift_amount: NodeId<LiteralInt>,
     ) -> VarLeftShift {
         VarLeftShift {
             node_id,
             var_name,
             shift_amount,
-            next,
         }
     }
-}
 
-impl AstNode for VarLeftShift {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_name.as_ref(), own_address);
-        print_dependencies_own(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_name.as_ref(), own_address);
-        print_dependencies_child(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_name.as_ref(), lexer);
-        print_labels_child(self.shift_amount.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    pub fn var_name(&self) -> NodeId<VarInvoke> {
+        self.var_name
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn shift_amount(&self) -> NodeId<LiteralInt> {
+        self.shift_amount
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VarLeftShift {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_name.evaluate_node(code, stack, lexer)?;
-        self.shift_amount.evaluate_node(code, stack, lexer)?;
+        arena.check(self.var_name, stack, lexer)?;
+        arena.check(self.shift_amount, stack, lexer)?;
 
         let symbol = match stack.pop_symbol()? {
             Some(symbol) => symbol,
             None => {
-                return Err(CompilerError::SanityError(format!(
-                    "failed to pop expected literal int symbol (on varLeftShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "failed to pop expected literal int symbol (on varLeftShift.check_node())"
+                        .to_string(),
+                ))
             }
         };
 
         match symbol.type_value {
             SymbolType::Int(IntValue::Literal(value)) => {
                 if value > 16 {
-                    let shift_amount_span = self.shift_amount.get_span();
+                    let shift_amount_span = arena.node(self.shift_amount).get_span();
                     let highlight = ScopeStack::form_string_highlight(shift_amount_span, lexer);
                     let ((line, col), (_, _)) = lexer.line_col(shift_amount_span);
 
@@ -888,99 +1090,89 @@ impl AstNode for VarLeftShift {
                 }
             }
             _ => {
-                return Err(CompilerError::SanityError(format!(
-                    "shift_amount received is NOT a literal int (on varLeftShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "shift_amount received is NOT a literal int (on varLeftShift.check_node())"
+                        .to_string(),
+                ))
             }
         }
+        stack.check_writable(arena.node(self.var_name).get_span(), lexer)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_name, code, annotations, lexer)?;
+        arena.generate(self.shift_amount, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarLeftShift
     }
 }
 
 #[derive(Debug)]
 pub struct VarRightShift {
     node_id: Span,
-    var_name: Box<VarInvoke>,
-    shift_amount: Box<LiteralInt>,
-    next: Option<Box<dyn AstNode>>,
+    var_name: NodeId<VarInvoke>,
+    shift_amount: NodeId<LiteralInt>,
 }
 
 impl VarRightShift {
     pub fn new(
         node_id: Span,
-        var_name: Box<VarInvoke>,
-        shift_amount: Box<LiteralInt>,
-        next: Option<Box<dyn AstNode>>,
+        var_name: NodeId<VarInvoke>,
+        shift_amount: NodeId<LiteralInt>,
     ) -> VarRightShift {
         VarRightShift {
             node_id,
             var_name,
             shift_amount,
-            next,
         }
     }
-}
 
-impl AstNode for VarRightShift {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_name.as_ref(), own_address);
-        print_dependencies_own(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_name.as_ref(), own_address);
-        print_dependencies_child(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_name.as_ref(), lexer);
-        print_labels_child(self.shift_amount.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
+    pub fn var_name(&self) -> NodeId<VarInvoke> {
+        self.var_name
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn shift_amount(&self) -> NodeId<LiteralInt> {
+        self.shift_amount
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VarRightShift {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_name.evaluate_node(code, stack, lexer)?;
-        self.shift_amount.evaluate_node(code, stack, lexer)?;
+        arena.check(self.var_name, stack, lexer)?;
+        arena.check(self.shift_amount, stack, lexer)?;
 
         let symbol = match stack.pop_symbol()? {
             Some(symbol) => symbol,
             None => {
-                return Err(CompilerError::SanityError(format!(
-                    "failed to pop expected literal int symbol (on varRightShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "failed to pop expected literal int symbol (on varRightShift.check_node())"
+                        .to_string(),
+                ))
             }
         };
 
         match symbol.type_value {
             SymbolType::Int(IntValue::Literal(value)) => {
                 if value > 16 {
-                    let shift_amount_span = self.shift_amount.get_span();
+                    let shift_amount_span = arena.node(self.shift_amount).get_span();
                     let highlight = ScopeStack::form_string_highlight(shift_amount_span, lexer);
                     let ((line, col), (_, _)) = lexer.line_col(shift_amount_span);
 
@@ -993,100 +1185,86 @@ impl AstNode for VarRightShift {
                 }
             }
             _ => {
-                return Err(CompilerError::SanityError(format!(
-                    "shift_amount received is NOT a literal int (on varRightShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "shift_amount received is NOT a literal int (on varRightShift.check_node())"
+                        .to_string(),
+                ))
             }
         }
+        stack.check_writable(arena.node(self.var_name).get_span(), lexer)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_name, code, annotations, lexer)?;
+        arena.generate(self.shift_amount, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarRightShift
     }
 }
 
 #[derive(Debug)]
 pub struct VecLeftShift {
     node_id: Span,
-    vec_access: Box<VecAccess>,
-    shift_amount: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    vec_access: NodeId<VecAccess>,
+    shift_amount: NodeId,
 }
 
 impl VecLeftShift {
-    pub fn new(
-        node_id: Span,
-        vec_access: Box<VecAccess>,
-        shift_amount: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VecLeftShift {
+    pub fn new(node_id: Span, vec_access: NodeId<VecAccess>, shift_amount: NodeId) -> VecLeftShift {
         VecLeftShift {
             node_id,
             vec_access,
             shift_amount,
-            next,
         }
     }
-}
 
-impl AstNode for VecLeftShift {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.vec_access.as_ref(), own_address);
-        print_dependencies_own(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.vec_access.as_ref(), own_address);
-        print_dependencies_child(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.vec_access.as_ref(), lexer);
-        print_labels_child(self.shift_amount.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    pub fn vec_access(&self) -> NodeId<VecAccess> {
+        self.vec_access
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn shift_amount(&self) -> NodeId {
+        self.shift_amount
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VecLeftShift {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.vec_access.evaluate_node(code, stack, lexer)?;
+        arena.check(self.vec_access, stack, lexer)?;
 
-        self.shift_amount.evaluate_node(code, stack, lexer)?;
+        arena.check(self.shift_amount, stack, lexer)?;
 
         let symbol = match stack.pop_symbol()? {
             Some(symbol) => symbol,
             None => {
-                return Err(CompilerError::SanityError(format!(
-                    "failed to pop expected literal int symbol (on vecLeftShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "failed to pop expected literal int symbol (on vecLeftShift.check_node())"
+                        .to_string(),
+                ))
             }
         };
 
         match symbol.type_value {
             SymbolType::Int(IntValue::Literal(value)) => {
                 if value > 16 {
-                    let shift_amount_span = self.shift_amount.get_span();
+                    let shift_amount_span = arena.node(self.shift_amount).get_span();
                     let highlight = ScopeStack::form_string_highlight(shift_amount_span, lexer);
                     let ((line, col), (_, _)) = lexer.line_col(shift_amount_span);
 
@@ -1099,100 +1277,89 @@ impl AstNode for VecLeftShift {
                 }
             }
             _ => {
-                return Err(CompilerError::SanityError(format!(
-                    "shift_amount received is NOT a literal int (on vecLeftShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "shift_amount received is NOT a literal int (on vecLeftShift.check_node())"
+                        .to_string(),
+                ))
             }
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.vec_access, code, annotations, lexer)?;
+        arena.generate(self.shift_amount, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VecLeftShift
     }
 }
 
 #[derive(Debug)]
 pub struct VecRightShift {
     node_id: Span,
-    vec_access: Box<VecAccess>,
-    shift_amount: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    vec_access: NodeId<VecAccess>,
+    shift_amount: NodeId,
 }
 
 impl VecRightShift {
     pub fn new(
         node_id: Span,
-        vec_access: Box<VecAccess>,
-        shift_amount: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
+        vec_access: NodeId<VecAccess>,
+        shift_amount: NodeId,
     ) -> VecRightShift {
         VecRightShift {
             node_id,
             vec_access,
             shift_amount,
-            next,
         }
     }
-}
 
-impl AstNode for VecRightShift {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.vec_access.as_ref(), own_address);
-        print_dependencies_own(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.vec_access.as_ref(), own_address);
-        print_dependencies_child(self.shift_amount.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.vec_access.as_ref(), lexer);
-        print_labels_child(self.shift_amount.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
+    pub fn vec_access(&self) -> NodeId<VecAccess> {
+        self.vec_access
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn shift_amount(&self) -> NodeId {
+        self.shift_amount
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VecRightShift {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.vec_access.evaluate_node(code, stack, lexer)?;
+        arena.check(self.vec_access, stack, lexer)?;
 
-        self.shift_amount.evaluate_node(code, stack, lexer)?;
+        arena.check(self.shift_amount, stack, lexer)?;
 
         let symbol = match stack.pop_symbol()? {
             Some(symbol) => symbol,
             None => {
-                return Err(CompilerError::SanityError(format!(
-                    "failed to pop expected literal int symbol (on vecRightShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "failed to pop expected literal int symbol (on vecRightShift.check_node())"
+                        .to_string(),
+                ))
             }
         };
 
         match symbol.type_value {
             SymbolType::Int(IntValue::Literal(value)) => {
                 if value > 16 {
-                    let shift_amount_span = self.shift_amount.get_span();
+                    let shift_amount_span = arena.node(self.shift_amount).get_span();
                     let highlight = ScopeStack::form_string_highlight(shift_amount_span, lexer);
                     let ((line, col), (_, _)) = lexer.line_col(shift_amount_span);
 
@@ -1205,96 +1372,112 @@ impl AstNode for VecRightShift {
                 }
             }
             _ => {
-                return Err(CompilerError::SanityError(format!(
-                    "shift_amount received is NOT a literal int (on vecRightShift.evaluate_node())"
-                )))
+                return Err(CompilerError::SanityError(
+                    "shift_amount received is NOT a literal int (on vecRightShift.check_node())"
+                        .to_string(),
+                ))
             }
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.vec_access, code, annotations, lexer)?;
+        arena.generate(self.shift_amount, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VecRightShift
     }
 }
 
 #[derive(Debug)]
 pub struct VarSet {
     node_id: Span,
-    var_name: Box<VarInvoke>,
-    new_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    var_name: NodeId<VarInvoke>,
+    new_value: NodeId,
 }
 
 impl VarSet {
-    pub fn new(
-        node_id: Span,
-        var_name: Box<VarInvoke>,
-        new_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VarSet {
+    pub fn new(node_id: Span, var_name: NodeId<VarInvoke>, new_value: NodeId) -> VarSet {
         VarSet {
             node_id,
             var_name,
             new_value,
-            next,
         }
     }
-}
 
-impl AstNode for VarSet {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_name.as_ref(), own_address);
-        print_dependencies_own(self.new_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_name.as_ref(), own_address);
-        print_dependencies_child(self.new_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_name.as_ref(), lexer);
-        print_labels_child(self.new_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    pub fn var_name(&self) -> NodeId<VarInvoke> {
+        self.var_name
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn new_value(&self) -> NodeId {
+        self.new_value
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VarSet {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_name.evaluate_node(code, stack, lexer)?;
+        // The new value is read before the variable is assigned, so it goes first.
         let new_value_symbol =
-            self.new_value
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "New value has no SymbolType (on VarSet.evaluate_node())"
-                )))?;
-
-        let def_symbol =
-            stack.get_previous_def(self.var_name.get_span(), lexer, SymbolClass::default_var())?;
+            arena
+                .check(self.new_value, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "New value has no SymbolType (on VarSet.check_node())".to_string(),
+                ))?;
+
+        let def_symbol = stack.get_previous_def(
+            arena.node(self.var_name).get_span(),
+            lexer,
+            SymbolClass::default_var(),
+        )?;
+        stack.check_writable(arena.node(self.var_name).get_span(), lexer)?;
+        stack.mark_assigned(&def_symbol);
+        arena.check(self.var_name, stack, lexer)?;
 
         let _updated_symbol =
             def_symbol.cast_or_scream(&new_value_symbol, self.node_id, lexer, true)?;
+        stack.warn_lossy(
+            arena,
+            self.new_value,
+            &new_value_symbol,
+            &def_symbol.type_value,
+            true,
+            lexer,
+        );
+
+        Ok(Some(def_symbol.type_value))
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_name, code, annotations, lexer)?;
+        let new_value_symbol = arena
+            .generate(self.new_value, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "New value has no SymbolType (on VarSet.generate_node())".to_string(),
+            ))?;
+
+        let def_symbol = annotations.symbol(arena.node(self.var_name).get_span())?;
 
         let setter_register;
         match new_value_symbol {
@@ -1337,90 +1520,89 @@ impl AstNode for VarSet {
 
         let return_symbol = Some(SymbolType::Int(IntValue::Memory(def_symbol.offset_source, def_symbol.offset)));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(return_symbol)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarSet
     }
 }
 
 #[derive(Debug)]
 pub struct VecSet {
     node_id: Span,
-    vec_access: Box<VecAccess>,
-    new_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    vec_access: NodeId<VecAccess>,
+    new_value: NodeId,
 }
 
 impl VecSet {
-    pub fn new(
-        node_id: Span,
-        vec_access: Box<VecAccess>,
-        new_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VecSet {
+    pub fn new(node_id: Span, vec_access: NodeId<VecAccess>, new_value: NodeId) -> VecSet {
         VecSet {
             node_id,
             vec_access,
             new_value,
-            next,
         }
     }
-}
 
-impl AstNode for VecSet {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.vec_access.as_ref(), own_address);
-        print_dependencies_own(self.new_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.vec_access.as_ref(), own_address);
-        print_dependencies_child(self.new_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.vec_access.as_ref(), lexer);
-        print_labels_child(self.new_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
+    pub fn vec_access(&self) -> NodeId<VecAccess> {
+        self.vec_access
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn new_value(&self) -> NodeId {
+        self.new_value
     }
-    fn evaluate_node(
+}
+
+impl AstNode for VecSet {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-
         let new_value_symbol =
-            self.new_value
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "New value has no SymbolType (on VecSet.evaluate_node())"
-                )))?;
-
-        let def_symbol =
-            stack.get_previous_def(self.vec_access.vec_name.get_span(), lexer, SymbolClass::default_vec())?;
+            arena
+                .check(self.new_value, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "New value has no SymbolType (on VecSet.check_node())".to_string(),
+                ))?;
+
+        let def_symbol = stack.get_previous_def(
+            arena.node(arena.get(self.vec_access).vec_name).get_span(),
+            lexer,
+            SymbolClass::default_vec(),
+        )?;
 
         let _updated_symbol =
             def_symbol.cast_or_scream(&new_value_symbol, self.node_id, lexer, true)?;
+        stack.warn_lossy(
+            arena,
+            self.new_value,
+            &new_value_symbol,
+            &def_symbol.type_value,
+            true,
+            lexer,
+        );
+
+        arena.check(self.vec_access, stack, lexer)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let new_value_symbol = arena
+            .generate(self.new_value, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "New value has no SymbolType (on VecSet.generate_node())".to_string(),
+            ))?;
+
+        let def_symbol =
+            annotations.symbol(arena.node(arena.get(self.vec_access).vec_name).get_span())?;
 
         let setter_register;
         match new_value_symbol {
@@ -1458,7 +1640,7 @@ impl AstNode for VecSet {
 
         let return_symbol;
 
-        match self.vec_access.evaluate_node(code, stack, lexer)?.ok_or(CompilerError::ParsingErrors(format!("vec_access.evaluate_node() returned no type value for index expression in VecSet.evaluate_node()")))? {
+        match arena.generate(self.vec_access, code, annotations, lexer)?.ok_or(CompilerError::ParsingErrors("vec_access.generate_node() returned no type value for index expression in VecSet.generate_node()".to_string()))? {
             SymbolType::Int(IntValue::Memory(offset_source, offset)) => {
                 return_symbol = Some(SymbolType::Int(IntValue::Memory(offset_source, offset)));
                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
@@ -1468,78 +1650,51 @@ impl AstNode for VecSet {
                 ))));
             },
             bad => return Err(CompilerError::SanityError(format!(
-                "vec_access.evaluate_node() on VecSet returned something different from a memory location: {:?}", bad
+                "vec_access.generate_node() on VecSet returned something different from a memory location: {:?}", bad
             ))),
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(return_symbol)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VecSet
     }
 }
 
 #[derive(Debug)]
 pub struct Input {
     node_id: Span,
-    var_name: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    var_name: NodeId,
 }
 
 impl Input {
-    pub fn new(node_id: Span, var_name: Box<dyn AstNode>, next: Option<Box<dyn AstNode>>) -> Input {
-        Input {
-            node_id,
-            var_name,
-            next,
-        }
+    pub fn new(node_id: Span, var_name: NodeId) -> Input {
+        Input { node_id, var_name }
+    }
+
+    pub fn var_name(&self) -> NodeId {
+        self.var_name
     }
 }
 
 impl AstNode for Input {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_name.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_name.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_name.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_name.evaluate_node(code, stack, lexer)?;
-
-        let id = self.var_name.get_span();
-        let var_def = stack.get_previous_def(id, lexer, SymbolClass::default_var())?;
+        let id = arena.node(self.var_name).get_span();
+        let var_def = stack.get_value_def(id, lexer)?;
+        stack.check_writable(id, lexer)?;
+        stack.mark_assigned(&var_def);
+        arena.check(self.var_name, stack, lexer)?;
 
         match var_def.type_value {
-            SymbolType::Int(_) | SymbolType::Float(_) => (),
+            SymbolType::Int(_) | SymbolType::Float(_) | SymbolType::Error => (),
             _ => {
                 let first_highlight = ScopeStack::form_string_highlight(var_def.span, lexer);
                 let ((first_line, first_col), (_, _)) = lexer.line_col(var_def.span);
@@ -1559,78 +1714,57 @@ impl AstNode for Input {
             }
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
-    fn get_span(&self) -> Span {
-        self.node_id
-    }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_name, code, annotations, lexer)?;
+
+        Ok(None)
+    }
+    fn get_span(&self) -> Span {
+        self.node_id
+    }
+    fn kind(&self) -> NodeKind {
+        NodeKind::Input
     }
 }
 
 #[derive(Debug)]
 pub struct OutputId {
     node_id: Span,
-    var_name: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    var_name: NodeId,
 }
 
 impl OutputId {
-    pub fn new(
-        node_id: Span,
-        var_name: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> OutputId {
-        OutputId {
-            node_id,
-            var_name,
-            next,
-        }
+    pub fn new(node_id: Span, var_name: NodeId) -> OutputId {
+        OutputId { node_id, var_name }
+    }
+
+    pub fn var_name(&self) -> NodeId {
+        self.var_name
     }
 }
 
 impl AstNode for OutputId {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.var_name.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.var_name.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.var_name.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.var_name.evaluate_node(code, stack, lexer)?;
+        arena.check(self.var_name, stack, lexer)?;
 
-        let id = self.var_name.get_span();
-        let var_def = stack.get_previous_def(id, lexer, SymbolClass::default_var())?;
+        let id = arena.node(self.var_name).get_span();
+        let var_def = stack.get_value_def(id, lexer)?;
 
         match var_def.type_value {
-            SymbolType::Int(_) | SymbolType::Float(_) => (),
+            SymbolType::Int(_) | SymbolType::Float(_) | SymbolType::Error => (),
             _ => {
                 let first_highlight = ScopeStack::form_string_highlight(var_def.span, lexer);
                 let ((first_line, first_col), (_, _)) = lexer.line_col(var_def.span);
@@ -1650,78 +1784,57 @@ impl AstNode for OutputId {
             }
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.var_name, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::OutputId
     }
 }
 
 #[derive(Debug)]
 pub struct OutputLit {
     node_id: Span,
-    lit_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    lit_value: NodeId,
 }
 
 impl OutputLit {
-    pub fn new(
-        node_id: Span,
-        lit_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> OutputLit {
-        OutputLit {
-            node_id,
-            lit_value,
-            next,
-        }
+    pub fn new(node_id: Span, lit_value: NodeId) -> OutputLit {
+        OutputLit { node_id, lit_value }
+    }
+
+    pub fn lit_value(&self) -> NodeId {
+        self.lit_value
     }
 }
 
 impl AstNode for OutputLit {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.lit_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.lit_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.lit_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        self.lit_value.evaluate_node(code, stack, lexer)?;
+        arena.check(self.lit_value, stack, lexer)?;
 
         let symbol = match stack.pop_symbol()? {
             Some(symbol) => symbol,
             None => {
                 return Err(CompilerError::SanityError(format!(
-                    "evaluate_node() failed to pop symbol (self: {:?})",
+                    "check_node() failed to pop symbol (self: {:?})",
                     &self,
                 )))
             }
@@ -1742,207 +1855,252 @@ impl AstNode for OutputLit {
             }
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        arena.generate(self.lit_value, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::OutputLit
     }
 }
 
 #[derive(Debug)]
 pub struct Continue {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl Continue {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> Continue {
-        Continue { node_id, next }
+    pub fn new(node_id: Span) -> Continue {
+        Continue { node_id }
     }
 }
 
 impl AstNode for Continue {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        stack.check_in_loop(self.node_id, lexer)?;
 
         Ok(None)
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        _lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(None)
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::Continue
     }
 }
 
 #[derive(Debug)]
 pub struct Break {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl Break {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> Break {
-        Break { node_id, next }
+    pub fn new(node_id: Span) -> Break {
+        Break { node_id }
     }
 }
 
 impl AstNode for Break {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        stack.check_in_loop(self.node_id, lexer)?;
+        stack.mark_break();
+
+        Ok(None)
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        _lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(None)
+    }
+    fn get_span(&self) -> Span {
+        self.node_id
     }
-    fn is_tree_member(&self) -> bool {
-        true
+    fn kind(&self) -> NodeKind {
+        NodeKind::Break
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+}
+
+pub const RETURN_VAL_OFFSET: i32 = 12;
+
+#[derive(Debug)]
+pub struct Asm {
+    node_id: Span,
+}
+
+impl Asm {
+    pub fn new(node_id: Span) -> Asm {
+        Asm { node_id }
     }
-    fn evaluate_node(
+}
+
+impl AstNode for Asm {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        inline_asm::check(self.node_id, stack, lexer)?;
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        inline_asm::assemble(self.node_id, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::Asm
     }
 }
 
-const RETURN_VAL_OFFSET: i32 = 12;
-
 #[derive(Debug)]
 pub struct Return {
     node_id: Span,
-    ret_value: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    ret_value: NodeId,
 }
 
 impl Return {
-    pub fn new(
-        node_id: Span,
-        ret_value: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> Return {
-        Return {
-            node_id,
-            ret_value,
-            next,
-        }
+    pub fn new(node_id: Span, ret_value: NodeId) -> Return {
+        Return { node_id, ret_value }
+    }
+
+    pub fn ret_value(&self) -> NodeId {
+        self.ret_value
     }
 }
 
 impl AstNode for Return {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.ret_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.ret_value.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.ret_value.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let current_scope_type = stack.get_current_scope_type()?;
-        let return_value_type = &self.ret_value.evaluate_node(code, stack, lexer)?.ok_or(
-            CompilerError::SanityError(format!(
-                "Return got no return_value_type from ret_value.evaluate_node(): {:?}",
-                self.ret_value
-            )),
-        )?;
+        let return_value_type =
+            &arena
+                .check(self.ret_value, stack, lexer)?
+                .ok_or(CompilerError::SanityError(format!(
+                    "Return got no return_value_type from ret_value.check_node(): {:?}",
+                    self.ret_value
+                )))?;
+        stack.end_path();
 
-        match return_value_type {
-            SymbolType::String(_) => {
-                let span = self.ret_value.get_span();
-                let id = lexer.span_str(span).to_string();
-                let ((line, col), (_, _)) = lexer.line_col(span);
-                let highlight = ScopeStack::form_string_highlight(span, lexer);
-                return Err(CompilerError::SemanticErrorFunctionString {
-                    id,
+        if let SymbolType::String(_) = return_value_type {
+            let span = arena.node(self.ret_value).get_span();
+            let id = lexer.span_str(span).to_string();
+            let ((line, col), (_, _)) = lexer.line_col(span);
+            let highlight = ScopeStack::form_string_highlight(span, lexer);
+            return Err(CompilerError::SemanticErrorFunctionString {
+                id,
+                line,
+                col,
+                highlight,
+            });
+        }
+
+        match current_scope_type.associate_with(return_value_type, self.node_id, lexer) {
+            Ok(_) => (),
+            Err(_) => {
+                let id = arena.node(self.ret_value).get_span();
+                let expected_type = current_scope_type.to_str().to_string();
+                let received_type = return_value_type.to_str().to_string();
+                let highlight = ScopeStack::form_string_highlight(id, lexer);
+                let ((line, col), (_, _)) = lexer.line_col(id);
+                return Err(CompilerError::SemanticErrorWrrongParReturn {
+                    expected_type,
+                    received_type,
                     line,
                     col,
                     highlight,
                 });
             }
+        }
+        stack.warn_lossy(
+            arena,
+            self.ret_value,
+            return_value_type,
+            &current_scope_type,
+            true,
+            lexer,
+        );
+
+        stack.annotate_return_type(self.node_id, current_scope_type);
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let current_scope_type = annotations.return_type(self.node_id)?;
+        let return_value_type = &arena
+            .generate(self.ret_value, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(format!(
+                "Return got no return_value_type from ret_value.generate_node(): {:?}",
+                self.ret_value
+            )))?;
+
+        match return_value_type {
             SymbolType::Int(return_value) => {
                 match return_value {
                     IntValue::Undefined => {
-                        return Err(CompilerError::SanityError(format!("Undefined Int as return value in Return function.")))
+                        return Err(CompilerError::SanityError(
+                            "Undefined Int as return value in Return function.".to_string(),
+                        ))
                     }
                     IntValue::Temp(register) => {
-                        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(*register, Register::Rfp, RETURN_VAL_OFFSET))))
+                        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(
+                            Operation::StoreAI(*register, Register::Rfp, RETURN_VAL_OFFSET),
+                        )))
                     }
                     IntValue::Memory(offset_source, offset) => {
                         let new_register = code.new_register();
@@ -1992,119 +2150,178 @@ impl AstNode for Return {
                     jump_reg,
                 ))));
             }
-            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Unsuported type usage for Return in function: {}", return_value_type.to_str().to_string()))),
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Unsuported type usage for Return in function: {}", return_value_type.to_str()))),
         }
 
-        match current_scope_type.associate_with(return_value_type, self.node_id, lexer) {
-            Ok(SymbolType::Int(_)) => (),
-            Ok(_) => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Unsuported type usage for Return in function: {}", return_value_type.to_str().to_string()))),
-            Err(_) => {
-                let id = self.ret_value.get_span();
-                let expected_type = current_scope_type.to_str().to_string();
-                let received_type = return_value_type.to_str().to_string();
-                let highlight = ScopeStack::form_string_highlight(id, lexer);
-                let ((line, col), (_, _)) = lexer.line_col(id);
-                return Err(CompilerError::SemanticErrorWrrongParReturn {
-                    expected_type,
-                    received_type,
-                    line,
-                    col,
-                    highlight,
-                });
-            }
+        match current_scope_type.associate_with(return_value_type, self.node_id, lexer)? {
+            SymbolType::Int(_) => (),
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Unsuported type usage for Return in function: {}", return_value_type.to_str()))),
         }
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::Return
     }
 }
 
 #[derive(Debug)]
 pub struct FnCall {
     node_id: Span,
-    args: Vec<Box<dyn AstNode>>,
-    next: Option<Box<dyn AstNode>>,
+    args: Vec<NodeId>,
 }
 
 impl FnCall {
-    pub fn new(
-        node_id: Span,
-        args: Vec<Box<dyn AstNode>>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> FnCall {
-        FnCall {
-            node_id,
-            args,
-            next,
-        }
+    pub fn new(node_id: Span, args: Vec<NodeId>) -> FnCall {
+        FnCall { node_id, args }
     }
 
-    fn print_label_fn_call(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        println!(
-            "{:p} [label=\"call {}\"];",
-            own_address,
-            lexer.span_str(self.node_id)
-        );
+    pub fn args(&self) -> &[NodeId] {
+        &self.args
     }
-}
 
-impl AstNode for FnCall {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        for arg in &self.args {
-            print_dependencies_own(arg.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
+    // Exact parameter types win over ones the arguments convert to, and
+    // anything else than a single winner is an error.
+    fn check_overloaded(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        overloads: Vec<DefSymbol>,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let mut arg_types = vec![];
         for arg in &self.args {
-            print_dependencies_child(arg.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
+            let arg_type = arena
+                .check(*arg, stack, lexer)?
+                .ok_or(CompilerError::SanityError(format!(
+                    "FnCall error; .check_node() on arg returned no type: {:?}",
+                    arg
+                )))?;
+            arg_types.push(arg_type);
+        }
+        if arg_types
+            .iter()
+            .any(|arg_type| matches!(arg_type, SymbolType::Error))
+        {
+            return Ok(Some(SymbolType::Error));
+        }
+
+        let mut exact = vec![];
+        let mut converted = vec![];
+        for overload in &overloads {
+            let parameters = match &overload.class {
+                SymbolClass::Fn(params) if params.len() == arg_types.len() => params.clone(),
+                _ => continue,
+            };
+            let mut param_types = vec![];
+            for param in &parameters {
+                param_types.push(param.get_symbol_type(stack, lexer)?);
+            }
+            let pairs = arg_types.iter().zip(&param_types);
+            if pairs
+                .clone()
+                .all(|(arg, param)| arg.to_str() == param.to_str())
+            {
+                exact.push(overload.clone());
+            } else if pairs.clone().all(|(arg, param)| converts_to(arg, param)) {
+                converted.push(overload.clone());
+            }
         }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.print_label_fn_call(lexer, own_address);
-        for arg in &self.args {
-            print_labels_child(arg.as_ref(), lexer);
+        let matches = if exact.is_empty() { converted } else { exact };
+
+        if let [chosen] = matches.as_slice() {
+            if let SymbolClass::Fn(params) = &chosen.class {
+                for ((arg, arg_type), param) in self.args.iter().zip(&arg_types).zip(params) {
+                    let param_type = param.get_symbol_type(stack, lexer)?;
+                    stack.warn_lossy(arena, *arg, arg_type, &param_type, true, lexer);
+                }
+            }
         }
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
+
+        let id = lexer.span_str(self.node_id).to_string();
+        let arg_names: Vec<&str> = arg_types.iter().map(|arg_type| arg_type.to_str()).collect();
+        let arg_types = arg_names.join(", ");
+        let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+        let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+        match matches.as_slice() {
+            [chosen] => {
+                stack.resolve_call(self.node_id, chosen);
+                Ok(Some(chosen.type_value.clone()))
+            }
+            [] => Err(CompilerError::SemanticErrorNoOverload {
+                id,
+                arg_types,
+                candidates: list_candidates(&overloads, stack, lexer)?,
+                line,
+                col,
+                highlight,
+            }),
+            tied => Err(CompilerError::SemanticErrorAmbiguousCall {
+                id,
+                arg_types,
+                candidates: list_candidates(tied, stack, lexer)?,
+                line,
+                col,
+                highlight,
+            }),
         }
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+}
+
+// Strings never make it into a call, and chars only go where a char is taken.
+fn converts_to(arg: &SymbolType, param: &SymbolType) -> bool {
+    match (arg, param) {
+        (SymbolType::String(_), _) | (_, SymbolType::String(_)) => false,
+        (SymbolType::Char(_), SymbolType::Char(_)) => true,
+        (SymbolType::Char(_), _) | (_, SymbolType::Char(_)) => false,
+        _ => true,
     }
-    fn evaluate_node(
+}
+
+fn list_candidates(
+    overloads: &[DefSymbol],
+    stack: &mut ScopeStack,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<String, CompilerError> {
+    let mut candidates = String::new();
+    for overload in overloads {
+        let params = match &overload.class {
+            SymbolClass::Fn(params) => params.clone(),
+            _ => vec![],
+        };
+        candidates.push_str(&format!(
+            "\n  {} at line {}, column {}",
+            stack.signature(&overload.id, &params, lexer)?,
+            overload.line,
+            overload.col
+        ));
+    }
+    Ok(candidates)
+}
+
+impl AstNode for FnCall {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let span = self.node_id;
+        let overloads = stack.get_overloads(lexer.span_str(span));
+        if overloads.len() > 1 {
+            return self.check_overloaded(arena, stack, overloads, lexer);
+        }
         let class = SymbolClass::Fn(vec![]);
-        let parameters = {
-            let previous_def = stack.get_previous_def(span, lexer, class.clone())?;
-            match &previous_def.class {
-                SymbolClass::Fn(params) => params.clone(),
-                _ => {
-                    return Err(CompilerError::SanityError(
-                        "FnCall.evaluate_node() received an invalid class from previous def."
-                            .to_string(),
-                    ))
-                }
+        let previous_def = stack.get_previous_def(span, lexer, class.clone())?;
+        let parameters = match &previous_def.class {
+            SymbolClass::Fn(params) => params.clone(),
+            _ => {
+                return Err(CompilerError::SanityError(
+                    "FnCall.check_node() received an invalid class from previous def.".to_string(),
+                ))
             }
         };
         let args_num = self.args.len();
@@ -2142,20 +2359,21 @@ impl AstNode for FnCall {
             });
         }
 
-        let mut next_param_loc = FN_OFFSET as i32;
         if params_num > 0 {
             let mut param_types = vec![];
             for param in &parameters {
-                let param_type = param.get_symbol_type(lexer)?;
+                let param_type = param.get_symbol_type(stack, lexer)?;
                 param_types.push(param_type);
             }
             for (i, arg) in self.args.iter().enumerate() {
                 let arg_type =
-                    arg.evaluate_node(code, stack, lexer)?
+                    arena
+                        .check(*arg, stack, lexer)?
                         .ok_or(CompilerError::SanityError(format!(
-                            "FnCall error; .evaluate_node() on arg returned no type: {:?}",
+                            "FnCall error; .check_node() on arg returned no type: {:?}",
                             arg
                         )))?;
+                stack.warn_lossy(arena, *arg, &arg_type, &param_types[i], true, lexer);
                 match (arg_type, &param_types[i]) {
                     (SymbolType::String(_), _) | (_, SymbolType::String(_)) => {
                         let param = parameters[i];
@@ -2172,46 +2390,7 @@ impl AstNode for FnCall {
                     }
                     (SymbolType::Char(_), SymbolType::Char(_)) => continue,
                     (SymbolType::Char(_), _) | (_, SymbolType::Char(_)) => (),
-                    (SymbolType::Int(int_value), SymbolType::Int(_)) => {
-                        match int_value {
-                            bad @ IntValue::Undefined => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Usage of undefined int type as function parameter for \"{}()\":\n{:?}", id, bad))),
-                            IntValue::Temp(register) => {
-                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-                                    register,
-                                    Register::Rsp,
-                                    next_param_loc,
-                                ))));
-                            }
-                            IntValue::Memory(offset_source, offset) => {
-                                let new_register = code.new_register();
-                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
-                                    offset_source,
-                                    offset as i32,
-                                    new_register,
-                                ))));
-                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-                                    new_register,
-                                    Register::Rsp,
-                                    next_param_loc,
-                                ))));
-                            }
-                            IntValue::Literal(number) => {
-                                let new_register = code.new_register();
-                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
-                                    number,
-                                    new_register,
-                                ))));
-                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-                                    new_register,
-                                    Register::Rsp,
-                                    next_param_loc,
-                                ))));
-                            }
-                        };
-                        next_param_loc += INT_SIZE as i32;
-                        continue;
-                    },
-                    (bad, worse) => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Usage of unsuported types as function parameters for \"{}()\":\n{:?}\n{:?}", id, bad, worse))),
+                    _ => continue,
                 }
                 let id = lexer.span_str(self.node_id).to_string();
                 let previous_def = stack.get_previous_def(span, lexer, class)?;
@@ -2231,113 +2410,218 @@ impl AstNode for FnCall {
                 });
             }
         }
-        
+
+        Ok(Some(previous_def.type_value))
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let parameters = match &annotations.symbol(self.node_id)?.class {
+            SymbolClass::Fn(params) => params.clone(),
+            _ => {
+                return Err(CompilerError::SanityError(
+                    "FnCall.generate_node() received an invalid class from previous def."
+                        .to_string(),
+                ))
+            }
+        };
+        let params_num = parameters.len();
+
+        let id = lexer.span_str(self.node_id).to_string();
+
+        let mut param_types = vec![];
+        for param in &parameters {
+            let param_type = annotations.symbol(param.node_id)?.type_value.clone();
+            param_types.push(param_type);
+        }
+
+        let mut next_param_loc = FN_OFFSET as i32;
+        if params_num > 0 {
+            for (i, arg) in self.args.iter().enumerate() {
+                let arg_type = arena.generate(*arg, code, annotations, lexer)?.ok_or(
+                    CompilerError::SanityError(format!(
+                        "FnCall error; .generate_node() on arg returned no type: {:?}",
+                        arg
+                    )),
+                )?;
+                match (arg_type, &param_types[i]) {
+                    (SymbolType::Char(_), SymbolType::Char(_)) => continue,
+                    (SymbolType::Int(int_value), SymbolType::Int(_)) => {
+                        match int_value {
+                            bad @ IntValue::Undefined => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Usage of undefined int type as function parameter for \"{}()\":\n{:?}", id, bad))),
+                            IntValue::Temp(register) => {
+                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+                                    register,
+                                    Register::Rsp,
+                                    next_param_loc,
+                                ))));
+                            }
+                            IntValue::Memory(offset_source, offset) => {
+                                let new_register = code.new_register();
+                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
+                                    offset_source,
+                                    offset as i32,
+                                    new_register,
+                                ))));
+                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+                                    new_register,
+                                    Register::Rsp,
+                                    next_param_loc,
+                                ))));
+                            }
+                            IntValue::Literal(number) => {
+                                let new_register = code.new_register();
+                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                                    number,
+                                    new_register,
+                                ))));
+                                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+                                    new_register,
+                                    Register::Rsp,
+                                    next_param_loc,
+                                ))));
+                            }
+                        };
+                        next_param_loc += INT_SIZE as i32;
+                        continue;
+                    },
+                    (bad, worse) => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Usage of unsuported types as function parameters for \"{}()\":\n{:?}\n{:?}", id, bad, worse))),
+                }
+            }
+        }
+
         let current_pos_reg = code.new_register();
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::AddI(Register::Rpc, 5, current_pos_reg))));
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::AddI(
+            Register::Rpc,
+            5,
             current_pos_reg,
-            Register::Rsp,
-            0,
-        ))));
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-            Register::Rsp,
-            Register::Rsp,
-            4,
         ))));
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::StoreAI(
-            Register::Rfp,
-            Register::Rsp,
-            8,
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(
+            Operation::StoreAI(current_pos_reg, Register::Rsp, 0),
+        )));
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(
+            Operation::StoreAI(Register::Rsp, Register::Rsp, 4),
+        )));
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(
+            Operation::StoreAI(Register::Rfp, Register::Rsp, 8),
+        )));
+        let fn_label = code.fn_label(signature(&id, &param_types));
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::JumpI(
+            fn_label,
         ))));
-        let fn_label = match code.get_fn_label(&id) {
-            Some(label_ref) => label_ref.clone(),
-            None => return Err(CompilerError::SanityError(format!("code.get_fn_label() found nothing on FnCall.evaluate_node() for function \"{}\"", id)))
-        };
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::JumpI(fn_label))));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
         let return_val_reg = code.new_register();
-        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(Register::Rsp, RETURN_VAL_OFFSET, return_val_reg))));
+        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(
+            Operation::LoadAI(Register::Rsp, RETURN_VAL_OFFSET, return_val_reg),
+        )));
 
         Ok(Some(SymbolType::Int(IntValue::Temp(return_val_reg))))
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::FnCall
+    }
+}
+
+// Conditions must convert to bool, and literal ones are folded by now, so a
+// condition that never changes is known before run time.
+fn check_condition(
+    condition: &SymbolType,
+    span: Span,
+    condition_span: Span,
+    stack: &mut ScopeStack,
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Result<Option<bool>, CompilerError> {
+    if let BoolValue::Literal(value) = condition.to_bool(span, lexer)? {
+        let ((line, col), (_, _)) = lexer.line_col(condition_span);
+        let highlight = ScopeStack::form_string_highlight(condition_span, lexer);
+        stack.push_warning(CompilerWarning::ConstantCondition {
+            value,
+            line,
+            col,
+            highlight,
+        });
+        return Ok(Some(value));
     }
+    Ok(None)
 }
 
 #[derive(Debug)]
 pub struct If {
     node_id: Span,
-    condition: Box<dyn AstNode>,
-    consequence: CommandBlock,
-    next: Option<Box<dyn AstNode>>,
+    condition: NodeId,
+    consequence: NodeId<CommandBlock>,
 }
 
 impl If {
-    pub fn new(
-        node_id: Span,
-        condition: Box<dyn AstNode>,
-        consequence: CommandBlock,
-        next: Option<Box<dyn AstNode>>,
-    ) -> If {
+    pub fn new(node_id: Span, condition: NodeId, consequence: NodeId<CommandBlock>) -> If {
         If {
             node_id,
             condition,
             consequence,
-            next,
         }
     }
-}
 
-impl AstNode for If {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.condition.as_ref(), own_address);
-        self.consequence
-            .print_first_dependencies(print_dependencies_own, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.condition.as_ref(), own_address);
-        self.consequence
-            .print_first_dependencies(print_dependencies_child, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
+    pub fn condition(&self) -> NodeId {
+        self.condition
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.condition.as_ref(), lexer);
+
+    pub fn consequence(&self) -> NodeId<CommandBlock> {
         self.consequence
-            .print_first_labels(print_labels_child, lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
     }
-    fn evaluate_node(
+}
+
+impl AstNode for If {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let condition_symbol =
-            self.condition
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "condition has no SymbolType (on If.evaluate_node())"
-                )))?;
-        condition_symbol.to_bool(self.node_id, lexer)?;
+            arena
+                .check(self.condition, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "condition has no SymbolType (on If.check_node())".to_string(),
+                ))?;
+        let condition_span = arena.node(self.condition).get_span();
+        let condition = check_condition(
+            &condition_symbol,
+            self.node_id,
+            condition_span,
+            stack,
+            lexer,
+        )?;
+
+        // A constant condition always or never runs the consequence.
+        let flow = stack.flow();
+        arena.check(self.consequence, stack, lexer)?;
+        match condition {
+            Some(true) => (),
+            Some(false) => stack.restore_flow(flow),
+            None => stack.join_flow(flow),
+        }
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let condition_symbol = arena
+            .generate(self.condition, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "condition has no SymbolType (on If.generate_node())".to_string(),
+            ))?;
 
         let before_true_label = code.new_label();
         let after_true_label = code.new_label();
@@ -2345,122 +2629,152 @@ impl AstNode for If {
         let jump_if_true_voucher = code.generate_promise();
         code.push_code(CodeLine::Promise(jump_if_true_voucher));
 
-        code.push_code(CodeLine::Deliver(Instruction::Labeled(before_true_label, Operation::Nop)));
+        code.push_code(CodeLine::Deliver(Instruction::Labeled(
+            before_true_label,
+            Operation::Nop,
+        )));
 
         let promise_payment = match condition_symbol {
-            SymbolType::Bool(BoolValue::Literal(boolean)) => {if boolean {
-                vec![Instruction::Unlabeled(Operation::Nop)]
-            } else {
-                vec![Instruction::Unlabeled(Operation::JumpI(after_true_label))]
-            }},
-            SymbolType::Int(IntValue::Literal(number)) => {if number != 0 {
-                vec![Instruction::Unlabeled(Operation::Nop)]
-            } else {
-                vec![Instruction::Unlabeled(Operation::JumpI(after_true_label))]
-            }},
-            SymbolType::Bool(BoolValue::Temp(register)) | SymbolType::Int(IntValue::Temp(register)) => {
-                vec![Instruction::Unlabeled(Operation::Cbr(register, before_true_label, after_true_label))]
-            },
+            SymbolType::Bool(BoolValue::Literal(boolean)) => {
+                if boolean {
+                    vec![Instruction::Unlabeled(Operation::Nop)]
+                } else {
+                    vec![Instruction::Unlabeled(Operation::JumpI(after_true_label))]
+                }
+            }
+            SymbolType::Int(IntValue::Literal(number)) => {
+                if number != 0 {
+                    vec![Instruction::Unlabeled(Operation::Nop)]
+                } else {
+                    vec![Instruction::Unlabeled(Operation::JumpI(after_true_label))]
+                }
+            }
+            SymbolType::Bool(BoolValue::Temp(register))
+            | SymbolType::Int(IntValue::Temp(register)) => {
+                vec![Instruction::Unlabeled(Operation::Cbr(
+                    register,
+                    before_true_label,
+                    after_true_label,
+                ))]
+            }
             SymbolType::Int(IntValue::Memory(register, offset)) => {
                 let new_register = code.new_register();
-                vec![Instruction::Unlabeled(Operation::LoadAI(register, offset as i32, new_register)),
-                    Instruction::Unlabeled(Operation::Cbr(new_register, before_true_label, after_true_label))]
-            },
-            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("condition.evaluate_node() returned unsuported type for If.evaluate(): {:?}", condition_symbol)))
+                vec![
+                    Instruction::Unlabeled(Operation::LoadAI(
+                        register,
+                        offset as i32,
+                        new_register,
+                    )),
+                    Instruction::Unlabeled(Operation::Cbr(
+                        new_register,
+                        before_true_label,
+                        after_true_label,
+                    )),
+                ]
+            }
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!(
+                "condition.generate_node() returned unsuported type for If.generate_node(): {:?}",
+                condition_symbol
+            ))),
         };
         code.pay_promise(jump_if_true_voucher, promise_payment);
 
-        self.consequence.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.consequence, code, annotations, lexer)?;
         code.push_code(CodeLine::Deliver(Instruction::Labeled(after_true_label, Operation::Nop)));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::If
     }
 }
 
 #[derive(Debug)]
 pub struct IfElse {
     node_id: Span,
-    condition: Box<dyn AstNode>,
-    if_true: CommandBlock,
-    if_false: CommandBlock,
-    next: Option<Box<dyn AstNode>>,
+    condition: NodeId,
+    if_true: NodeId<CommandBlock>,
+    if_false: NodeId<CommandBlock>,
 }
 
 impl IfElse {
     pub fn new(
         node_id: Span,
-        condition: Box<dyn AstNode>,
-        if_true: CommandBlock,
-        if_false: CommandBlock,
-        next: Option<Box<dyn AstNode>>,
+        condition: NodeId,
+        if_true: NodeId<CommandBlock>,
+        if_false: NodeId<CommandBlock>,
     ) -> IfElse {
         IfElse {
             node_id,
             condition,
             if_true,
             if_false,
-            next,
         }
     }
-}
 
-impl AstNode for IfElse {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.condition.as_ref(), own_address);
-        self.if_true
-            .print_first_dependencies(print_dependencies_own, own_address);
-        self.if_false
-            .print_first_dependencies(print_dependencies_own, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.condition.as_ref(), own_address);
+    pub fn condition(&self) -> NodeId {
+        self.condition
+    }
+
+    pub fn if_true(&self) -> NodeId<CommandBlock> {
         self.if_true
-            .print_first_dependencies(print_dependencies_child, own_address);
+    }
+
+    pub fn if_false(&self) -> NodeId<CommandBlock> {
         self.if_false
-            .print_first_dependencies(print_dependencies_child, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.condition.as_ref(), lexer);
-        self.if_true.print_first_labels(print_labels_child, lexer);
-        self.if_false.print_first_labels(print_labels_child, lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
+}
+
+impl AstNode for IfElse {
+    fn check_node(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let condition_symbol =
+            arena
+                .check(self.condition, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "condition has no SymbolType (on IfElse.check_node())".to_string(),
+                ))?;
+        let condition_span = arena.node(self.condition).get_span();
+        let condition = check_condition(
+            &condition_symbol,
+            self.node_id,
+            condition_span,
+            stack,
+            lexer,
+        )?;
+
+        let flow = stack.flow();
+        arena.check(self.if_true, stack, lexer)?;
+        let if_true_flow = stack.flow();
+        stack.restore_flow(flow);
+        arena.check(self.if_false, stack, lexer)?;
+        match condition {
+            Some(true) => stack.restore_flow(if_true_flow),
+            Some(false) => (),
+            None => stack.join_flow(if_true_flow),
         }
+
+        Ok(None)
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn generate_node(
         &self,
+        arena: &AstArena,
         code: &mut IlocCode,
-        stack: &mut ScopeStack,
+        annotations: &Annotations,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        let condition_symbol =
-            self.condition
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "condition has no SymbolType (on IfElse.evaluate_node())"
-                )))?;
-        condition_symbol.to_bool(self.node_id, lexer)?;
+        let condition_symbol = arena
+            .generate(self.condition, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "condition has no SymbolType (on IfElse.generate_node())".to_string(),
+            ))?;
 
         let before_true_label = code.new_label();
         let between_true_and_false_label = code.new_label();
@@ -2490,51 +2804,45 @@ impl AstNode for IfElse {
                 vec![Instruction::Unlabeled(Operation::LoadAI(register, offset as i32, new_register)),
                     Instruction::Unlabeled(Operation::Cbr(new_register, before_true_label, between_true_and_false_label))]
             },
-            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("condition.evaluate_node() returned unsuported type for IfElse.evaluate(): {:?}", condition_symbol)))
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("condition.generate_node() returned unsuported type for IfElse.generate_node(): {:?}", condition_symbol)))
         };
         code.pay_promise(jump_if_true_voucher, promise_payment);
 
-        self.if_true.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.if_true, code, annotations, lexer)?;
 
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::JumpI(after_if_false_label))));
         code.push_code(CodeLine::Deliver(Instruction::Labeled(between_true_and_false_label, Operation::Nop)));
 
-        self.if_false.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.if_false, code, annotations, lexer)?;
 
         code.push_code(CodeLine::Deliver(Instruction::Labeled(after_if_false_label, Operation::Nop)));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::IfElse
     }
 }
 
 #[derive(Debug)]
 pub struct For {
     node_id: Span,
-    count_init: Box<dyn AstNode>,
-    count_check: Box<dyn AstNode>,
-    count_iter: Box<dyn AstNode>,
-    actions: CommandBlock,
-    next: Option<Box<dyn AstNode>>,
+    count_init: NodeId,
+    count_check: NodeId,
+    count_iter: NodeId,
+    actions: NodeId<CommandBlock>,
 }
 
 impl For {
     pub fn new(
         node_id: Span,
-        count_init: Box<dyn AstNode>,
-        count_check: Box<dyn AstNode>,
-        count_iter: Box<dyn AstNode>,
-        actions: CommandBlock,
-        next: Option<Box<dyn AstNode>>,
+        count_init: NodeId,
+        count_check: NodeId,
+        count_iter: NodeId,
+        actions: NodeId<CommandBlock>,
     ) -> For {
         For {
             node_id,
@@ -2542,63 +2850,75 @@ impl For {
             count_check,
             count_iter,
             actions,
-            next,
         }
     }
-}
 
-impl AstNode for For {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.count_init.as_ref(), own_address);
-        print_dependencies_own(self.count_check.as_ref(), own_address);
-        print_dependencies_own(self.count_iter.as_ref(), own_address);
-        self.actions
-            .print_first_dependencies(print_dependencies_own, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.count_init.as_ref(), own_address);
-        print_dependencies_child(self.count_check.as_ref(), own_address);
-        print_dependencies_child(self.count_iter.as_ref(), own_address);
-        self.actions
-            .print_first_dependencies(print_dependencies_child, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
+    pub fn count_init(&self) -> NodeId {
+        self.count_init
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.count_init.as_ref(), lexer);
-        print_labels_child(self.count_check.as_ref(), lexer);
-        print_labels_child(self.count_iter.as_ref(), lexer);
-        self.actions.print_first_labels(print_labels_child, lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
+
+    pub fn count_check(&self) -> NodeId {
+        self.count_check
     }
-    fn is_tree_member(&self) -> bool {
-        true
+
+    pub fn count_iter(&self) -> NodeId {
+        self.count_iter
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn actions(&self) -> NodeId<CommandBlock> {
+        self.actions
     }
-    fn evaluate_node(
+}
+
+impl AstNode for For {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        let _count_init_mem_loc = self.count_init.evaluate_node(code, stack, lexer)?;
+        arena.check(self.count_init, stack, lexer)?;
+
+        let count_check_symbol =
+            arena
+                .check(self.count_check, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "count_check has no SymbolType (on For.check_node())".to_string(),
+                ))?;
+        let condition = count_check_symbol.to_bool(self.node_id, lexer)?;
+
+        let flow = stack.flow();
+        stack.enter_loop();
+        arena.check(self.actions, stack, lexer)?;
+        let broken = stack.leave_loop();
+        arena.check(self.count_iter, stack, lexer)?;
+        stack.join_flow(flow);
+        if matches!(condition, BoolValue::Literal(true)) && !broken {
+            stack.end_path();
+        }
+
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let _count_init_mem_loc = arena.generate(self.count_init, code, annotations, lexer)?;
 
         let pre_check_label = code.new_label();
-        code.push_code(CodeLine::Deliver(Instruction::Labeled(pre_check_label, Operation::Nop)));
+        code.push_code(CodeLine::Deliver(Instruction::Labeled(
+            pre_check_label,
+            Operation::Nop,
+        )));
 
-        let count_check_symbol = self.count_check.evaluate_node(code, stack, lexer)?.ok_or(
-            CompilerError::SanityError(format!(
-                "count_check has no SymbolType (on For.evaluate_node())"
-            )),
-        )?;
-        count_check_symbol.to_bool(self.node_id, lexer)?;
+        let count_check_symbol = arena
+            .generate(self.count_check, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "count_check has no SymbolType (on For.generate_node())".to_string(),
+            ))?;
 
         let after_check_label = code.new_label();
         let escape_label = code.new_label();
@@ -2622,7 +2942,7 @@ impl AstNode for For {
                 vec![Instruction::Unlabeled(Operation::LoadAI(register, offset as i32, new_register)),
                     Instruction::Unlabeled(Operation::Cbr(new_register, after_check_label, escape_label))]
             },
-            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("count_check.evaluate_node() returned unsuported type for For.evaluate(): {:?}", count_check_symbol)))
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("count_check.generate_node() returned unsuported type for For.generate_node(): {:?}", count_check_symbol)))
         };
         for instruction in due_instructions {
             code.push_code(CodeLine::Deliver(instruction));
@@ -2630,97 +2950,100 @@ impl AstNode for For {
 
         code.push_code(CodeLine::Deliver(Instruction::Labeled(after_check_label, Operation::Nop)));
 
-        self.actions.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.actions, code, annotations, lexer)?;
 
-        let _count_iter_mem_loc = self.count_iter.evaluate_node(code, stack, lexer)?;
+        let _count_iter_mem_loc = arena.generate(self.count_iter, code, annotations, lexer)?;
 
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::JumpI(pre_check_label))));
         code.push_code(CodeLine::Deliver(Instruction::Labeled(escape_label, Operation::Nop)));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::For
     }
 }
 
 #[derive(Debug)]
 pub struct While {
     node_id: Span,
-    condition: Box<dyn AstNode>,
-    consequence: CommandBlock,
-    next: Option<Box<dyn AstNode>>,
+    condition: NodeId,
+    consequence: NodeId<CommandBlock>,
 }
 
 impl While {
-    pub fn new(
-        node_id: Span,
-        condition: Box<dyn AstNode>,
-        consequence: CommandBlock,
-        next: Option<Box<dyn AstNode>>,
-    ) -> While {
+    pub fn new(node_id: Span, condition: NodeId, consequence: NodeId<CommandBlock>) -> While {
         While {
             node_id,
             condition,
             consequence,
-            next,
         }
     }
-}
 
-impl AstNode for While {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.condition.as_ref(), own_address);
-        self.consequence
-            .print_first_dependencies(print_dependencies_own, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.condition.as_ref(), own_address);
-        self.consequence
-            .print_first_dependencies(print_dependencies_child, own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
+    pub fn condition(&self) -> NodeId {
+        self.condition
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.condition.as_ref(), lexer);
+
+    pub fn consequence(&self) -> NodeId<CommandBlock> {
         self.consequence
-            .print_first_labels(print_labels_child, lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
     }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+}
+
+impl AstNode for While {
+    fn check_node(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let condition_check_symbol =
+            arena
+                .check(self.condition, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "condition has no SymbolType (on While.check_node())".to_string(),
+                ))?;
+        let condition_span = arena.node(self.condition).get_span();
+        let condition = check_condition(
+            &condition_check_symbol,
+            self.node_id,
+            condition_span,
+            stack,
+            lexer,
+        )?;
+
+        let flow = stack.flow();
+        stack.enter_loop();
+        arena.check(self.consequence, stack, lexer)?;
+        let broken = stack.leave_loop();
+        stack.join_flow(flow);
+        // Without a break, only a return gets out of while (true).
+        if condition == Some(true) && !broken {
+            stack.end_path();
+        }
+
+        Ok(None)
     }
-    fn evaluate_node(
+    fn generate_node(
         &self,
+        arena: &AstArena,
         code: &mut IlocCode,
-        stack: &mut ScopeStack,
+        annotations: &Annotations,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let pre_check_label = code.new_label();
-        code.push_code(CodeLine::Deliver(Instruction::Labeled(pre_check_label, Operation::Nop)));
+        code.push_code(CodeLine::Deliver(Instruction::Labeled(
+            pre_check_label,
+            Operation::Nop,
+        )));
 
-        let condition_check_symbol =
-            self.condition
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "condition has no SymbolType (on While.evaluate_node())"
-                )))?;
-                condition_check_symbol.to_bool(self.node_id, lexer)?;
+        let condition_check_symbol = arena
+            .generate(self.condition, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "condition has no SymbolType (on While.generate_node())".to_string(),
+            ))?;
 
         let after_check_label = code.new_label();
         let escape_label = code.new_label();
@@ -2744,7 +3067,7 @@ impl AstNode for While {
                 vec![Instruction::Unlabeled(Operation::LoadAI(register, offset as i32, new_register)),
                     Instruction::Unlabeled(Operation::Cbr(new_register, after_check_label, escape_label))]
             },
-            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("count_check.evaluate_node() returned unsuported type for While.evaluate(): {:?}", condition_check_symbol)))
+            _ => return Err(CompilerError::IlocErrorUndefinedBehavior(format!("count_check.generate_node() returned unsuported type for While.generate_node(): {:?}", condition_check_symbol)))
         };
         for instruction in due_instructions {
             code.push_code(CodeLine::Deliver(instruction));
@@ -2752,131 +3075,126 @@ impl AstNode for While {
 
         code.push_code(CodeLine::Deliver(Instruction::Labeled(after_check_label, Operation::Nop)));
 
-        self.consequence.evaluate_node(code, stack, lexer)?;
+        arena.generate(self.consequence, code, annotations, lexer)?;
 
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::JumpI(pre_check_label))));
         code.push_code(CodeLine::Deliver(Instruction::Labeled(escape_label, Operation::Nop)));
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::While
     }
 }
 
 #[derive(Debug)]
 pub struct CommandBlock {
-    pub node_id: Span,
-    pub first_command: Option<Box<dyn AstNode>>,
-    pub next: Option<Box<dyn AstNode>>,
+    node_id: Span,
+    commands: Vec<NodeId>,
 }
 
 impl CommandBlock {
-    pub fn new(
-        node_id: Span,
-        first_command: Option<Box<dyn AstNode>>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> CommandBlock {
-        CommandBlock {
-            node_id,
-            first_command,
-            next,
-        }
+    pub fn new(node_id: Span, commands: Vec<NodeId>) -> CommandBlock {
+        CommandBlock { node_id, commands }
     }
-}
 
-impl AstNode for CommandBlock {
-    fn print_dependencies(&self, own_address: *const c_void, ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_ripple(next_node.as_ref(), own_address, ripple)
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        false
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    pub fn check_commands(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        // Only the first unreachable command is pointed out.
+        let mut after_return = false;
+        let mut warned = false;
+        for command in &self.commands {
+            if after_return && !warned {
+                let (start, end) = arena.extent(&[*command]);
+                let span = Span::new(start, end);
+                let ((line, col), (_, _)) = lexer.line_col(span);
+                let highlight = ScopeStack::form_string_highlight(span, lexer);
+                stack.push_warning(CompilerWarning::UnreachableCode {
+                    line,
+                    col,
+                    highlight,
+                });
+                warned = true;
+            }
+            // A const can't be written later, so it needs a value here.
+            if arena.kind(*command) == NodeKind::LocalVarDef {
+                let var_def = arena.get(command.cast::<LocalVarDef>());
+                if var_def.is_const() {
+                    let span = var_def.get_span();
+                    let ((line, col), (_, _)) = lexer.line_col(span);
+                    stack.report_error(CompilerError::SemanticErrorConstInit {
+                        id: lexer.span_str(span).to_string(),
+                        line,
+                        col,
+                        highlight: ScopeStack::form_string_highlight(span, lexer),
+                    })?;
+                }
+            }
+            arena.check(*command, stack, lexer)?;
+            if arena.kind(*command) == NodeKind::Return {
+                after_return = true;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn generate_commands(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        for command in &self.commands {
+            arena.generate(*command, code, annotations, lexer)?;
+        }
+        Ok(())
+    }
+
+    pub fn commands(&self) -> &[NodeId] {
+        &self.commands
+    }
+}
+
+impl AstNode for CommandBlock {
+    fn check_node(
+        &self,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        if let Some(command) = &self.first_command {
+        if !self.commands.is_empty() {
             stack.add_scope(None);
-            command.evaluate_node(code, stack, lexer)?;
-            stack.remove_scope()?;
+            self.check_commands(arena, stack, lexer)?;
+            let scope = stack.remove_scope()?;
+            stack.warn_unused(&scope, &[], lexer);
         };
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(None)
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        self.generate_commands(arena, code, annotations, lexer)?;
 
         Ok(None)
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
-    }
-}
-
-impl CommandBlock {
-    pub fn print_first_dependencies(
-        &self,
-        print_func: fn(&(dyn AstNode), *const c_void),
-        own_address: *const c_void,
-    ) {
-        let mut current_command = &self.first_command;
-        loop {
-            match current_command {
-                Some(command) => {
-                    if command.is_tree_member() {
-                        print_func(command.as_ref(), own_address);
-                        break;
-                    } else {
-                        current_command = command.get_next();
-                        continue;
-                    };
-                }
-                None => break,
-            }
-        }
-    }
-    pub fn print_first_labels(
-        &self,
-        print_func: fn(&(dyn AstNode), &dyn NonStreamingLexer<u32>),
-        lexer: &dyn NonStreamingLexer<u32>,
-    ) {
-        let mut current_command = &self.first_command;
-        loop {
-            match current_command {
-                Some(command) => {
-                    if command.is_tree_member() {
-                        print_func(command.as_ref(), lexer);
-                        break;
-                    } else {
-                        current_command = command.get_next();
-                        continue;
-                    };
-                }
-                None => break,
-            }
-        }
+    fn kind(&self) -> NodeKind {
+        NodeKind::CommandBlock
     }
 }
 
@@ -2884,20 +3202,18 @@ impl CommandBlock {
 pub struct Ternary {
     left_span: Span,
     right_span: Span,
-    condition: Box<dyn AstNode>,
-    if_true: Box<dyn AstNode>,
-    if_false: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    condition: NodeId,
+    if_true: NodeId,
+    if_false: NodeId,
 }
 
 impl Ternary {
     pub fn new(
         left_span: Span,
         right_span: Span,
-        condition: Box<dyn AstNode>,
-        if_true: Box<dyn AstNode>,
-        if_false: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
+        condition: NodeId,
+        if_true: NodeId,
+        if_false: NodeId,
     ) -> Ternary {
         Ternary {
             left_span,
@@ -2905,70 +3221,48 @@ impl Ternary {
             condition,
             if_true,
             if_false,
-            next,
         }
     }
 
-    fn print_label_ternary(&self, own_address: *const c_void) {
-        println!("{:p} [label=\"?:\"];", own_address);
+    pub fn condition(&self) -> NodeId {
+        self.condition
     }
-}
 
-impl AstNode for Ternary {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.condition.as_ref(), own_address);
-        print_dependencies_own(self.if_true.as_ref(), own_address);
-        print_dependencies_own(self.if_false.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.condition.as_ref(), own_address);
-        print_dependencies_child(self.if_true.as_ref(), own_address);
-        print_dependencies_child(self.if_false.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.print_label_ternary(own_address);
-        print_labels_child(self.condition.as_ref(), lexer);
-        print_labels_child(self.if_true.as_ref(), lexer);
-        print_labels_child(self.if_false.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
+    pub fn if_true(&self) -> NodeId {
+        self.if_true
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn if_false(&self) -> NodeId {
+        self.if_false
     }
-    fn evaluate_node(
+}
+
+impl AstNode for Ternary {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let condition_symbol =
-            self.condition
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "condition has no SymbolType (on Ternary.evaluate_node())"
-                )))?;
+            arena
+                .check(self.condition, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "condition has no SymbolType (on Ternary.check_node())".to_string(),
+                ))?;
         let if_true_symbol =
-            self.if_true
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "if_true has no SymbolType (on Ternary.evaluate_node())"
-                )))?;
+            arena
+                .check(self.if_true, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "if_true has no SymbolType (on Ternary.check_node())".to_string(),
+                ))?;
         let if_false_symbol =
-            self.if_false
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                    "if_false has no SymbolType (on Ternary.evaluate_node())"
-                )))?;
-        let return_symbol = match condition_symbol.to_bool(self.left_span, lexer)? {
+            arena
+                .check(self.if_false, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "if_false has no SymbolType (on Ternary.check_node())".to_string(),
+                ))?;
+        match condition_symbol.to_bool(self.left_span, lexer)? {
             BoolValue::Literal(truthy_value) => {
                 if truthy_value {
                     Ok(Some(if_true_symbol))
@@ -2981,45 +3275,88 @@ impl AstNode for Ternary {
                 self.right_span,
                 lexer,
             )?)),
-        };
-
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
-        return_symbol
+        }
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let condition_symbol = arena
+            .generate(self.condition, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "condition has no SymbolType (on Ternary.generate_node())".to_string(),
+            ))?;
+        let if_true_symbol = arena
+            .generate(self.if_true, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "if_true has no SymbolType (on Ternary.generate_node())".to_string(),
+            ))?;
+        let if_false_symbol = arena
+            .generate(self.if_false, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "if_false has no SymbolType (on Ternary.generate_node())".to_string(),
+            ))?;
+        match condition_symbol.to_bool(self.left_span, lexer)? {
+            BoolValue::Literal(truthy_value) => {
+                if truthy_value {
+                    Ok(Some(if_true_symbol))
+                } else {
+                    Ok(Some(if_false_symbol))
+                }
+            }
+            _ => Ok(Some(if_true_symbol.associate_with(
+                &if_false_symbol,
+                self.right_span,
+                lexer,
+            )?)),
+        }
     }
     fn get_span(&self) -> Span {
         self.left_span
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::Ternary
     }
 }
 
+// Integer division by zero halts the simulator without a trace, with
+// --checks=div the program traps instead.
+fn check_divisor(code: &mut IlocCode, divisor: Register) {
+    if !code.checks().div {
+        return;
+    }
+    let zero_register = code.new_register();
+    let is_zero_register = code.new_register();
+    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+        0,
+        zero_register,
+    ))));
+    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::CmpEQ(
+        divisor,
+        zero_register,
+        is_zero_register,
+    ))));
+    code.trap_if(is_zero_register, Trap::DivisionByZero);
+}
+
 #[derive(Debug)]
 pub struct Binary {
     node_id: Span,
     op_type: BinaryType,
-    lhs: Box<dyn AstNode>,
-    rhs: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    lhs: NodeId,
+    rhs: NodeId,
 }
 
 impl Binary {
-    pub fn new(
-        node_id: Span,
-        op_type: BinaryType,
-        lhs: Box<dyn AstNode>,
-        rhs: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> Binary {
+    pub fn new(node_id: Span, op_type: BinaryType, lhs: NodeId, rhs: NodeId) -> Binary {
         Binary {
             node_id,
             op_type,
             lhs,
             rhs,
-            next,
         }
     }
 
@@ -3030,21 +3367,22 @@ impl Binary {
         lexer: &dyn NonStreamingLexer<u32>,
         code: &mut IlocCode,
     ) -> Result<SymbolType, CompilerError> {
+        let unfolded = || SymbolType::unfolded_in_codegen("binary_evaluation()");
         match &self.op_type {
             BinaryType::BoolOr => match (
                 left_value.to_bool(self.node_id, lexer)?,
                 right_value.to_bool(self.node_id, lexer)?,
             ) {
                 (BoolValue::Undefined, _) | (_, BoolValue::Undefined) => Ok(SymbolType::Bool(BoolValue::Undefined)),
-                (BoolValue::Literal(left_value), BoolValue::Literal(right_value)) => {
-                    Ok(SymbolType::Bool(BoolValue::Literal(left_value || right_value)))
-                }
+                (BoolValue::Literal(_), BoolValue::Literal(_)) => Err(unfolded()),
                 (BoolValue::Temp(register), BoolValue::Literal(value)) | (BoolValue::Literal(value), BoolValue::Temp(register)) => {
                     if value {
-                        Ok(SymbolType::Bool(BoolValue::Literal(true)))
-                    } else {
-                        Ok(SymbolType::Bool(BoolValue::Temp(register)))
+                        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                            1,
+                            register,
+                        ))));
                     }
+                    Ok(SymbolType::Bool(BoolValue::Temp(register)))
                 }
                 (BoolValue::Temp(left_register), BoolValue::Temp(right_register)) => {
                     code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Or(
@@ -3059,15 +3397,15 @@ impl Binary {
                 right_value.to_bool(self.node_id, lexer)?,
             ) {
                 (BoolValue::Undefined, _) | (_, BoolValue::Undefined) => Ok(SymbolType::Bool(BoolValue::Undefined)),
-                (BoolValue::Literal(left_value), BoolValue::Literal(right_value)) => {
-                    Ok(SymbolType::Bool(BoolValue::Literal(left_value && right_value)))
-                }
+                (BoolValue::Literal(_), BoolValue::Literal(_)) => Err(unfolded()),
                 (BoolValue::Temp(register), BoolValue::Literal(value)) | (BoolValue::Literal(value), BoolValue::Temp(register)) => {
                     if !value {
-                        Ok(SymbolType::Bool(BoolValue::Literal(false)))
-                    } else {
-                        Ok(SymbolType::Bool(BoolValue::Temp(register)))
+                        code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                            0,
+                            register,
+                        ))));
                     }
+                    Ok(SymbolType::Bool(BoolValue::Temp(register)))
                 }
                 (BoolValue::Temp(left_register), BoolValue::Temp(right_register)) => {
                     code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::And(
@@ -3081,31 +3419,26 @@ impl Binary {
                 left_value.to_int(self.node_id, lexer)?,
                 right_value.to_int(self.node_id, lexer)?,
             ) {
-                (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                    Ok(SymbolType::Int(IntValue::Literal(left_value | right_value)))
-                }
+                (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                 (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
             },
             BinaryType::BitXor => match (
                 left_value.to_int(self.node_id, lexer)?,
                 right_value.to_int(self.node_id, lexer)?,
             ) {
-                (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                    Ok(SymbolType::Int(IntValue::Literal(left_value ^ right_value)))
-                }
+                (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                 (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
             },
             BinaryType::BitAnd => match (
                 left_value.to_int(self.node_id, lexer)?,
                 right_value.to_int(self.node_id, lexer)?,
             ) {
-                (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                    Ok(SymbolType::Int(IntValue::Literal(left_value & right_value)))
-                }
+                (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                 (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
             },
             BinaryType::Add => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => match (left_value, right_value) {
                         (SymbolType::String(left_maybe), SymbolType::String(right_maybe)) => {
                             match (left_maybe, right_maybe) {
@@ -3113,10 +3446,10 @@ impl Binary {
                                     Some(format!("{}{}", left_value, right_value)),
                                 )),
                                 (Some(left_value), None) => {
-                                    Ok(SymbolType::String(Some(format!("{}", left_value))))
+                                    Ok(SymbolType::String(Some(left_value.to_string())))
                                 }
                                 (None, Some(right_value)) => {
-                                    Ok(SymbolType::String(Some(format!("{}", right_value))))
+                                    Ok(SymbolType::String(Some(right_value.to_string())))
                                 }
                                 (None, None) => Ok(SymbolType::String(None)),
                             }
@@ -3142,9 +3475,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Int(IntValue::Literal(left_value + right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Int(IntValue::Undefined)),
                         }
                     }
@@ -3193,10 +3524,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Int(IntValue::Literal(left_value + right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -3241,15 +3569,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Float(Some(left_value + right_value)))
-                        }
-                        (_, _) => Ok(SymbolType::Float(None)),
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
+                        (_, _) => Ok(SymbolType::Float(FloatValue::Undefined)),
                     },
                 }
             }
             BinaryType::Sub => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3277,9 +3604,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Int(IntValue::Literal(left_value - right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Int(IntValue::Undefined)),
                         }
                     }
@@ -3374,10 +3699,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Int(IntValue::Literal(left_value - right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -3422,15 +3744,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Float(Some(left_value - right_value)))
-                        }
-                        (_, _) => Ok(SymbolType::Float(None)),
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
+                        (_, _) => Ok(SymbolType::Float(FloatValue::Undefined)),
                     },
                 }
             }
             BinaryType::Mult => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3458,9 +3779,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Int(IntValue::Literal(left_value * right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Int(IntValue::Undefined)),
                         }
                     },
@@ -3509,10 +3828,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Int(IntValue::Literal(left_value * right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -3557,15 +3873,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Float(Some(left_value * right_value)))
-                        }
-                        (_, _) => Ok(SymbolType::Float(None)),
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
+                        (_, _) => Ok(SymbolType::Float(FloatValue::Undefined)),
                     },
                 }
             }
             BinaryType::Div => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3593,13 +3908,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                if right_value == 0 {
-                                    Ok(SymbolType::Int(IntValue::Literal(0i32)))
-                                } else {
-                                    Ok(SymbolType::Int(IntValue::Literal(left_value / right_value)))
-                                }
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Int(IntValue::Undefined)),
                         }
                     }
@@ -3614,7 +3923,7 @@ impl Binary {
                             }
                             (IntValue::Temp(register), IntValue::Literal(other_value)) => {
                                 if other_value == 0 {
-                                    return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Division of expression by literal 0")))
+                                    return Err(CompilerError::IlocErrorUndefinedBehavior("Division of expression by literal 0".to_string()))
                                 }
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::DivI(
                                     register,
@@ -3629,6 +3938,7 @@ impl Binary {
                                     other_value,
                                     new_register,
                                 ))));
+                                check_divisor(code, register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     new_register,
                                     register,
@@ -3643,6 +3953,7 @@ impl Binary {
                                     offset as i32,
                                     new_register,
                                 ))));
+                                check_divisor(code, new_register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     register,
                                     new_register,
@@ -3657,6 +3968,7 @@ impl Binary {
                                     offset as i32,
                                     new_register,
                                 ))));
+                                check_divisor(code, register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     new_register,
                                     register,
@@ -3676,6 +3988,7 @@ impl Binary {
                                     offset as i32,
                                     other_register,
                                 ))));
+                                check_divisor(code, other_register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     lit_register,
                                     other_register,
@@ -3685,7 +3998,7 @@ impl Binary {
                             }
                             (IntValue::Memory(mem_register, offset), IntValue::Literal(value)) => {
                                 if value == 0 {
-                                    return Err(CompilerError::IlocErrorUndefinedBehavior(format!("Division of variable by literal 0")))
+                                    return Err(CompilerError::IlocErrorUndefinedBehavior("Division of variable by literal 0".to_string()))
                                 }
                                 let new_register = code.new_register();
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
@@ -3700,17 +4013,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => {
-                                if right_value == 0 {
-                                    Err(CompilerError::IlocErrorUndefinedBehavior(format!("Division by literal 0: \"{} / 0\"", left_value)))
-                                } else {
-                                    Ok(SymbolType::Int(IntValue::Literal(left_value / right_value)))
-                                }
-                                
-                            },
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -3727,6 +4030,7 @@ impl Binary {
                                     right_offset as i32,
                                     right_register,
                                 ))));
+                                check_divisor(code, right_register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     left_register,
                                     right_register,
@@ -3738,6 +4042,7 @@ impl Binary {
                                 IntValue::Temp(left_register),
                                 IntValue::Temp(right_register),
                             ) => {
+                                check_divisor(code, right_register);
                                 code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Div(
                                     left_register,
                                     right_register,
@@ -3755,19 +4060,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            if right_value == 0.0 {
-                                Ok(SymbolType::Float(Some(0f64)))
-                            } else {
-                                Ok(SymbolType::Float(Some(left_value / right_value)))
-                            }
-                        }
-                        (_, _) => Ok(SymbolType::Float(None)),
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
+                        (_, _) => Ok(SymbolType::Float(FloatValue::Undefined)),
                     },
                 }
             }
             BinaryType::Mod => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3795,13 +4095,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                if right_value == 0 {
-                                    Ok(SymbolType::Int(IntValue::Literal(0i32)))
-                                } else {
-                                    Ok(SymbolType::Int(IntValue::Literal(left_value % right_value)))
-                                }
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Int(IntValue::Undefined)),
                         }
                     }
@@ -3809,19 +4103,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            if right_value == 0.0 {
-                                Ok(SymbolType::Float(Some(0f64)))
-                            } else {
-                                Ok(SymbolType::Float(Some(left_value % right_value)))
-                            }
-                        }
-                        (_, _) => Ok(SymbolType::Float(None)),
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
+                        (_, _) => Ok(SymbolType::Float(FloatValue::Undefined)),
                     },
                 }
             }
             BinaryType::Equal => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3849,9 +4138,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value == right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -3910,10 +4197,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value == right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -3958,15 +4242,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value == right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
             BinaryType::NotEqual => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -3994,9 +4277,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value != right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -4055,10 +4336,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(new_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value != right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -4103,15 +4381,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value != right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
             BinaryType::Lesser => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -4139,9 +4416,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value < right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -4246,10 +4521,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(left_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value < right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -4294,15 +4566,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value < right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
             BinaryType::Greater => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -4330,9 +4601,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value > right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -4437,10 +4706,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(left_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value > right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -4485,15 +4751,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value > right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
             BinaryType::LesserEqual => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -4521,9 +4786,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value <= right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -4628,10 +4891,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(left_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value <= right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -4676,15 +4936,14 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value <= right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
             BinaryType::GreaterEqual => {
                 match left_value.associate_with(&right_value, self.node_id, lexer)? {
+                    SymbolType::Error => Err(SymbolType::error_in_codegen("binary_evaluation()")),
                     SymbolType::String(_) => {
                         let invalid_type = "int or float".to_string();
                         let ((line, col), (_, _)) = lexer.line_col(self.node_id);
@@ -4808,10 +5067,7 @@ impl Binary {
                                 ))));
                                 Ok(SymbolType::Int(IntValue::Temp(left_register)))
                             }
-                            (
-                                IntValue::Literal(left_value),
-                                IntValue::Literal(right_value),
-                            ) => Ok(SymbolType::Bool(BoolValue::Literal(left_value >= right_value))),
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (
                                 IntValue::Memory(mem_left_register, left_offset),
                                 IntValue::Memory(mem_right_register, right_offset),
@@ -4857,9 +5113,7 @@ impl Binary {
                             left_value.to_int(self.node_id, lexer)?,
                             right_value.to_int(self.node_id, lexer)?,
                         ) {
-                            (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
-                                Ok(SymbolType::Bool(BoolValue::Literal(left_value >= right_value)))
-                            }
+                            (IntValue::Literal(_), IntValue::Literal(_)) => Err(unfolded()),
                             (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                         }
                     }
@@ -4867,93 +5121,327 @@ impl Binary {
                         left_value.to_float(self.node_id, lexer)?,
                         right_value.to_float(self.node_id, lexer)?,
                     ) {
-                        (Some(left_value), Some(right_value)) => {
-                            Ok(SymbolType::Bool(BoolValue::Literal(left_value >= right_value)))
-                        }
+                        (FloatValue::Literal(_), FloatValue::Literal(_)) => Err(unfolded()),
                         (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
                     },
                 }
             }
         }
     }
-}
 
-impl AstNode for Binary {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.lhs.as_ref(), own_address);
-        print_dependencies_own(self.rhs.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
+    // Mirrors binary_evaluation() without emitting code. Literals still fold, so
+    // constant conditions and shift amounts are known, but anything that would
+    // live in a register comes back undefined.
+    fn binary_check(
+        &self,
+        left_value: SymbolType,
+        right_value: SymbolType,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<SymbolType, CompilerError> {
+        let span = self.node_id;
+        if let (SymbolType::Error, _) | (_, SymbolType::Error) = (&left_value, &right_value) {
+            return Ok(SymbolType::Error);
         }
-        print_dependencies_child(self.lhs.as_ref(), own_address);
-        print_dependencies_child(self.rhs.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
+        let divides = matches!(&self.op_type, BinaryType::Div | BinaryType::Mod);
+        match &self.op_type {
+            BinaryType::BoolOr | BinaryType::BoolAnd => {
+                match (
+                    left_value.to_bool(span, lexer)?,
+                    right_value.to_bool(span, lexer)?,
+                ) {
+                    (BoolValue::Literal(left_value), BoolValue::Literal(right_value)) => {
+                        Ok(SymbolType::Bool(BoolValue::Literal(match &self.op_type {
+                            BinaryType::BoolOr => left_value || right_value,
+                            _ => left_value && right_value,
+                        })))
+                    }
+                    (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
+                }
+            }
+            BinaryType::BitOr | BinaryType::BitXor | BinaryType::BitAnd => {
+                match (
+                    left_value.to_int(span, lexer)?,
+                    right_value.to_int(span, lexer)?,
+                ) {
+                    (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
+                        Ok(SymbolType::Int(IntValue::Literal(match &self.op_type {
+                            BinaryType::BitOr => left_value | right_value,
+                            BinaryType::BitXor => left_value ^ right_value,
+                            _ => left_value & right_value,
+                        })))
+                    }
+                    (_, _) => Ok(SymbolType::Bool(BoolValue::Undefined)),
+                }
+            }
+            _ => match left_value.associate_with(&right_value, span, lexer)? {
+                SymbolType::Error => Ok(SymbolType::Error),
+                SymbolType::String(_) => match (&self.op_type, left_value, right_value) {
+                    (
+                        BinaryType::Add,
+                        SymbolType::String(left_maybe),
+                        SymbolType::String(right_maybe),
+                    ) => Ok(SymbolType::String(match (left_maybe, right_maybe) {
+                        (Some(left_value), Some(right_value)) => {
+                            Some(format!("{}{}", left_value, right_value))
+                        }
+                        (Some(value), None) | (None, Some(value)) => Some(value),
+                        (None, None) => None,
+                    })),
+                    (_, _, _) => {
+                        let invalid_type = "int or float".to_string();
+                        let ((line, col), (_, _)) = lexer.line_col(span);
+                        let highlight = ScopeStack::form_string_highlight(span, lexer);
+                        Err(CompilerError::SemanticErrorStringToX {
+                            invalid_type,
+                            line,
+                            col,
+                            highlight,
+                        })
+                    }
+                },
+                SymbolType::Char(_) => {
+                    let invalid_type = "int or float".to_string();
+                    let ((line, col), (_, _)) = lexer.line_col(span);
+                    let highlight = ScopeStack::form_string_highlight(span, lexer);
+                    Err(CompilerError::SemanticErrorCharToX {
+                        invalid_type,
+                        line,
+                        col,
+                        highlight,
+                    })
+                }
+                association @ SymbolType::Bool(_) | association @ SymbolType::Int(_) => {
+                    let is_bool = matches!(association, SymbolType::Bool(_));
+                    if divides && matches!(right_value.to_int(span, lexer)?, IntValue::Literal(0)) {
+                        return Err(self.division_by_zero(lexer));
+                    }
+                    let (left_value, right_value) = match (
+                        left_value.to_int(span, lexer)?,
+                        right_value.to_int(span, lexer)?,
+                    ) {
+                        (IntValue::Literal(left_value), IntValue::Literal(right_value)) => {
+                            (left_value, right_value)
+                        }
+                        (_, _) => {
+                            return Ok(match &self.op_type {
+                                BinaryType::Add
+                                | BinaryType::Sub
+                                | BinaryType::Mult
+                                | BinaryType::Div
+                                | BinaryType::Mod => SymbolType::Int(IntValue::Undefined),
+                                _ if is_bool => SymbolType::Bool(BoolValue::Undefined),
+                                _ => SymbolType::Int(IntValue::Undefined),
+                            })
+                        }
+                    };
+                    let folded = match &self.op_type {
+                        BinaryType::Add => Some((
+                            left_value.checked_add(right_value),
+                            left_value.wrapping_add(right_value),
+                        )),
+                        BinaryType::Sub => Some((
+                            left_value.checked_sub(right_value),
+                            left_value.wrapping_sub(right_value),
+                        )),
+                        BinaryType::Mult => Some((
+                            left_value.checked_mul(right_value),
+                            left_value.wrapping_mul(right_value),
+                        )),
+                        BinaryType::Div => Some((
+                            left_value.checked_div(right_value),
+                            left_value.wrapping_div(right_value),
+                        )),
+                        BinaryType::Mod => Some((
+                            left_value.checked_rem(right_value),
+                            left_value.wrapping_rem(right_value),
+                        )),
+                        _ => None,
+                    };
+                    if let Some((checked, wrapped)) = folded {
+                        let value = stack.fold_int(checked, wrapped, span, lexer)?;
+                        return Ok(SymbolType::Int(IntValue::Literal(value)));
+                    }
+                    Ok(match &self.op_type {
+                        BinaryType::Equal => {
+                            SymbolType::Bool(BoolValue::Literal(left_value == right_value))
+                        }
+                        BinaryType::NotEqual => {
+                            SymbolType::Bool(BoolValue::Literal(left_value != right_value))
+                        }
+                        BinaryType::Lesser => {
+                            SymbolType::Bool(BoolValue::Literal(left_value < right_value))
+                        }
+                        BinaryType::Greater => {
+                            SymbolType::Bool(BoolValue::Literal(left_value > right_value))
+                        }
+                        BinaryType::LesserEqual => {
+                            SymbolType::Bool(BoolValue::Literal(left_value <= right_value))
+                        }
+                        _ => SymbolType::Bool(BoolValue::Literal(left_value >= right_value)),
+                    })
+                }
+                SymbolType::Float(_) => {
+                    let zero_divisor = matches!(
+                        right_value.to_float(span, lexer)?,
+                        FloatValue::Literal(value) if value == 0.0
+                    );
+                    if divides && zero_divisor {
+                        return Err(self.division_by_zero(lexer));
+                    }
+                    let (left_value, right_value) = match (
+                        left_value.to_float(span, lexer)?,
+                        right_value.to_float(span, lexer)?,
+                    ) {
+                        (FloatValue::Literal(left_value), FloatValue::Literal(right_value)) => {
+                            (left_value, right_value)
+                        }
+                        (_, _) => {
+                            return Ok(match &self.op_type {
+                                BinaryType::Add
+                                | BinaryType::Sub
+                                | BinaryType::Mult
+                                | BinaryType::Div
+                                | BinaryType::Mod => SymbolType::Float(FloatValue::Undefined),
+                                _ => SymbolType::Bool(BoolValue::Undefined),
+                            })
+                        }
+                    };
+                    Ok(match &self.op_type {
+                        BinaryType::Add => {
+                            SymbolType::Float(FloatValue::Literal(left_value + right_value))
+                        }
+                        BinaryType::Sub => {
+                            SymbolType::Float(FloatValue::Literal(left_value - right_value))
+                        }
+                        BinaryType::Mult => {
+                            SymbolType::Float(FloatValue::Literal(left_value * right_value))
+                        }
+                        BinaryType::Div => {
+                            SymbolType::Float(FloatValue::Literal(left_value / right_value))
+                        }
+                        BinaryType::Mod => {
+                            SymbolType::Float(FloatValue::Literal(left_value % right_value))
+                        }
+                        BinaryType::Equal => {
+                            SymbolType::Bool(BoolValue::Literal(left_value == right_value))
+                        }
+                        BinaryType::NotEqual => {
+                            SymbolType::Bool(BoolValue::Literal(left_value != right_value))
+                        }
+                        BinaryType::Lesser => {
+                            SymbolType::Bool(BoolValue::Literal(left_value < right_value))
+                        }
+                        BinaryType::Greater => {
+                            SymbolType::Bool(BoolValue::Literal(left_value > right_value))
+                        }
+                        BinaryType::LesserEqual => {
+                            SymbolType::Bool(BoolValue::Literal(left_value <= right_value))
+                        }
+                        _ => SymbolType::Bool(BoolValue::Literal(left_value >= right_value)),
+                    })
+                }
+            },
         }
     }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.lhs.as_ref(), lexer);
-        print_labels_child(self.rhs.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
+
+    fn division_by_zero(&self, lexer: &dyn NonStreamingLexer<u32>) -> CompilerError {
+        let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+        CompilerError::SemanticErrorDivisionByZero {
+            line,
+            col,
+            highlight: ScopeStack::form_string_highlight(self.node_id, lexer),
         }
     }
-    fn is_tree_member(&self) -> bool {
-        true
+
+    pub fn lhs(&self) -> NodeId {
+        self.lhs
+    }
+
+    pub fn rhs(&self) -> NodeId {
+        self.rhs
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn op_type(&self) -> &BinaryType {
+        &self.op_type
     }
-    fn evaluate_node(
+}
+
+impl AstNode for Binary {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        let left_value_type = match self.lhs.evaluate_node(code, stack, lexer)? {
+        let left_value_type = match arena.check(self.lhs, stack, lexer)? {
             Some(value) => value,
             None => {
                 return Err(CompilerError::SanityError(format!(
-                    "lhs.evaluate_node() returned None for Binary of type {:?}",
+                    "lhs.check_node() returned None for Binary of type {:?}",
                     self.op_type
                 )))
             }
         };
 
-        let right_value_type = match self.rhs.evaluate_node(code, stack, lexer)? {
+        let right_value_type = match arena.check(self.rhs, stack, lexer)? {
             Some(value) => value,
             None => {
                 return Err(CompilerError::SanityError(format!(
-                    "rhs.evaluate_node() returned None for Binary of type {:?}",
+                    "rhs.check_node() returned None for Binary of type {:?}",
                     self.op_type
                 )))
             }
         };
 
-        let return_value = Ok(Some(self.binary_evaluation(
+        Ok(Some(self.binary_check(
             left_value_type,
             right_value_type,
+            stack,
             lexer,
-            code,
-        )?));
-
-        if let Some(node) = &self.next {
-            return Err(CompilerError::SanityError(format!(
-                "Binary {:?} has a self.next node: {:?}",
-                self.op_type, node
-            )));
-        };
-
-        return_value
-    }
-    fn get_span(&self) -> Span {
-        self.node_id
+        )?))
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
-    }
-}
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let left_value_type = match arena.generate(self.lhs, code, annotations, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SanityError(format!(
+                    "lhs.generate_node() returned None for Binary of type {:?}",
+                    self.op_type
+                )))
+            }
+        };
+
+        let right_value_type = match arena.generate(self.rhs, code, annotations, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SanityError(format!(
+                    "rhs.generate_node() returned None for Binary of type {:?}",
+                    self.op_type
+                )))
+            }
+        };
+
+        Ok(Some(self.binary_evaluation(
+            left_value_type,
+            right_value_type,
+            lexer,
+            code,
+        )?))
+    }
+    fn get_span(&self) -> Span {
+        self.node_id
+    }
+    fn kind(&self) -> NodeKind {
+        NodeKind::Binary
+    }
+}
 
 #[derive(Debug)]
 pub enum BinaryType {
@@ -4979,22 +5467,15 @@ pub enum BinaryType {
 pub struct Unary {
     node_id: Span,
     op_type: UnaryType,
-    operand: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    operand: NodeId,
 }
 
 impl Unary {
-    pub fn new(
-        node_id: Span,
-        op_type: UnaryType,
-        operand: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> Unary {
+    pub fn new(node_id: Span, op_type: UnaryType, operand: NodeId) -> Unary {
         Unary {
             node_id,
             op_type,
             operand,
-            next,
         }
     }
     fn unary_evaluation(
@@ -5003,24 +5484,21 @@ impl Unary {
         lexer: &dyn NonStreamingLexer<u32>,
         code: &mut IlocCode,
     ) -> Result<SymbolType, CompilerError> {
+        let unfolded = || SymbolType::unfolded_in_codegen("unary_evaluation()");
         match &self.op_type {
             UnaryType::Positive => match type_value {
+                SymbolType::Error => Err(SymbolType::error_in_codegen("unary_evaluation()")),
                 SymbolType::Int(IntValue::Undefined) => {
-                    Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                        "Unary operation Positive matched with undefined Int."
-                    )))
-                }
-                symbol @ SymbolType::Int(_) => {
-                    Ok(symbol)
+                    Err(CompilerError::IlocErrorUndefinedBehavior(
+                        "Unary operation Positive matched with undefined Int.".to_string(),
+                    ))
                 }
+                symbol @ SymbolType::Int(_) => Ok(symbol),
                 symbol @ SymbolType::Float(_) => Ok(symbol),
-                SymbolType::Bool(BoolValue::Literal(value)) => {
-                    match value {
-                        true => Ok(SymbolType::Int(IntValue::Literal(1))),
-                        false => Ok(SymbolType::Int(IntValue::Literal(0))),
-                    }
-                },
-                SymbolType::Bool(BoolValue::Temp(register)) => Ok(SymbolType::Int(IntValue::Temp(register))),
+                SymbolType::Bool(BoolValue::Literal(_)) => Err(unfolded()),
+                SymbolType::Bool(BoolValue::Temp(register)) => {
+                    Ok(SymbolType::Int(IntValue::Temp(register)))
+                }
                 SymbolType::Bool(BoolValue::Undefined) => Ok(SymbolType::Int(IntValue::Undefined)),
                 SymbolType::Char(_) => {
                     let invalid_type = "int or float".to_string();
@@ -5046,14 +5524,13 @@ impl Unary {
                 }
             },
             UnaryType::Negative => match type_value {
+                SymbolType::Error => Err(SymbolType::error_in_codegen("unary_evaluation()")),
                 SymbolType::Int(IntValue::Undefined) => {
-                    Err(CompilerError::IlocErrorUndefinedBehavior(format!(
-                        "Unary operation Negative matched with undefined Int."
-                    )))
-                }
-                SymbolType::Int(IntValue::Literal(number)) => {
-                    Ok(SymbolType::Int(IntValue::Literal(-number)))
+                    Err(CompilerError::IlocErrorUndefinedBehavior(
+                        "Unary operation Negative matched with undefined Int.".to_string(),
+                    ))
                 }
+                SymbolType::Int(IntValue::Literal(_)) => Err(unfolded()),
                 SymbolType::Int(IntValue::Temp(register)) => {
                     code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::MultI(
                         register,
@@ -5078,10 +5555,7 @@ impl Unary {
                 }
                 symbol @ SymbolType::Float(_) => Ok(symbol),
                 SymbolType::Bool(maybe_value) => match &maybe_value {
-                    BoolValue::Literal(value) => match value {
-                        true => Ok(SymbolType::Int(IntValue::Literal(-1))),
-                        false => Ok(SymbolType::Int(IntValue::Literal(0))),
-                    },
+                    BoolValue::Literal(_) => Err(unfolded()),
                     BoolValue::Temp(register) => {
                         let register_copy = *register;
                         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::MultI(
@@ -5117,31 +5591,17 @@ impl Unary {
                 }
             },
             UnaryType::Not => match type_value {
+                SymbolType::Error => Err(SymbolType::error_in_codegen("unary_evaluation()")),
                 SymbolType::Int(maybe_value) => match &maybe_value {
-                    IntValue::Literal(value) => {
-                        if *value == 0i32 {
-                            Ok(SymbolType::Bool(BoolValue::Literal(true)))
-                        } else {
-                            Ok(SymbolType::Bool(BoolValue::Literal(false)))
-                        }
-                    }
+                    IntValue::Literal(_) => Err(unfolded()),
                     _ => Ok(SymbolType::Bool(BoolValue::Undefined)),
                 },
                 SymbolType::Float(maybe_value) => match &maybe_value {
-                    Some(value) => {
-                        if *value == 0f64 {
-                            Ok(SymbolType::Bool(BoolValue::Literal(true)))
-                        } else {
-                            Ok(SymbolType::Bool(BoolValue::Literal(false)))
-                        }
-                    }
-                    None => Ok(SymbolType::Bool(BoolValue::Undefined)),
+                    FloatValue::Literal(_) => Err(unfolded()),
+                    _ => Ok(SymbolType::Bool(BoolValue::Undefined)),
                 },
                 SymbolType::Bool(maybe_value) => match &maybe_value {
-                    BoolValue::Literal(value) => match value {
-                        true => Ok(SymbolType::Bool(BoolValue::Literal(false))),
-                        false => Ok(SymbolType::Bool(BoolValue::Literal(true))),
-                    },
+                    BoolValue::Literal(_) => Err(unfolded()),
                     BoolValue::Temp(register) => {
                         let register_copy = *register;
                         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::Not(
@@ -5175,25 +5635,14 @@ impl Unary {
                 }
             },
             UnaryType::Boolean => match type_value {
+                SymbolType::Error => Err(SymbolType::error_in_codegen("unary_evaluation()")),
                 SymbolType::Int(maybe_value) => match &maybe_value {
-                    IntValue::Literal(value) => {
-                        if *value == 0i32 {
-                            Ok(SymbolType::Bool(BoolValue::Literal(false)))
-                        } else {
-                            Ok(SymbolType::Bool(BoolValue::Literal(true)))
-                        }
-                    }
+                    IntValue::Literal(_) => Err(unfolded()),
                     _ => Ok(SymbolType::Bool(BoolValue::Undefined)),
                 },
                 SymbolType::Float(maybe_value) => match &maybe_value {
-                    Some(value) => {
-                        if *value == 0f64 {
-                            Ok(SymbolType::Bool(BoolValue::Literal(false)))
-                        } else {
-                            Ok(SymbolType::Bool(BoolValue::Literal(true)))
-                        }
-                    }
-                    None => Ok(SymbolType::Bool(BoolValue::Undefined)),
+                    FloatValue::Literal(_) => Err(unfolded()),
+                    _ => Ok(SymbolType::Bool(BoolValue::Undefined)),
                 },
                 symbol @ SymbolType::Bool(_) => Ok(symbol),
                 SymbolType::Char(_) => {
@@ -5224,46 +5673,114 @@ impl Unary {
             UnaryType::Pointer => Ok(type_value),
         }
     }
-}
 
-impl AstNode for Unary {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.operand.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.operand.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        print_labels_child(self.operand.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
+    // Mirrors unary_evaluation() without emitting code. An undefined int is fine
+    // here, it only means the operand isn't known before run time.
+    fn unary_check(
+        &self,
+        type_value: SymbolType,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<SymbolType, CompilerError> {
+        let invalid_type = match &self.op_type {
+            UnaryType::Positive | UnaryType::Negative => "int or float",
+            _ => "bool",
+        };
+        match (&self.op_type, type_value) {
+            (_, SymbolType::Error) => Ok(SymbolType::Error),
+            (UnaryType::Hash, _) => Ok(SymbolType::Int(IntValue::Undefined)),
+            (UnaryType::Address, type_value) | (UnaryType::Pointer, type_value) => Ok(type_value),
+            (_, SymbolType::Char(_)) => {
+                let invalid_type = invalid_type.to_string();
+                let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+                let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+                Err(CompilerError::SemanticErrorCharToX {
+                    invalid_type,
+                    line,
+                    col,
+                    highlight,
+                })
+            }
+            (_, SymbolType::String(_)) => {
+                let invalid_type = invalid_type.to_string();
+                let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+                let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+                Err(CompilerError::SemanticErrorStringToX {
+                    invalid_type,
+                    line,
+                    col,
+                    highlight,
+                })
+            }
+            (UnaryType::Positive, symbol @ SymbolType::Int(_))
+            | (UnaryType::Positive, symbol @ SymbolType::Float(_))
+            | (UnaryType::Negative, symbol @ SymbolType::Float(_))
+            | (UnaryType::Boolean, symbol @ SymbolType::Bool(_)) => Ok(symbol),
+            (UnaryType::Negative, SymbolType::Int(IntValue::Literal(number))) => {
+                let value = stack.fold_int(
+                    number.checked_neg(),
+                    number.wrapping_neg(),
+                    self.node_id,
+                    lexer,
+                )?;
+                Ok(SymbolType::Int(IntValue::Literal(value)))
+            }
+            (UnaryType::Positive, SymbolType::Bool(BoolValue::Literal(value))) => {
+                Ok(SymbolType::Int(IntValue::Literal(value as i32)))
+            }
+            (UnaryType::Negative, SymbolType::Bool(BoolValue::Literal(value))) => {
+                Ok(SymbolType::Int(IntValue::Literal(-(value as i32))))
+            }
+            (UnaryType::Positive, _) | (UnaryType::Negative, _) => {
+                Ok(SymbolType::Int(IntValue::Undefined))
+            }
+            (op_type, type_value) => match type_value.to_bool(self.node_id, lexer)? {
+                BoolValue::Literal(value) => Ok(SymbolType::Bool(BoolValue::Literal(
+                    value != matches!(op_type, UnaryType::Not),
+                ))),
+                _ => Ok(SymbolType::Bool(BoolValue::Undefined)),
+            },
         }
     }
-    fn is_tree_member(&self) -> bool {
-        true
+
+    pub fn operand(&self) -> NodeId {
+        self.operand
     }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
+
+    pub fn op_type(&self) -> &UnaryType {
+        &self.op_type
     }
-    fn evaluate_node(
+}
+
+impl AstNode for Unary {
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        if let Some(node) = &self.next {
-            return Err(CompilerError::LexicalError(format!(
-                "Unary struct holds a reference to a next node: {:?}",
-                node
-            )));
+        let type_value = match arena.check(self.operand, stack, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SemanticError(format!(
+                    "Unary struct operand returned no value on evaluation: {:?}",
+                    self.operand
+                )))
+            }
         };
 
-        let type_value = match self.operand.evaluate_node(code, stack, lexer)? {
+        let type_value = self.unary_check(type_value, stack, lexer)?;
+
+        Ok(Some(type_value))
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let type_value = match arena.generate(self.operand, code, annotations, lexer)? {
             Some(value) => value,
             None => {
                 return Err(CompilerError::SemanticError(format!(
@@ -5275,20 +5792,13 @@ impl AstNode for Unary {
 
         let type_value = self.unary_evaluation(type_value.clone(), lexer, code)?;
 
-        if let Some(node) = &self.next {
-            return Err(CompilerError::SanityError(format!(
-                "Unary {:?} has a self.next node: {:?}",
-                self.op_type, node
-            )));
-        };
-
         Ok(Some(type_value))
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::Unary
     }
 }
 
@@ -5303,80 +5813,341 @@ pub enum UnaryType {
     Pointer,
 }
 
+#[derive(Debug)]
+pub struct Cast {
+    node_id: Span,
+    cast_type: Span,
+    operand: NodeId,
+}
+
+impl Cast {
+    pub fn new(node_id: Span, cast_type: Span, operand: NodeId) -> Cast {
+        Cast {
+            node_id,
+            cast_type,
+            operand,
+        }
+    }
+    fn load_register(&self, value: IntValue, code: &mut IlocCode) -> Option<Register> {
+        match value {
+            IntValue::Temp(register) => Some(register),
+            IntValue::Memory(offset_source, offset) => {
+                let register = code.new_register();
+                code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadAI(
+                    offset_source,
+                    offset as i32,
+                    register,
+                ))));
+                Some(register)
+            }
+            _ => None,
+        }
+    }
+    // Mirrors cast_evaluation() without emitting code, and is where redundant
+    // casts get reported.
+    fn cast_check(
+        &self,
+        type_value: SymbolType,
+        target_type: SymbolType,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<SymbolType, CompilerError> {
+        match (type_value, target_type) {
+            (SymbolType::Error, _) | (_, SymbolType::Error) => Ok(SymbolType::Error),
+            (symbol @ SymbolType::Int(_), SymbolType::Int(_))
+            | (symbol @ SymbolType::Float(_), SymbolType::Float(_))
+            | (symbol @ SymbolType::Char(_), SymbolType::Char(_)) => {
+                let cast_type = symbol.to_str().to_string();
+                let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+                let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+                stack.push_warning(CompilerWarning::RedundantCast {
+                    cast_type,
+                    line,
+                    col,
+                    highlight,
+                });
+                Ok(symbol)
+            }
+            (SymbolType::Int(value), SymbolType::Char(_)) => match value {
+                IntValue::Literal(value) => Ok(SymbolType::Char(CharValue::Literal(value as u8))),
+                _ => Ok(SymbolType::Char(CharValue::Undefined)),
+            },
+            (SymbolType::Int(value), SymbolType::Float(_)) => match value {
+                IntValue::Literal(value) => {
+                    Ok(SymbolType::Float(FloatValue::Literal(value as f64)))
+                }
+                _ => Ok(SymbolType::Float(FloatValue::Undefined)),
+            },
+            (SymbolType::Char(value), SymbolType::Int(_)) => match value {
+                CharValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                _ => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (SymbolType::Float(value), SymbolType::Int(_)) => match value {
+                FloatValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                _ => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (SymbolType::Bool(value), SymbolType::Int(_)) => match value {
+                BoolValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                _ => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (from_type, to_type) => {
+                let from_type = from_type.to_str().to_string();
+                let to_type = to_type.to_str().to_string();
+                let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+                let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+                Err(CompilerError::SemanticErrorInvalidCast {
+                    from_type,
+                    to_type,
+                    line,
+                    col,
+                    highlight,
+                })
+            }
+        }
+    }
+
+    fn cast_evaluation(
+        &self,
+        type_value: SymbolType,
+        target_type: SymbolType,
+        lexer: &dyn NonStreamingLexer<u32>,
+        code: &mut IlocCode,
+    ) -> Result<SymbolType, CompilerError> {
+        match (type_value, target_type) {
+            (symbol @ SymbolType::Int(_), SymbolType::Int(_))
+            | (symbol @ SymbolType::Float(_), SymbolType::Float(_))
+            | (symbol @ SymbolType::Char(_), SymbolType::Char(_)) => Ok(symbol),
+            (SymbolType::Int(IntValue::Literal(value)), SymbolType::Char(_)) => {
+                Ok(SymbolType::Char(CharValue::Literal(value as u8)))
+            }
+            (SymbolType::Int(value), SymbolType::Char(_)) => match self.load_register(value, code) {
+                Some(register) => {
+                    let char_register = code.new_register();
+                    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::I2c(
+                        register,
+                        char_register,
+                    ))));
+                    Ok(SymbolType::Char(CharValue::Temp(char_register)))
+                }
+                None => Ok(SymbolType::Char(CharValue::Undefined)),
+            },
+            (SymbolType::Int(IntValue::Literal(value)), SymbolType::Float(_)) => {
+                Ok(SymbolType::Float(FloatValue::Literal(value as f64)))
+            }
+            (SymbolType::Int(value), SymbolType::Float(_)) => match self.load_register(value, code) {
+                Some(register) => {
+                    let float_register = code.new_register();
+                    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::I2f(
+                        register,
+                        float_register,
+                    ))));
+                    Ok(SymbolType::Float(FloatValue::Temp(float_register)))
+                }
+                None => Ok(SymbolType::Float(FloatValue::Undefined)),
+            },
+            (SymbolType::Char(value), SymbolType::Int(_)) => match value {
+                CharValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                CharValue::Temp(register) => {
+                    let int_register = code.new_register();
+                    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::C2i(
+                        register,
+                        int_register,
+                    ))));
+                    Ok(SymbolType::Int(IntValue::Temp(int_register)))
+                }
+                CharValue::Undefined => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (SymbolType::Float(value), SymbolType::Int(_)) => match value {
+                FloatValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                FloatValue::Temp(register) => {
+                    let int_register = code.new_register();
+                    code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::F2i(
+                        register,
+                        int_register,
+                    ))));
+                    Ok(SymbolType::Int(IntValue::Temp(int_register)))
+                }
+                FloatValue::Undefined => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (SymbolType::Bool(value), SymbolType::Int(_)) => match value {
+                BoolValue::Literal(value) => Ok(SymbolType::Int(IntValue::Literal(value as i32))),
+                BoolValue::Temp(register) => Ok(SymbolType::Int(IntValue::Temp(register))),
+                BoolValue::Undefined => Ok(SymbolType::Int(IntValue::Undefined)),
+            },
+            (from_type, to_type) => {
+                let from_type = from_type.to_str().to_string();
+                let to_type = to_type.to_str().to_string();
+                let ((line, col), (_, _)) = lexer.line_col(self.node_id);
+                let highlight = ScopeStack::form_string_highlight(self.node_id, lexer);
+                Err(CompilerError::SemanticErrorInvalidCast {
+                    from_type,
+                    to_type,
+                    line,
+                    col,
+                    highlight,
+                })
+            }
+        }
+    }
+
+    pub fn operand(&self) -> NodeId {
+        self.operand
+    }
+
+    pub fn cast_type(&self) -> Span {
+        self.cast_type
+    }
+}
+
+impl AstNode for Cast {
+    fn check_node(
+        &self,
+        arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let target_type = stack.resolve_type(self.cast_type, lexer)?;
+        let type_value = match arena.check(self.operand, stack, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SemanticError(format!(
+                    "Cast struct operand returned no value on evaluation: {:?}",
+                    self.operand
+                )))
+            }
+        };
+
+        let type_value = self.cast_check(type_value, target_type, stack, lexer)?;
+        Ok(Some(type_value))
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let target_type = annotations.resolve_type(self.cast_type, lexer)?;
+        let type_value = match arena.generate(self.operand, code, annotations, lexer)? {
+            Some(value) => value,
+            None => {
+                return Err(CompilerError::SemanticError(format!(
+                    "Cast struct operand returned no value on evaluation: {:?}",
+                    self.operand
+                )))
+            }
+        };
+
+        let type_value = self.cast_evaluation(type_value, target_type, lexer, code)?;
+        Ok(Some(type_value))
+    }
+    fn get_span(&self) -> Span {
+        self.node_id
+    }
+    fn kind(&self) -> NodeKind {
+        NodeKind::Cast
+    }
+}
+
 #[derive(Debug)]
 pub struct VecAccess {
     node_id: Span,
-    vec_name: Box<VecInvoke>,
-    vec_index: Box<dyn AstNode>,
-    next: Option<Box<dyn AstNode>>,
+    vec_name: NodeId<VecInvoke>,
+    vec_index: NodeId,
 }
 
 impl VecAccess {
-    pub fn new(
-        node_id: Span,
-        vec_name: Box<VecInvoke>,
-        vec_index: Box<dyn AstNode>,
-        next: Option<Box<dyn AstNode>>,
-    ) -> VecAccess {
+    pub fn new(node_id: Span, vec_name: NodeId<VecInvoke>, vec_index: NodeId) -> VecAccess {
         VecAccess {
             node_id,
             vec_name,
             vec_index,
-            next,
         }
     }
 
-    fn print_label_vec_access(&self, own_address: *const c_void) {
-        println!("{:p} [label=\"[]\"];", own_address);
+    pub fn vec_name(&self) -> NodeId<VecInvoke> {
+        self.vec_name
+    }
+
+    pub fn vec_index(&self) -> NodeId {
+        self.vec_index
     }
 }
 
 impl AstNode for VecAccess {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        print_dependencies_own(self.vec_name.as_ref(), own_address);
-        print_dependencies_own(self.vec_index.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        print_dependencies_child(self.vec_name.as_ref(), own_address);
-        print_dependencies_child(self.vec_index.as_ref(), own_address);
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.print_label_vec_access(own_address);
-        print_labels_child(self.vec_name.as_ref(), lexer);
-        print_labels_child(self.vec_index.as_ref(), lexer);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
-        let vec_type_value =
-            self.vec_name
-                .evaluate_node(code, stack, lexer)?
-                .ok_or(CompilerError::SanityError(format!(
-                "VecAccess.evaluate_node() found no TypeValue from self.vec_name.evaluate_node()"
-            )))?;
+        let _vec_type_value =
+            arena
+                .check(self.vec_name, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "VecAccess.check_node() found no TypeValue from self.vec_name.check_node()"
+                        .to_string(),
+                ))?;
+
+        let indexer_type_value =
+            arena
+                .check(self.vec_index, stack, lexer)?
+                .ok_or(CompilerError::SanityError(
+                    "VecAccess.check_node() found no TypeValue from self.vec_index.check_node()"
+                        .to_string(),
+                ))?;
+
+        let previous_def = stack.get_previous_def(
+            arena.node(self.vec_name).get_span(),
+            lexer,
+            SymbolClass::default_vec(),
+        )?;
 
-        let indexer_type_value = self.vec_index.evaluate_node(code, stack, lexer)?.ok_or(CompilerError::SanityError(format!(
-            "VecAccess.evaluate_node() found no TypeValue from self.vec_index.evaluate_node()"
-        )))?;
+        // Only indices that fold to a constant can be checked this early, and
+        // vectors of an undeclared enum have no size to check against.
+        if let SymbolType::Int(IntValue::Literal(index)) = indexer_type_value {
+            let element_size = previous_def.type_value.get_symbol_type_size();
+            let length = previous_def.size.unwrap_or(0) / std::cmp::max(element_size, 1);
+            if element_size > 0 && (index < 0 || index as u32 >= length) {
+                let ((second_line, second_col), (_, _)) = lexer.line_col(self.node_id);
+                return Err(CompilerError::SemanticErrorIndexOutOfBounds {
+                    id: previous_def.id.clone(),
+                    index,
+                    length,
+                    first_line: previous_def.line,
+                    first_col: previous_def.col,
+                    first_highlight: ScopeStack::form_string_highlight(previous_def.span, lexer),
+                    second_line,
+                    second_col,
+                    second_highlight: ScopeStack::form_string_highlight(self.node_id, lexer),
+                });
+            }
+        }
 
-        let previous_def =
-            stack.get_previous_def(self.vec_name.get_span(), lexer, SymbolClass::default_vec())?;
+        Ok(Some(previous_def.type_value))
+    }
+    fn generate_node(
+        &self,
+        arena: &AstArena,
+        code: &mut IlocCode,
+        annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let vec_type_value = arena
+            .generate(self.vec_name, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "VecAccess.generate_node() found no TypeValue from self.vec_name.generate_node()"
+                    .to_string(),
+            ))?;
+
+        let indexer_type_value = arena
+            .generate(self.vec_index, code, annotations, lexer)?
+            .ok_or(CompilerError::SanityError(
+                "VecAccess.generate_node() found no TypeValue from self.vec_index.generate_node()"
+                    .to_string(),
+            ))?;
+
+        let previous_def = annotations.symbol(arena.node(self.vec_name).get_span())?;
 
         let offset_register;
         match indexer_type_value {
@@ -5413,6 +6184,36 @@ impl AstNode for VecAccess {
         } // foo[bar] => bar into register
 
         let type_size = vec_type_value.get_symbol_type_size() as i32;
+        // Constant indices were already checked against the declaration.
+        let constant_index = matches!(indexer_type_value, SymbolType::Int(IntValue::Literal(_)));
+        if code.checks().bounds && !constant_index {
+            let length = previous_def.size.unwrap_or(0) as i32 / std::cmp::max(type_size, 1);
+            let bound_register = code.new_register();
+            let past_end_register = code.new_register();
+            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                length,
+                bound_register,
+            ))));
+            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::CmpGE(
+                offset_register,
+                bound_register,
+                past_end_register,
+            ))));
+            code.trap_if(past_end_register, Trap::IndexOutOfBounds);
+
+            let zero_register = code.new_register();
+            let negative_register = code.new_register();
+            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::LoadI(
+                0,
+                zero_register,
+            ))));
+            code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::CmpLT(
+                offset_register,
+                zero_register,
+                negative_register,
+            ))));
+            code.trap_if(negative_register, Trap::IndexOutOfBounds);
+        } // foo[bar] => 0 <= bar < length, or trap
         code.push_code(CodeLine::Deliver(Instruction::Unlabeled(Operation::MultI(
             offset_register,
             type_size,
@@ -5426,177 +6227,118 @@ impl AstNode for VecAccess {
             offset_register,
         )))); // foo[bar] => (bar * size of type + offset) as register
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(SymbolType::Int(IntValue::Memory(offset_register, 0))))
     }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VecAccess
     }
 }
 
 #[derive(Debug)]
 pub struct VarInvoke {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl VarInvoke {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> VarInvoke {
-        VarInvoke { node_id, next }
+    pub fn new(node_id: Span) -> VarInvoke {
+        VarInvoke { node_id }
     }
 }
 
 impl AstNode for VarInvoke {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let span = self.node_id;
-        let class = SymbolClass::default_var();
-        let previous_def = stack.get_previous_def(span, lexer, class)?;
-        let type_value = previous_def.type_value.clone();
+        let previous_def = stack.get_value_def(span, lexer)?;
+        stack.check_assigned(&previous_def, span, lexer);
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(Some(previous_def.type_value))
+    }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        annotations: &Annotations,
+        _lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let type_value = annotations.symbol(self.node_id)?.type_value.clone();
 
         Ok(Some(type_value))
     }
+
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VarInvoke
     }
 }
 
 #[derive(Debug)]
 pub struct VecInvoke {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl VecInvoke {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> VecInvoke {
-        VecInvoke { node_id, next }
+    pub fn new(node_id: Span) -> VecInvoke {
+        VecInvoke { node_id }
     }
 }
 
 impl AstNode for VecInvoke {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn check_node(
         &self,
-        code: &mut IlocCode,
+        _arena: &AstArena,
         stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<Option<SymbolType>, CompilerError> {
         let span = self.node_id;
         let class = SymbolClass::default_vec();
         let previous_def = stack.get_previous_def(span, lexer, class)?;
-        let type_value = previous_def.type_value.clone();
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
+        Ok(Some(previous_def.type_value))
+    }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        annotations: &Annotations,
+        _lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let type_value = annotations.symbol(self.node_id)?.type_value.clone();
 
         Ok(Some(type_value))
     }
+
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::VecInvoke
     }
 }
 
 #[derive(Debug)]
 pub struct LiteralInt {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LiteralInt {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> LiteralInt {
-        LiteralInt { node_id, next }
+    pub fn new(node_id: Span) -> LiteralInt {
+        LiteralInt { node_id }
     }
-}
 
-impl AstNode for LiteralInt {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn literal_type(
         &self,
-        code: &mut IlocCode,
-        stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        let class = SymbolClass::Lit;
-
+    ) -> Result<SymbolType, CompilerError> {
         let span = self.node_id;
         let id = lexer.span_str(span).to_string();
 
@@ -5609,68 +6351,56 @@ impl AstNode for LiteralInt {
                 )))
             }
         };
-        let var_type = SymbolType::Int(IntValue::Literal(var_value));
+        Ok(SymbolType::Int(IntValue::Literal(var_value)))
+    }
+}
 
-        let ((line, col), (_, _)) = lexer.line_col(span);
-        let our_symbol = CallSymbol::new(id, span, line, col, var_type.clone(), class);
+impl AstNode for LiteralInt {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        let var_type = self.literal_type(lexer)?;
+        let our_symbol = CallSymbol::new(span, var_type.clone());
 
         stack.push_symbol(our_symbol)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(var_type))
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(Some(self.literal_type(lexer)?))
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::LiteralInt
     }
 }
 
 #[derive(Debug)]
 pub struct LiteralFloat {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LiteralFloat {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> LiteralFloat {
-        LiteralFloat { node_id, next }
+    pub fn new(node_id: Span) -> LiteralFloat {
+        LiteralFloat { node_id }
     }
-}
 
-impl AstNode for LiteralFloat {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn literal_type(
         &self,
-        code: &mut IlocCode,
-        stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        let class = SymbolClass::Lit;
-
+    ) -> Result<SymbolType, CompilerError> {
         let span = self.node_id;
         let id = lexer.span_str(span).to_string();
 
@@ -5683,68 +6413,56 @@ impl AstNode for LiteralFloat {
                 )))
             }
         };
-        let var_type = SymbolType::Float(Some(var_value));
+        Ok(SymbolType::Float(FloatValue::Literal(var_value)))
+    }
+}
 
-        let ((line, col), (_, _)) = lexer.line_col(span);
-        let our_symbol = CallSymbol::new(id, span, line, col, var_type.clone(), class);
+impl AstNode for LiteralFloat {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        let var_type = self.literal_type(lexer)?;
+        let our_symbol = CallSymbol::new(span, var_type.clone());
 
         stack.push_symbol(our_symbol)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(var_type))
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(Some(self.literal_type(lexer)?))
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::LiteralFloat
     }
 }
 
 #[derive(Debug)]
 pub struct LiteralBool {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LiteralBool {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> LiteralBool {
-        LiteralBool { node_id, next }
+    pub fn new(node_id: Span) -> LiteralBool {
+        LiteralBool { node_id }
     }
-}
 
-impl AstNode for LiteralBool {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        print_label_self(self.node_id, lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn literal_type(
         &self,
-        code: &mut IlocCode,
-        stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        let class = SymbolClass::Lit;
-
+    ) -> Result<SymbolType, CompilerError> {
         let span = self.node_id;
         let id = lexer.span_str(span).to_string();
 
@@ -5757,276 +6475,159 @@ impl AstNode for LiteralBool {
                 )))
             }
         };
-        let var_type = SymbolType::Bool(BoolValue::Literal(var_value));
+        Ok(SymbolType::Bool(BoolValue::Literal(var_value)))
+    }
+}
 
-        let ((line, col), (_, _)) = lexer.line_col(span);
-        let our_symbol = CallSymbol::new(id, span, line, col, var_type.clone(), class);
+impl AstNode for LiteralBool {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        let var_type = self.literal_type(lexer)?;
+        let our_symbol = CallSymbol::new(span, var_type.clone());
 
         stack.push_symbol(our_symbol)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(var_type))
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(Some(self.literal_type(lexer)?))
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::LiteralBool
     }
 }
 
 #[derive(Debug)]
 pub struct LiteralChar {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LiteralChar {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> LiteralChar {
-        LiteralChar { node_id, next }
+    pub fn new(node_id: Span) -> LiteralChar {
+        LiteralChar { node_id }
     }
 
-    fn print_label_lit_char(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        let text = lexer.span_str(self.node_id);
-        println!(
-            "{:p} [label=\"{}\"];",
-            own_address,
-            &text[1..(text.len() - 1)]
-        );
-    }
-}
-
-impl AstNode for LiteralChar {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.print_label_lit_char(lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
+    fn literal_type(
         &self,
-        code: &mut IlocCode,
-        stack: &mut ScopeStack,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        let class = SymbolClass::Lit;
-
+    ) -> Result<SymbolType, CompilerError> {
         let span = self.node_id;
         let id = lexer.span_str(span).to_string();
 
         let id_chars = id.as_bytes();
         if id_chars.len() != 3 {
             return Err(CompilerError::SanityError(
-                "on evaluate_node(), character not 3 characters long ".to_string(),
+                "on literal_type(), character not 3 characters long ".to_string(),
             ));
         }
 
         let var_value = id_chars[1];
-        let var_type = SymbolType::Char(Some(var_value));
+        Ok(SymbolType::Char(CharValue::Literal(var_value)))
+    }
+}
 
-        let ((line, col), (_, _)) = lexer.line_col(span);
-        let our_symbol = CallSymbol::new(id, span, line, col, var_type.clone(), class);
+impl AstNode for LiteralChar {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        let var_type = self.literal_type(lexer)?;
+        let our_symbol = CallSymbol::new(span, var_type.clone());
 
         stack.push_symbol(our_symbol)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(var_type))
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(Some(self.literal_type(lexer)?))
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
+    fn kind(&self) -> NodeKind {
+        NodeKind::LiteralChar
     }
 }
 
 #[derive(Debug)]
 pub struct LiteralString {
     node_id: Span,
-    next: Option<Box<dyn AstNode>>,
 }
 
 impl LiteralString {
-    pub fn new(node_id: Span, next: Option<Box<dyn AstNode>>) -> LiteralString {
-        LiteralString { node_id, next }
+    pub fn new(node_id: Span) -> LiteralString {
+        LiteralString { node_id }
     }
 
-    fn print_label_lit_string(
+    fn literal_type(
         &self,
         lexer: &dyn NonStreamingLexer<u32>,
-        own_address: *const c_void,
-    ) {
-        let text = lexer.span_str(self.node_id);
-        println!(
-            "{:p} [label=\"{}\"];",
-            own_address,
-            &text[1..(text.len() - 1)]
-        );
-    }
-}
-
-impl AstNode for LiteralString {
-    fn print_dependencies(&self, own_address: *const c_void, _ripple: bool) {
-        if let Some(next_node) = &self.next {
-            print_dependencies_own_next(next_node.as_ref(), own_address);
-        }
-        if let Some(next_node) = &self.next {
-            print_dependencies_next(next_node.as_ref(), own_address);
-        }
-    }
-    fn print_labels(&self, lexer: &dyn NonStreamingLexer<u32>, own_address: *const c_void) {
-        self.print_label_lit_string(lexer, own_address);
-        if let Some(next_node) = &self.next {
-            print_labels_next(next_node.as_ref(), own_address, lexer)
-        }
-    }
-    fn is_tree_member(&self) -> bool {
-        true
-    }
-    fn append_to_next(&mut self, new_last: Box<dyn AstNode>) {
-        self.next = append_node(&mut self.next, new_last)
-    }
-    fn evaluate_node(
-        &self,
-        code: &mut IlocCode,
-        stack: &mut ScopeStack,
-        lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<SymbolType>, CompilerError> {
-        let class = SymbolClass::Lit;
-
+    ) -> Result<SymbolType, CompilerError> {
         let span = self.node_id;
         let id = lexer.span_str(span).to_string();
 
         if id.len() < 2 {
             return Err(CompilerError::SanityError(
-                "on evaluate_node(), string smaller than 2 characters (no \") ".to_string(),
+                "on literal_type(), string smaller than 2 characters (no \") ".to_string(),
             ));
         }
-        let clean_string = (&id[1..id.len() - 1]).to_string();
+        let clean_string = id[1..id.len() - 1].to_string();
 
-        let var_type = SymbolType::String(Some(clean_string));
+        Ok(SymbolType::String(Some(clean_string)))
+    }
+}
 
-        let ((line, col), (_, _)) = lexer.line_col(span);
-        let our_symbol = CallSymbol::new(id, span, line, col, var_type.clone(), class);
+impl AstNode for LiteralString {
+    fn check_node(
+        &self,
+        _arena: &AstArena,
+        stack: &mut ScopeStack,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        let span = self.node_id;
+        let var_type = self.literal_type(lexer)?;
+        let our_symbol = CallSymbol::new(span, var_type.clone());
 
         stack.push_symbol(our_symbol)?;
 
-        if let Some(node) = &self.next {
-            node.evaluate_node(code, stack, lexer)?;
-        };
-
         Ok(Some(var_type))
     }
+    fn generate_node(
+        &self,
+        _arena: &AstArena,
+        _code: &mut IlocCode,
+        _annotations: &Annotations,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<Option<SymbolType>, CompilerError> {
+        Ok(Some(self.literal_type(lexer)?))
+    }
     fn get_span(&self) -> Span {
         self.node_id
     }
-    fn get_next(&self) -> &Option<Box<dyn AstNode>> {
-        &self.next
-    }
-}
-
-fn print_dependencies_ripple(next_node: &(dyn AstNode), own_address: *const c_void, ripple: bool) {
-    if next_node.is_tree_member() {
-        let next_address = addr_of!(*next_node) as *const c_void;
-        if ripple {
-            println!("{:p}, {:p}", own_address, next_address);
-        }
-        next_node.print_dependencies(next_address, false);
-    } else {
-        next_node.print_dependencies(own_address, ripple);
-    }
-}
-
-fn print_dependencies_own(child: &(dyn AstNode), own_address: *const c_void) {
-    if child.is_tree_member() {
-        let child_address = addr_of!(*child) as *const c_void;
-        println!("{:p}, {:p}", own_address, child_address);
-    }
-}
-
-fn print_dependencies_child(child: &(dyn AstNode), own_address: *const c_void) {
-    if child.is_tree_member() {
-        let child_address = addr_of!(*child) as *const c_void;
-        child.print_dependencies(child_address, false);
-    } else {
-        child.print_dependencies(own_address, true);
-    }
-}
-
-fn print_dependencies_own_next(next_node: &(dyn AstNode), own_address: *const c_void) {
-    if next_node.is_tree_member() {
-        let next_address = addr_of!(*next_node) as *const c_void;
-        println!("{:p}, {:p}", own_address, next_address);
-    }
-}
-
-fn print_dependencies_next(next_node: &(dyn AstNode), own_address: *const c_void) {
-    if next_node.is_tree_member() {
-        let next_address = addr_of!(*next_node) as *const c_void;
-        next_node.print_dependencies(next_address, false);
-    } else {
-        next_node.print_dependencies(own_address, true);
-    }
-}
-
-fn print_label_self(
-    self_span: Span,
-    lexer: &dyn NonStreamingLexer<u32>,
-    own_address: *const c_void,
-) {
-    println!(
-        "{:p} [label=\"{}\"];",
-        own_address,
-        lexer.span_str(self_span)
-    );
-}
-
-fn print_labels_child(child: &(dyn AstNode), lexer: &dyn NonStreamingLexer<u32>) {
-    child.print_labels(lexer, addr_of!(*child) as *const c_void);
-}
-
-fn print_labels_next(
-    next_node: &(dyn AstNode),
-    own_address: *const c_void,
-    lexer: &dyn NonStreamingLexer<u32>,
-) {
-    if next_node.is_tree_member() {
-        let next_address = addr_of!(*next_node) as *const c_void;
-        next_node.print_labels(lexer, next_address);
-    } else {
-        next_node.print_labels(lexer, own_address);
-    }
-}
-
-fn append_node(
-    current_next: &mut Option<Box<dyn AstNode>>,
-    new_last: Box<dyn AstNode>,
-) -> Option<Box<dyn AstNode>> {
-    match current_next.take() {
-        Some(mut node) => {
-            node.append_to_next(new_last);
-            Some(node)
-        }
-        None => Some(new_last),
+    fn kind(&self) -> NodeKind {
+        NodeKind::LiteralString
     }
 }
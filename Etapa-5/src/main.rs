@@ -3,26 +3,113 @@
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
 mod abstract_syntax_tree;
+mod ast_export;
+mod ast_import;
 mod ast_node;
 mod auxiliary_lexical_structures;
+mod call_graph;
 mod error;
+mod formatter;
+mod inline_asm;
 mod instructions;
 mod lexical_structures;
 mod semantic_structures;
+mod symbol_dump;
+mod tree_printer;
+mod trivia;
+mod visitor;
 
+use std::fs;
 use std::io::{self, Read, Write};
 
 use lrlex::lrlex_mod;
-use lrpar::lrpar_mod;
+use lrpar::{lrpar_mod, NonStreamingLexer};
 
+use abstract_syntax_tree::AbstractSyntaxTree;
 use error::CompilerError;
+use instructions::RuntimeChecks;
+use semantic_structures::OverflowMode;
+use trivia::TriviaLexer;
 
 // Using `lrlex_mod!` brings the lexer for `scanner.l` into scope.
 lrlex_mod!("scanner.l");
 // Using `lrpar_mod!` brings the lexer for `parser.y` into scope.
 lrpar_mod!("parser.y");
 
+enum Emit {
+    Iloc,
+    AstJson,
+    AstSexp,
+    Dot,
+    Symbols,
+    CallGraph,
+}
+
+// Deep enough for generated code with long operator chains. Every pass
+// recurses once per level, so the compiler runs on a thread with a stack
+// sized for this depth in debug builds.
+const DEFAULT_MAX_DEPTH: usize = 10_000;
+const STACK_SIZE: usize = 1 << 30;
+
 fn run_app() -> Result<(), CompilerError> {
+    let mut arguments = std::env::args().skip(1).peekable();
+    if arguments.peek().map(String::as_str) == Some("fmt") {
+        return run_fmt(arguments.skip(1).collect());
+    }
+
+    // --check stops after semantic analysis, like Etapa-4 did.
+    let mut check_only = false;
+    let mut emit = Emit::Iloc;
+    // --import reads a tree dump instead of a program and writes it back.
+    let mut import = None;
+    let mut max_depth = DEFAULT_MAX_DEPTH;
+    let mut warnings_as_errors = false;
+    // --fix prints the source back with the suggested casts applied.
+    let mut fix = false;
+    let mut checks = RuntimeChecks::default();
+    let mut overflow = OverflowMode::default();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--check" => check_only = true,
+            "-Werror" => warnings_as_errors = true,
+            "--fix" => fix = true,
+            "--overflow=wrap" => overflow = OverflowMode::Wrap,
+            "--overflow=error" => overflow = OverflowMode::Error,
+            "--max-depth" => max_depth = parse_max_depth(arguments.next())?,
+            "--import" => {
+                import = match arguments.next().as_deref() {
+                    Some("json") => Some(Emit::AstJson),
+                    Some("sexp") => Some(Emit::AstSexp),
+                    Some(value) => {
+                        return Err(CompilerError::UnknownArgument(format!(
+                            "--import {}",
+                            value
+                        )))
+                    }
+                    None => return Err(CompilerError::UnknownArgument(argument)),
+                }
+            }
+            "--emit" => {
+                emit = match arguments.next().as_deref() {
+                    Some("iloc") => Emit::Iloc,
+                    Some("ast-json") => Emit::AstJson,
+                    Some("ast-sexp") => Emit::AstSexp,
+                    Some("dot") => Emit::Dot,
+                    Some("symbols") => Emit::Symbols,
+                    Some("callgraph") => Emit::CallGraph,
+                    Some(value) => {
+                        return Err(CompilerError::UnknownArgument(format!("--emit {}", value)))
+                    }
+                    None => return Err(CompilerError::UnknownArgument(argument)),
+                }
+            }
+            _ => match argument.strip_prefix("--checks=") {
+                Some(value) => checks = parse_checks(value)?,
+                None => return Err(CompilerError::UnknownArgument(argument)),
+            },
+        }
+    }
+
     // We need to get a `LexerDef` for the `calc` language in order that we can lex input.
     let lexerdef = scanner_l::lexerdef();
     let stdin = io::stdin();
@@ -32,34 +119,199 @@ fn run_app() -> Result<(), CompilerError> {
 
     handle.read_to_string(&mut buffer)?;
 
-    let lexer = lexerdef.lexer(&buffer);
-    let (parsed, mut errors) = parser_y::parse(&lexer);
+    if let Some(format) = import {
+        return run_import(format, emit, &buffer);
+    }
+
+    let lexer = TriviaLexer::new(lexerdef.lexer(&buffer));
+    let abstract_syntax_tree = parse_tree(&lexer, max_depth)?;
+
+    // Tree dumps don't need the program to be semantically valid.
+    match emit {
+        Emit::AstJson => {
+            print!(
+                "{}",
+                ast_export::to_json(&abstract_syntax_tree.export(&lexer))
+            );
+            return Ok(());
+        }
+        Emit::AstSexp => {
+            print!(
+                "{}",
+                ast_export::to_sexp(&abstract_syntax_tree.export(&lexer))
+            );
+            return Ok(());
+        }
+        Emit::Dot => {
+            print!("{}", abstract_syntax_tree.dot(&lexer));
+            return Ok(());
+        }
+        Emit::Iloc | Emit::Symbols | Emit::CallGraph => (),
+    }
+    let (annotations, warnings) = abstract_syntax_tree.check(overflow, &lexer)?;
+    // Warnings go to stderr, stdout is reserved for the ILOC program.
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if warnings_as_errors && !warnings.is_empty() {
+        return Err(CompilerError::WarningsAsErrors(warnings.len()));
+    }
+    if fix {
+        print!(
+            "{}",
+            abstract_syntax_tree.insert_casts(&annotations, &buffer)
+        );
+        return Ok(());
+    }
+    if let Emit::Symbols = emit {
+        print!("{}", symbol_dump::dump_symbols(&annotations)?);
+        return Ok(());
+    }
+    if let Emit::CallGraph = emit {
+        print!("{}", annotations.call_graph().dot());
+        return Ok(());
+    }
+    if !check_only {
+        let iloc_code = abstract_syntax_tree.generate(&annotations, checks, &lexer)?;
+        iloc_code.print();
+    }
+    // abstract_syntax_tree.print_tree(&lexer);
+    Ok(())
+}
 
-    if errors.len() > 0 {
+// The dump is written back in the format given by --emit, or in the one it
+// was read in.
+fn run_import(format: Emit, emit: Emit, buffer: &str) -> Result<(), CompilerError> {
+    let nodes = match format {
+        Emit::AstJson => ast_import::from_json(buffer)?,
+        _ => ast_import::from_sexp(buffer)?,
+    };
+    let output = match emit {
+        Emit::Iloc => format,
+        emit => emit,
+    };
+    match output {
+        Emit::AstJson => print!("{}", ast_export::to_json(&nodes)),
+        Emit::AstSexp => print!("{}", ast_export::to_sexp(&nodes)),
+        _ => return Err(CompilerError::UnknownArgument("--import".to_string())),
+    }
+    Ok(())
+}
+
+fn parse_checks(value: &str) -> Result<RuntimeChecks, CompilerError> {
+    let mut checks = RuntimeChecks::default();
+    for check in value.split(',') {
+        match check {
+            "bounds" => checks.bounds = true,
+            "div" => checks.div = true,
+            _ => {
+                return Err(CompilerError::UnknownArgument(format!(
+                    "--checks={}",
+                    value
+                )))
+            }
+        }
+    }
+    Ok(checks)
+}
+
+fn parse_max_depth(value: Option<String>) -> Result<usize, CompilerError> {
+    match value.as_deref().map(str::parse::<usize>) {
+        Some(Ok(max_depth)) if max_depth > 0 => Ok(max_depth),
+        Some(_) => Err(CompilerError::UnknownArgument(format!(
+            "--max-depth {}",
+            value.unwrap()
+        ))),
+        None => Err(CompilerError::UnknownArgument("--max-depth".to_string())),
+    }
+}
+
+fn parse_tree(
+    lexer: &dyn NonStreamingLexer<u32>,
+    max_depth: usize,
+) -> Result<AbstractSyntaxTree, CompilerError> {
+    let (parsed, mut errors) = parser_y::parse(lexer);
+
+    if !errors.is_empty() {
         let first_error = errors.remove(0);
-        let mut report = first_error.pp(&lexer, &parser_y::token_epp);
+        let mut report = first_error.pp(lexer, &parser_y::token_epp);
         for error in errors {
-            report.push_str(&error.pp(&lexer, &parser_y::token_epp));
+            report.push_str(&error.pp(lexer, &parser_y::token_epp));
         }
         return Err(CompilerError::ParsingErrors(report));
     }
 
-    match parsed {
-        Some(Ok(abstract_syntax_tree)) => {
-            let iloc_code = abstract_syntax_tree.evaluate(&lexer)?;
-            iloc_code.print();
-            // abstract_syntax_tree.print_tree(&lexer);
+    let abstract_syntax_tree = match parsed {
+        Some(result) => result?,
+        None => return Err(CompilerError::EvalParserFailure),
+    };
+    abstract_syntax_tree.check_depth(max_depth, lexer)?;
+    Ok(abstract_syntax_tree)
+}
+
+// fmt [--check] [--split-declarations] [--max-depth N] [files...]
+// Files are rewritten in place, or only reported with --check. Without
+// files, stdin is formatted to stdout.
+fn run_fmt(arguments: Vec<String>) -> Result<(), CompilerError> {
+    let mut check = false;
+    let mut split_declarations = false;
+    let mut max_depth = DEFAULT_MAX_DEPTH;
+    let mut paths = vec![];
+    let mut arguments = arguments.into_iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--check" => check = true,
+            "--split-declarations" => split_declarations = true,
+            "--max-depth" => max_depth = parse_max_depth(arguments.next())?,
+            _ if argument.starts_with("--") => {
+                return Err(CompilerError::UnknownArgument(argument))
+            }
+            _ => paths.push(argument),
         }
-        Some(Err(error)) => {
-            return Err(error);
+    }
+
+    if paths.is_empty() {
+        let mut source = String::new();
+        io::stdin().lock().read_to_string(&mut source)?;
+        let formatted = format_source(&source, split_declarations, max_depth)?;
+        if !check {
+            print!("{}", formatted);
+        } else if formatted != source {
+            return Err(CompilerError::UnformattedSource("<stdin>".to_string()));
         }
-        None => {
-            return Err(CompilerError::EvalParserFailure);
+        return Ok(());
+    }
+
+    let mut unformatted = vec![];
+    for path in paths {
+        let source = fs::read_to_string(&path)?;
+        let formatted = format_source(&source, split_declarations, max_depth)?;
+        if formatted == source {
+            continue;
         }
-    };
+        if check {
+            unformatted.push(path);
+        } else {
+            fs::write(&path, formatted)?;
+        }
+    }
+    if !unformatted.is_empty() {
+        return Err(CompilerError::UnformattedSource(unformatted.join(", ")));
+    }
     Ok(())
 }
 
+fn format_source(
+    source: &str,
+    split_declarations: bool,
+    max_depth: usize,
+) -> Result<String, CompilerError> {
+    let lexerdef = scanner_l::lexerdef();
+    let lexer = TriviaLexer::new(lexerdef.lexer(source));
+    let abstract_syntax_tree = parse_tree(&lexer, max_depth)?;
+    Ok(abstract_syntax_tree.format(&lexer, lexer.comments(), split_declarations))
+}
+
 fn app_entry_point() -> i32 {
     match run_app() {
         Ok(()) => 0,
@@ -71,5 +323,10 @@ fn app_entry_point() -> i32 {
 }
 
 fn main() {
-    std::process::exit(app_entry_point())
+    // Only the pages a deep tree actually touches get committed.
+    let compiler = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(app_entry_point)
+        .expect("failed to spawn the compiler thread");
+    std::process::exit(compiler.join().unwrap_or(101))
 }
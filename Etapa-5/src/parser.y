@@ -13,56 +13,56 @@
 %left '?' ':'
 %left '(' ')'
 
+%token 'TK_COMMENT'
+
 %%
 
 program -> Result<AbstractSyntaxTree, CompilerError>:
-     { /* %empty */ Ok(AbstractSyntaxTree::new(None)) }
-    | topLevelDefList { Ok(AbstractSyntaxTree::new(Some($1?))) }
+     { /* %empty */ Ok(AbstractSyntaxTree::new(vec![])) }
+    | topLevelDefList { Ok(AbstractSyntaxTree::new($1?)) }
     ;
 
-topLevelDefList -> Result<Box<dyn AstNode>, CompilerError>:
+topLevelDefList -> Result<Vec<NodeId>, CompilerError>:
     topLevelDef { $1 }
     | topLevelDefList topLevelDef {
-        let mut upper_def = $1?;
-        upper_def.append_to_next($2?);
-        Ok(upper_def)
+        let mut list = $1?;
+        list.extend($2?);
+        Ok(list)
     }
     ;
 
-topLevelDef -> Result<Box<dyn AstNode>, CompilerError>:
-    optionalStatic type_rule identifier_rule topDefEnd {
-        let is_static = $1?;
-        let var_type = $2?;
-        let name = $3?;
-        Ok(
-            match $4? {
-                AuxTopDefEnd::FnDefEnd{params, commands} => {
-                    Box::new(FnDef::new(is_static, var_type, name, params, commands.first_command, None))
-                },
-                AuxTopDefEnd::SingleGlob => {
-                    Box::new(GlobalVarDef::new(is_static, var_type, name, None))
-                },
-                AuxTopDefEnd::GlobList(var_or_vec) => {
-                    top_level_def_assembler(is_static, var_type, var_or_vec)?
-                },
-                AuxTopDefEnd::VecAndGlobList(vec_size, var_or_vec) => {
-                    let mut upper_def = GlobalVecDef::new(is_static, var_type, name, vec_size, None);
-                    if var_or_vec.len() > 0 {
-                        upper_def.append_to_next(top_level_def_assembler(is_static, var_type, var_or_vec)?);
-                    }
-                    Box::new(upper_def)
-                },
-            }
-        )
+topLevelDef -> Result<Vec<NodeId>, CompilerError>:
+    type_rule identifier_rule topDefEnd { mount_top_level_def(false, $1?, $2?, $3?) }
+    | 'TK_PR_STATIC' type_rule identifier_rule topDefEnd { mount_top_level_def(true, $2?, $3?, $4?) }
+    | enumDef { Ok(vec![$1?]) }
+    ;
+
+enumDef -> Result<NodeId, CompilerError>:
+    'TK_PR_ENUM' identifier_rule '{' enumeratorList '}' { Ok(alloc_node(EnumDef::new($2?, $4?)).erase()) }
+    | 'TK_PR_ENUM' identifier_rule '{' enumeratorList '}' ';' { Ok(alloc_node(EnumDef::new($2?, $4?)).erase()) }
+    ;
+
+enumeratorList -> Result<Vec<Enumerator>, CompilerError>:
+    enumerator { Ok(vec![$1?]) }
+    | enumeratorList ',' enumerator {
+        let mut list = $1?;
+        list.push($3?);
+        Ok(list)
     }
     ;
 
+enumerator -> Result<Enumerator, CompilerError>:
+    identifier_rule { Ok(Enumerator{node_id: $1?, value: None, is_negative: false}) }
+    | identifier_rule setTok literal_int { Ok(Enumerator{node_id: $1?, value: Some($3?), is_negative: false}) }
+    | identifier_rule setTok minusTok literal_int { Ok(Enumerator{node_id: $1?, value: Some($4?), is_negative: true}) }
+    ;
+
 topDefEnd -> Result<AuxTopDefEnd, CompilerError>:
     '(' optionalParamList ')' commandBlock { Ok(AuxTopDefEnd::FnDefEnd{params: $2?, commands: $4?}) }
     | ';' { Ok(AuxTopDefEnd::SingleGlob) }
     | ',' globDefEndList ';' { Ok(AuxTopDefEnd::GlobList($2?)) }
     | '[' literal_int ']' endOrGlobDefEndList {
-        let int = Box::new(LiteralInt::new($2?, None));
+        let int = alloc_node(LiteralInt::new($2?));
         Ok(AuxTopDefEnd::VecAndGlobList(int, $4?))
     }
     ;
@@ -102,10 +102,10 @@ varOrVecName -> Result<AuxVarOrVecName, CompilerError>:
     }
     ;
 
-optionalArray -> Result<Option<Box<LiteralInt>>, CompilerError>:
+optionalArray -> Result<Option<NodeId<LiteralInt>>, CompilerError>:
       { /* %empty */ Ok(None) }
     | '[' literal_int ']' { 
-        let int = Box::new(LiteralInt::new($2?, None));
+        let int = alloc_node(LiteralInt::new($2?));
         Ok(Some(int))
     }
     ;
@@ -121,6 +121,7 @@ type_rule -> Result<Span, CompilerError>:
     | 'TK_PR_BOOL' { Ok($span) }
     | 'TK_PR_CHAR' { Ok($span) }
     | 'TK_PR_STRING' { Ok($span) }
+    | 'TK_PR_ENUM' identifier_rule { $2 }
     ;
 
 identifier_rule -> Result<Span, CompilerError>:
@@ -156,50 +157,44 @@ optionalConst -> Result<bool, CompilerError>:
     ;
 
 
-commandBlock -> Result<CommandBlock, CompilerError>:
+commandBlock -> Result<NodeId<CommandBlock>, CompilerError>:
     '{' optionalSimpleCommandList '}' {
         let node_id = $span;
-        let first_command = $2?;
-        let next = None;
-        Ok(CommandBlock::new(node_id, first_command, next))
+        let commands = $2?;
+        Ok(alloc_node(CommandBlock::new(node_id, commands)))
     }
     ;
 
-optionalSimpleCommandList -> Result<Option<Box<dyn AstNode>>, CompilerError>:
-      { /* %empty */ Ok(None) }
-    | simpleCommandList { Ok(Some($1?)) }
+optionalSimpleCommandList -> Result<Vec<NodeId>, CompilerError>:
+      { /* %empty */ Ok(vec![]) }
+    | simpleCommandList { $1 }
     ;
 
-simpleCommandList -> Result<Box<dyn AstNode>, CompilerError>:
+simpleCommandList -> Result<Vec<NodeId>, CompilerError>:
     simpleCommandSequence { $1 }
     | simpleCommandList simpleCommandSequence {
-        let mut left_node = $1?;
-        let right_node = $2?;
-        left_node.append_to_next(right_node);
-        Ok(left_node)
+        let mut list = $1?;
+        list.extend($2?);
+        Ok(list)
     }
     ;
 
-simpleCommandSequence -> Result<Box<dyn AstNode>, CompilerError>:
-    commandBlock ';' { Ok(Box::new($1?)) }
+simpleCommandSequence -> Result<Vec<NodeId>, CompilerError>:
+    commandBlock ';' { Ok(vec![$1?.erase()]) }
     | localDefList ';' { $1 }
-    | simpleCommand { $1 }
+    | simpleCommand { Ok(vec![$1?]) }
     ;
 
-localDefList -> Result<Box<dyn AstNode>, CompilerError>:
+localDefList -> Result<Vec<NodeId>, CompilerError>:
     optionalStatic optionalConst type_rule localNameDefList {
         let is_static = $1?;
         let is_const = $2?;
         let var_type = $3?;
-        let mut name_def_vec = $4?;
-        if name_def_vec.len() < 1 {
+        let name_def_vec = $4?;
+        if name_def_vec.is_empty() {
             return Err(CompilerError::TreeBuildingError("localNameDefList returned vector with zero elements".to_string()));
         };
-        let mut top_name_def = mount_local_def(is_static, is_const, var_type, name_def_vec.remove(0));
-        for name_def in name_def_vec {
-            top_name_def.append_to_next(mount_local_def(is_static, is_const, var_type, name_def))
-        };
-        Ok(Box::new(top_name_def))
+        Ok(name_def_vec.into_iter().map(|name_def| mount_local_def(is_static, is_const, var_type, name_def)).collect())
     }
     ;
 
@@ -233,13 +228,13 @@ localNameDefAssign -> Result<AuxLocalNameDef, CompilerError>:
     }
     ;
 
-literal -> Result<Box<dyn AstNode>, CompilerError>:
-    literal_int { Ok(Box::new(LiteralInt::new($1?, None))) }
-    | 'TK_LIT_FLOAT' { Ok(Box::new(LiteralFloat::new($span, None))) }
-    | 'TK_LIT_FALSE' { Ok(Box::new(LiteralBool::new($span, None))) }
-    | 'TK_LIT_TRUE' { Ok(Box::new(LiteralBool::new($span, None))) }
-    | 'TK_LIT_CHAR' { Ok(Box::new(LiteralChar::new($span, None))) }
-    | 'TK_LIT_STRING' { Ok(Box::new(LiteralString::new($span, None))) }
+literal -> Result<NodeId, CompilerError>:
+    literal_int { Ok(alloc_node(LiteralInt::new($1?)).erase()) }
+    | 'TK_LIT_FLOAT' { Ok(alloc_node(LiteralFloat::new($span)).erase()) }
+    | 'TK_LIT_FALSE' { Ok(alloc_node(LiteralBool::new($span)).erase()) }
+    | 'TK_LIT_TRUE' { Ok(alloc_node(LiteralBool::new($span)).erase()) }
+    | 'TK_LIT_CHAR' { Ok(alloc_node(LiteralChar::new($span)).erase()) }
+    | 'TK_LIT_STRING' { Ok(alloc_node(LiteralString::new($span)).erase()) }
     ;
 
 literal_int -> Result<Span, CompilerError>:
@@ -247,15 +242,16 @@ literal_int -> Result<Span, CompilerError>:
     ;
 
 
-simpleCommand -> Result<Box<dyn AstNode>, CompilerError>:
+simpleCommand -> Result<NodeId, CompilerError>:
     varShift ';' { $1 }
     | varSet ';' { $1 }
     | IO ';' { $1 }
-    | continueTok ';' { Ok(Box::new(Continue::new($1?, None))) }
-    | breakTok ';' { Ok(Box::new(Break::new($1?, None))) }
-    | returnTok expression ';' { Ok(Box::new(Return::new($1?, Box::new($2?), None))) }
+    | continueTok ';' { Ok(alloc_node(Continue::new($1?)).erase()) }
+    | breakTok ';' { Ok(alloc_node(Break::new($1?)).erase()) }
+    | returnTok expression ';' { Ok(alloc_node(Return::new($1?, $2?)).erase()) }
     | functionCall ';' { $1 }
     | conditional ';' { $1 }
+    | asmTok ';' { Ok(alloc_node(Asm::new($1?)).erase()) }
     ;
 
 continueTok -> Result<Span, CompilerError>:
@@ -266,80 +262,84 @@ breakTok -> Result<Span, CompilerError>:
     'TK_PR_BREAK' { Ok($span) }
     ;
 
+asmTok -> Result<Span, CompilerError>:
+    'TK_ASM' { Ok($span) }
+    ;
+
 returnTok -> Result<Span, CompilerError>:
     'TK_PR_RETURN' { Ok($span) }
     ;
 
-varShift -> Result<Box<dyn AstNode>, CompilerError>:
+varShift -> Result<NodeId, CompilerError>:
     identifier_rule leftShiftTok literal_int {
-        let var_name = Box::new(VarInvoke::new($1?, None));
+        let var_name = alloc_node(VarInvoke::new($1?));
         let shift_type = $2?;
-        let shift_amount = Box::new(LiteralInt::new($3?, None));
-        Ok(Box::new(VarLeftShift::new(shift_type, var_name, shift_amount, None)))
+        let shift_amount = alloc_node(LiteralInt::new($3?));
+        Ok(alloc_node(VarLeftShift::new(shift_type, var_name, shift_amount)).erase())
     }
     | identifier_rule rightShiftTok literal_int {
-        let var_name = Box::new(VarInvoke::new($1?, None));
+        let var_name = alloc_node(VarInvoke::new($1?));
         let shift_type = $2?;
-        let shift_amount = Box::new(LiteralInt::new($3?, None));
-        Ok(Box::new(VarRightShift::new(shift_type, var_name, shift_amount, None)))
+        let shift_amount = alloc_node(LiteralInt::new($3?));
+        Ok(alloc_node(VarRightShift::new(shift_type, var_name, shift_amount)).erase())
     }
     | vecAccess leftShiftTok literal_int {
-        let vec_access = Box::new($1?);
+        let vec_access = $1?;
         let shift_type = $2?;
-        let shift_amount = Box::new(LiteralInt::new($3?, None));
-        Ok(Box::new(VecLeftShift::new(shift_type, vec_access, shift_amount, None)))
+        let shift_amount = alloc_node(LiteralInt::new($3?));
+        Ok(alloc_node(VecLeftShift::new(shift_type, vec_access, shift_amount.erase())).erase())
     }
     | vecAccess rightShiftTok literal_int {
-        let vec_access = Box::new($1?);
+        let vec_access = $1?;
         let shift_type = $2?;
-        let shift_amount = Box::new(LiteralInt::new($3?, None));
-        Ok(Box::new(VecRightShift::new(shift_type, vec_access, shift_amount, None)))
+        let shift_amount = alloc_node(LiteralInt::new($3?));
+        Ok(alloc_node(VecRightShift::new(shift_type, vec_access, shift_amount.erase())).erase())
     }
     ;
 
-vecAccess -> Result<VecAccess, CompilerError>:
+vecAccess -> Result<NodeId<VecAccess>, CompilerError>:
     identifier_rule '[' expression ']' {
         let node_id = $span;
-        let vec_name = Box::new(VecInvoke::new($1?, None));
-        let vec_index = Box::new($3?);
-        Ok(VecAccess::new(node_id, vec_name, vec_index, None))
+        let vec_name = alloc_node(VecInvoke::new($1?));
+        let vec_index = $3?;
+        Ok(alloc_node(VecAccess::new(node_id, vec_name, vec_index)))
     }
     ;
 
 
-varSet -> Result<Box<dyn AstNode>, CompilerError>:
+varSet -> Result<NodeId, CompilerError>:
     identifier_rule setTok expression {
         let id_span = $1?;
-        let var_name = Box::new(VarInvoke::new(id_span, None));
+        let var_name = alloc_node(VarInvoke::new(id_span));
         let op_name = $2?;
-        let new_value = Box::new($3?);
-        Ok(Box::new(VarSet::new(op_name, var_name, new_value, None)))
+        let new_value = $3?;
+        Ok(alloc_node(VarSet::new(op_name, var_name, new_value)).erase())
     }
     | vecAccess setTok expression {
-        let vec_access = Box::new($1?);
+        let vec_access = $1?;
         let op_name = $2?;
-        let new_value = Box::new($3?);
-        Ok(Box::new(VecSet::new(op_name, vec_access, new_value, None)))
+        let new_value = $3?;
+        Ok(alloc_node(VecSet::new(op_name, vec_access, new_value)).erase())
     }
     ;
 
-IO -> Result<Box<dyn AstNode>, CompilerError>:
+IO -> Result<NodeId, CompilerError>:
     inputTok identifier_rule {
         let op_name = $1?;
         let id_span = $2?;
-        let var_name = Box::new(VarInvoke::new(id_span, None));
-        Ok(Box::new(Input::new(op_name, var_name, None)))
+        let var_name = alloc_node(VarInvoke::new(id_span));
+        Ok(alloc_node(Input::new(op_name, var_name.erase())).erase())
     }
     | outputTok identifier_rule {
         let op_name = $1?;
         let id_span = $2?;
-        let var_name = Box::new(VarInvoke::new(id_span, None));
-        Ok(Box::new(OutputId::new(op_name, var_name, None)))
+        let var_name = alloc_node(VarInvoke::new(id_span));
+        Ok(alloc_node(OutputId::new(op_name, var_name.erase())).erase())
     }
     | outputTok literal {
         let op_name = $1?;
         let lit_value = $2?;
-        Ok(Box::new(OutputLit::new(op_name, lit_value, None)))
+        Ok(alloc_node(OutputLit::new(op_name, lit_value)).erase())
     }
     ;
 
@@ -352,244 +352,238 @@ outputTok -> Result<Span, CompilerError>:
     ;
 
 
-functionCall -> Result<Box<dyn AstNode>, CompilerError>:
+functionCall -> Result<NodeId, CompilerError>:
     identifier_rule '(' optionalExpressionList ')' {
         let fn_name = $1?;
         let args = $3?;
-        Ok(Box::new(FnCall::new(fn_name, args, None)))
+        Ok(alloc_node(FnCall::new(fn_name, args)).erase())
     }
     ;
 
-optionalExpressionList -> Result<Vec<Box<dyn AstNode>>, CompilerError>:
+optionalExpressionList -> Result<Vec<NodeId>, CompilerError>:
       { /* %empty */ Ok(vec![]) }
-    | expressionList { Ok($1?) }
+    | expressionList { $1 }
     ;
 
-expressionList -> Result<Vec<Box<dyn AstNode>>, CompilerError>:
-    expression { Ok(vec![Box::new($1?)]) }
+expressionList -> Result<Vec<NodeId>, CompilerError>:
+    expression { Ok(vec![$1?]) }
     | expressionList ',' expression {
         let mut list = $1?;
-        list.push(Box::new($3?));
+        list.push($3?);
         Ok(list)
     }
     ;
 
 
-conditional -> Result<Box<dyn AstNode>, CompilerError>:
+conditional -> Result<NodeId, CompilerError>:
     ifTok '(' expression ')' commandBlock {
         let op_name = $1?;
-        let condition = Box::new($3?);
+        let condition = $3?;
         let consequence =  $5?;
-        Ok(Box::new(If::new(op_name, condition, consequence, None)))
+        Ok(alloc_node(If::new(op_name, condition, consequence)).erase())
     }
     | ifTok '(' expression ')' commandBlock 'TK_PR_ELSE' commandBlock {
         let op_name = $1?;
-        let condition = Box::new($3?);
+        let condition = $3?;
         let if_true = $5?;
         let if_false = $7?;
-        Ok(Box::new(IfElse::new(op_name, condition, if_true, if_false, None)))
+        Ok(alloc_node(IfElse::new(op_name, condition, if_true, if_false)).erase())
     }
     | forTok '(' varSet ':' expression ':' varSet ')' commandBlock {
         let op_name = $1?;
-        let count_init = Box::new($3?);
-        let count_check = Box::new($5?);
-        let count_iter = Box::new($7?);
+        let count_init = $3?;
+        let count_check = $5?;
+        let count_iter = $7?;
         let actions = $9?;
-        Ok(Box::new(For::new(op_name, count_init, count_check, count_iter, actions, None)))
+        Ok(alloc_node(For::new(op_name, count_init, count_check, count_iter, actions)).erase())
     }
     | whileTok '(' expression ')' 'TK_PR_DO' commandBlock {
         let op_name = $1?;
-        let condition = Box::new($3?);
+        let condition = $3?;
         let consequence =  $6?;
-        Ok(Box::new(While::new(op_name, condition, consequence, None)))
+        Ok(alloc_node(While::new(op_name, condition, consequence)).erase())
     }
     ;
 
 
-expression -> Result<Box<dyn AstNode>, CompilerError>:
+expression -> Result<NodeId, CompilerError>:
     ternaryOrUniBooleanOrLower { $1 }
     ;
 
-ternaryOrUniBooleanOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+ternaryOrUniBooleanOrLower -> Result<NodeId, CompilerError>:
     logicalOrOrLower questionTok ternaryOrUniBooleanOrLower doubleDotTok ternaryOrUniBooleanOrLower {
         let left_span = $2?;
         let right_span = $4?;
-        let condition = Box::new($1?);
-        let if_true = Box::new($3?);
-        let if_false = Box::new($5?);
-        Ok(Box::new(Ternary::new(left_span, right_span, condition, if_true, if_false, None)))
+        let condition = $1?;
+        let if_true = $3?;
+        let if_false = $5?;
+        Ok(alloc_node(Ternary::new(left_span, right_span, condition, if_true, if_false)).erase())
     }
     | logicalOrOrLower { $1 }
     ;
 
-logicalOrOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+logicalOrOrLower -> Result<NodeId, CompilerError>:
     logicalOrOrLower orTok logicalAndOrLower {
         let op_type = BinaryType::BoolOr;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | logicalAndOrLower { $1 }
     ;
 
-logicalAndOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+logicalAndOrLower -> Result<NodeId, CompilerError>:
     logicalAndOrLower andTok bitwiseOrOrLower {
         let op_type = BinaryType::BoolAnd;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | bitwiseOrOrLower { $1 }
     ;
 
-bitwiseOrOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+bitwiseOrOrLower -> Result<NodeId, CompilerError>:
     bitwiseOrOrLower pipeTok bitwiseXorOrLower {
         let op_type = BinaryType::BitOr;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | bitwiseXorOrLower { $1 }
     ;
 
-bitwiseXorOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+bitwiseXorOrLower -> Result<NodeId, CompilerError>:
     bitwiseXorOrLower circumflexTok bitwiseAndOrLower {
         let op_type = BinaryType::BitXor;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | bitwiseAndOrLower { $1 }
     ;
 
-bitwiseAndOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+bitwiseAndOrLower -> Result<NodeId, CompilerError>:
     bitwiseAndOrLower ampersandTok relationalEqualityOrLower {
         let op_type = BinaryType::BitAnd;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalEqualityOrLower { $1 }
     ;
 
-relationalEqualityOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+relationalEqualityOrLower -> Result<NodeId, CompilerError>:
     relationalEqualityOrLower equalTok relationalSizeOrLower {
         let op_type = BinaryType::Equal;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalEqualityOrLower notEqualTok relationalSizeOrLower {
         let op_type = BinaryType::NotEqual;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalSizeOrLower { $1 }
     ;
 
-relationalSizeOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+relationalSizeOrLower -> Result<NodeId, CompilerError>:
     relationalSizeOrLower lesserTok addSubOrLower {
         let op_type = BinaryType::Lesser;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalSizeOrLower greaterTok addSubOrLower {
         let op_type = BinaryType::Greater;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalSizeOrLower lesserEqualTok addSubOrLower {
         let op_type = BinaryType::LesserEqual;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | relationalSizeOrLower greaterEqualTok addSubOrLower {
         let op_type = BinaryType::GreaterEqual;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | addSubOrLower { $1 }
     ;
 
-addSubOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+addSubOrLower -> Result<NodeId, CompilerError>:
     addSubOrLower plusTok multDivRemainderOrLower {
         let op_type = BinaryType::Add;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | addSubOrLower minusTok multDivRemainderOrLower {
         let op_type = BinaryType::Sub;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | multDivRemainderOrLower { $1 }
     ;
 
-multDivRemainderOrLower -> Result<Box<dyn AstNode>, CompilerError>:
+multDivRemainderOrLower -> Result<NodeId, CompilerError>:
     multDivRemainderOrLower multTok unaryOperationOrOperand {
         let op_type = BinaryType::Mult;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | multDivRemainderOrLower divTok unaryOperationOrOperand {
         let op_type = BinaryType::Div;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | multDivRemainderOrLower modTok unaryOperationOrOperand {
         let op_type = BinaryType::Mod;
         let op_span = $2?;
-        let lhs = Box::new($1?);
-        let rhs = Box::new($3?);
-        Ok(Box::new(Binary::new(op_span, op_type, lhs, rhs, None)))
+        let lhs = $1?;
+        let rhs = $3?;
+        Ok(alloc_node(Binary::new(op_span, op_type, lhs, rhs)).erase())
     }
     | unaryOperationOrOperand { $1 }
     ;
 
-unaryOperationOrOperand -> Result<Box<dyn AstNode>, CompilerError>:
+unaryOperationOrOperand -> Result<NodeId, CompilerError>:
     expressionOperand { $1 }
     | unaryOperatorList expressionOperand {
-        let expr = Box::new($2?);
+        let expr = $2?;
         let mut op_list = $1?;
-        if op_list.len() < 1 {
+        if op_list.is_empty() {
             return Err(CompilerError::TreeBuildingError("unaryOperatorList returned vector with zero elements".to_string()));
         };
         let (last_span, last_type) = op_list.pop().unwrap();
-        let mut unary_box: Box<Unary> = Box::new(Unary::new(last_span, last_type, expr, None));
-        loop {
-            match op_list.pop() {
-                Some((next_span, next_type)) => {
-                    unary_box = Box::new(Unary::new(next_span, next_type, unary_box, None));
-                },
-                None => { break }
-            }
+        let mut unary_node = alloc_node(Unary::new(last_span, last_type, expr));
+        while let Some((next_span, next_type)) = op_list.pop() {
+            unary_node = alloc_node(Unary::new(next_span, next_type, unary_node.erase()));
         }
-        let unary_node: Box<dyn AstNode> = unary_box;
-        Ok(unary_node)
+        Ok(unary_node.erase())
     }
     ;
 
@@ -640,22 +634,34 @@ unaryOperator -> Result<(Span, UnaryType), CompilerError>:
         }
     ;
 
-expressionOperand -> Result<Box<dyn AstNode>, CompilerError>:
+expressionOperand -> Result<NodeId, CompilerError>:
     literal { $1 }
     | accessOrFnCall { $1 }
     | grouping { $1 }
+    | cast { $1 }
     ;
 
-accessOrFnCall -> Result<Box<dyn AstNode>, CompilerError>:
-    identifier_rule { Ok(Box::new(VarInvoke::new($1?, None))) }
-    | vecAccess { Ok(Box::new($1?)) }
+accessOrFnCall -> Result<NodeId, CompilerError>:
+    identifier_rule { Ok(alloc_node(VarInvoke::new($1?)).erase()) }
+    | vecAccess { Ok($1?.erase()) }
     | functionCall { $1 }
     ;
 
-grouping -> Result<Box<dyn AstNode>, CompilerError>:
+grouping -> Result<NodeId, CompilerError>:
     '(' expression ')' { $2 }
     ;
 
+cast -> Result<NodeId, CompilerError>:
+    castPrefix unaryOperationOrOperand {
+        let (cast_span, cast_type) = $1?;
+        Ok(alloc_node(Cast::new(cast_span, cast_type, $2?)).erase())
+    }
+    ;
+
+castPrefix -> Result<(Span, Span), CompilerError>:
+    '(' type_rule ')' { Ok(($span, $2?)) }
+    ;
+
 setTok -> Result<Span, CompilerError>:
     '=' { Ok($span) }
     ;
@@ -765,8 +771,7 @@ hashTok -> Result<Span, CompilerError>:
 use lrpar::Span;
 use super::lexical_structures::*;
 use super::auxiliary_lexical_structures::*;
-use super::ast_node::AstNode;
-use super::abstract_syntax_tree::AbstractSyntaxTree;
+use super::abstract_syntax_tree::{alloc_node, AbstractSyntaxTree, NodeId};
 use super::error::CompilerError;
 
 
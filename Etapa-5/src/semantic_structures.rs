@@ -3,15 +3,17 @@
 // Guilherme de Oliveira (00278301)
 // Jean Pierre Comerlatto Darricarrere (00182408)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use lrpar::{NonStreamingLexer, Span};
 
-use super::error::CompilerError;
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::call_graph::CallGraph;
+use super::error::{ClassMismatch, CompilerError, CompilerWarning};
 use super::instructions::Register;
 use super::lexical_structures::Parameter;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DefSymbol {
     pub id: String,
     pub span: Span,
@@ -22,9 +24,11 @@ pub struct DefSymbol {
     pub size: Option<u32>,
     pub offset_source: Register,
     pub offset: u32,
+    pub is_const: bool,
 }
 
 impl DefSymbol {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         span: Span,
@@ -46,6 +50,7 @@ impl DefSymbol {
             size,
             offset_source,
             offset,
+            is_const: false,
         }
     }
     pub fn cast_or_scream(
@@ -56,6 +61,7 @@ impl DefSymbol {
         check_string_size: bool,
     ) -> Result<DefSymbol, CompilerError> {
         match (&self.type_value, friend) {
+            (SymbolType::Error, _) | (_, SymbolType::Error) => Ok(self.clone()),
             (SymbolType::String(_), right_type @ SymbolType::String(None)) => Ok(DefSymbol::new(
                 self.id.clone(),
                 self.span,
@@ -69,7 +75,7 @@ impl DefSymbol {
             )),
             (SymbolType::String(_), right_type @ SymbolType::String(Some(_))) => {
                 let incoming_size = right_type.get_symbol_type_size();
-                let our_size = self.size.unwrap_or(0) as u32;
+                let our_size = self.size.unwrap_or(0);
                 if check_string_size && our_size < incoming_size {
                     let highlight = ScopeStack::form_string_highlight(span, lexer);
                     let ((line, col), (_, _)) = lexer.line_col(span);
@@ -215,31 +221,13 @@ impl DefSymbol {
 
 #[derive(Debug)]
 pub struct CallSymbol {
-    pub id: String,
     pub span: Span,
-    pub line: usize,
-    pub col: usize,
     pub type_value: SymbolType,
-    pub class: SymbolClass,
 }
 
 impl CallSymbol {
-    pub fn new(
-        id: String,
-        span: Span,
-        line: usize,
-        col: usize,
-        type_value: SymbolType,
-        class: SymbolClass,
-    ) -> CallSymbol {
-        CallSymbol {
-            id,
-            span,
-            line,
-            col,
-            type_value,
-            class,
-        }
+    pub fn new(span: Span, type_value: SymbolType) -> CallSymbol {
+        CallSymbol { span, type_value }
     }
 }
 
@@ -251,6 +239,20 @@ pub enum IntValue {
     Undefined,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum FloatValue {
+    Literal(f64),
+    Temp(Register),
+    Undefined,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum CharValue {
+    Literal(u8),
+    Temp(Register),
+    Undefined,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum BoolValue {
     Literal(bool),
@@ -261,13 +263,35 @@ pub enum BoolValue {
 #[derive(Clone, Debug)]
 pub enum SymbolType {
     Int(IntValue),
-    Float(Option<f64>),
-    Char(Option<u8>),
+    Float(FloatValue),
+    Char(CharValue),
     Bool(BoolValue),
     String(Option<String>),
+    // Left behind by an error that was already reported, so whatever uses it
+    // doesn't report another one.
+    Error,
 }
 
 impl SymbolType {
+    // Whether an implicit conversion to target can drop part of the value.
+    // Literals are judged by the value itself.
+    pub fn is_lossy_to(&self, target: &SymbolType) -> bool {
+        match (self, target) {
+            (SymbolType::Float(FloatValue::Literal(value)), SymbolType::Int(_)) => {
+                value.fract() != 0.0 || *value < i32::MIN as f64 || *value > i32::MAX as f64
+            }
+            (SymbolType::Float(FloatValue::Literal(value)), SymbolType::Bool(_)) => {
+                *value != 0.0 && *value != 1.0
+            }
+            (SymbolType::Int(IntValue::Literal(value)), SymbolType::Bool(_)) => {
+                *value != 0 && *value != 1
+            }
+            (SymbolType::Float(_), SymbolType::Int(_))
+            | (SymbolType::Float(_), SymbolType::Bool(_))
+            | (SymbolType::Int(_), SymbolType::Bool(_)) => true,
+            _ => false,
+        }
+    }
     pub fn to_str(&self) -> &str {
         match self {
             SymbolType::Int(_) => "int",
@@ -275,14 +299,24 @@ impl SymbolType {
             SymbolType::Char(_) => "char",
             SymbolType::Bool(_) => "bool",
             SymbolType::String(_) => "string",
+            SymbolType::Error => "error",
         }
     }
+    // Code generation only runs on trees that checked without errors.
+    pub fn error_in_codegen(context: &str) -> CompilerError {
+        CompilerError::SanityError(format!("{} found an error type", context))
+    }
+    // Operations on constants are folded by the checker, so their values come
+    // from the annotations and codegen never sees two literals.
+    pub fn unfolded_in_codegen(context: &str) -> CompilerError {
+        CompilerError::SanityError(format!("{} found an operation on constants", context))
+    }
     pub fn from_str(str_type: &str) -> Result<SymbolType, CompilerError> {
         match str_type {
             "int" => Ok(SymbolType::Int(IntValue::Undefined)),
-            "float" => Ok(SymbolType::Float(None)),
+            "float" => Ok(SymbolType::Float(FloatValue::Undefined)),
             "bool" => Ok(SymbolType::Bool(BoolValue::Undefined)),
-            "char" => Ok(SymbolType::Char(None)),
+            "char" => Ok(SymbolType::Char(CharValue::Undefined)),
             "string" => Ok(SymbolType::String(None)),
             _ => Err(CompilerError::LexicalError(format!(
                 "invalid type declaration: {}",
@@ -298,7 +332,7 @@ impl SymbolType {
         match self {
             SymbolType::Bool(value) => Ok(*value),
             SymbolType::Int(IntValue::Literal(value)) => Ok(BoolValue::Literal(*value != 0)),
-            SymbolType::Float(Some(value)) => Ok(BoolValue::Literal(*value != 0.0)),
+            SymbolType::Float(FloatValue::Literal(value)) => Ok(BoolValue::Literal(*value != 0.0)),
             SymbolType::Char(_) => {
                 let invalid_type = "boolean".to_string();
                 let ((line, col), (_, _)) = lexer.line_col(span);
@@ -332,7 +366,7 @@ impl SymbolType {
         match &self {
             SymbolType::Int(int_type) => Ok(*int_type),
             SymbolType::Bool(BoolValue::Literal(value)) => Ok(IntValue::Literal(*value as i32)),
-            SymbolType::Float(Some(value)) => Ok(IntValue::Literal(*value as i32)),
+            SymbolType::Float(FloatValue::Literal(value)) => Ok(IntValue::Literal(*value as i32)),
             SymbolType::Char(_) => {
                 let invalid_type = "int".to_string();
                 let ((line, col), (_, _)) = lexer.line_col(span);
@@ -362,11 +396,13 @@ impl SymbolType {
         &self,
         span: Span,
         lexer: &dyn NonStreamingLexer<u32>,
-    ) -> Result<Option<f64>, CompilerError> {
+    ) -> Result<FloatValue, CompilerError> {
         match self {
-            SymbolType::Float(Some(value)) => Ok(Some(*value)),
-            SymbolType::Int(IntValue::Literal(value)) => Ok(Some(*value as f64)),
-            SymbolType::Bool(BoolValue::Literal(value)) => Ok(Some((*value as u32) as f64)),
+            SymbolType::Float(float_type) => Ok(*float_type),
+            SymbolType::Int(IntValue::Literal(value)) => Ok(FloatValue::Literal(*value as f64)),
+            SymbolType::Bool(BoolValue::Literal(value)) => {
+                Ok(FloatValue::Literal((*value as u32) as f64))
+            }
             SymbolType::Char(_) => {
                 let invalid_type = "float".to_string();
                 let ((line, col), (_, _)) = lexer.line_col(span);
@@ -389,7 +425,7 @@ impl SymbolType {
                     highlight,
                 })
             }
-            _ => Ok(None),
+            _ => Ok(FloatValue::Undefined),
         }
     }
     pub fn associate_with(
@@ -399,8 +435,11 @@ impl SymbolType {
         lexer: &dyn NonStreamingLexer<u32>,
     ) -> Result<SymbolType, CompilerError> {
         match (self, friend) {
+            (SymbolType::Error, _) | (_, SymbolType::Error) => Ok(SymbolType::Error),
             (SymbolType::Int(_), SymbolType::Int(_)) => Ok(SymbolType::Int(IntValue::Undefined)),
-            (SymbolType::Float(_), SymbolType::Float(_)) => Ok(SymbolType::Float(None)),
+            (SymbolType::Float(_), SymbolType::Float(_)) => {
+                Ok(SymbolType::Float(FloatValue::Undefined))
+            }
             (SymbolType::Bool(_), SymbolType::Bool(_)) => {
                 Ok(SymbolType::Bool(BoolValue::Undefined))
             }
@@ -409,9 +448,13 @@ impl SymbolType {
             (SymbolType::Float(_), SymbolType::Int(_))
             | (SymbolType::Int(_), SymbolType::Float(_))
             | (SymbolType::Bool(_), SymbolType::Float(_))
-            | (SymbolType::Float(_), SymbolType::Bool(_)) => Ok(SymbolType::Float(None)),
+            | (SymbolType::Float(_), SymbolType::Bool(_)) => {
+                Ok(SymbolType::Float(FloatValue::Undefined))
+            }
             (SymbolType::String(_), SymbolType::String(_)) => Ok(SymbolType::String(None)),
-            (SymbolType::Char(_), SymbolType::Char(_)) => Ok(SymbolType::Char(None)),
+            (SymbolType::Char(_), SymbolType::Char(_)) => {
+                Ok(SymbolType::Char(CharValue::Undefined))
+            }
             (SymbolType::String(_), _) => {
                 let invalid_type = "int or float".to_string();
                 let ((line, col), (_, _)) = lexer.line_col(span);
@@ -474,23 +517,25 @@ impl SymbolType {
             SymbolType::Float(_) => 8,
             SymbolType::Bool(_) => 1,
             SymbolType::String(maybe_string) => match maybe_string {
-                Some(string) => (string.len() as u32),
+                Some(string) => string.len() as u32,
                 None => 0,
             },
+            SymbolType::Error => 0,
         }
     }
 }
 
 impl PartialEq for SymbolType {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
+        matches!(
+            (self, other),
             (SymbolType::Char(_), SymbolType::Char(_))
-            | (SymbolType::Int(_), SymbolType::Int(_))
-            | (SymbolType::Float(_), SymbolType::Float(_))
-            | (SymbolType::Bool(_), SymbolType::Bool(_))
-            | (SymbolType::String(_), SymbolType::String(_)) => true,
-            _ => false,
-        }
+                | (SymbolType::Int(_), SymbolType::Int(_))
+                | (SymbolType::Float(_), SymbolType::Float(_))
+                | (SymbolType::Bool(_), SymbolType::Bool(_))
+                | (SymbolType::String(_), SymbolType::String(_))
+                | (SymbolType::Error, SymbolType::Error)
+        )
     }
 }
 
@@ -499,32 +544,34 @@ pub const INT_SIZE: u32 = 4;
 #[derive(Clone, Debug)]
 pub enum SymbolClass {
     Fn(Vec<Parameter>),
-    Var { is_global: bool, offset: u32 },
-    Vec { offset: u32 },
-    Lit,
+    Var,
+    Vec,
+    Enum,
+    Enumerator,
 }
 
 impl SymbolClass {
     pub fn default_var() -> SymbolClass {
-        SymbolClass::Var {
-            is_global: false,
-            offset: 0,
-        }
+        SymbolClass::Var
     }
     pub fn default_vec() -> SymbolClass {
-        SymbolClass::Vec { offset: 0 }
+        SymbolClass::Vec
+    }
+    pub fn default_enum() -> SymbolClass {
+        SymbolClass::Enum
     }
 }
 
 impl PartialEq for SymbolClass {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
+        matches!(
+            (self, other),
             (SymbolClass::Fn(_), SymbolClass::Fn(_))
-            | (SymbolClass::Var { .. }, SymbolClass::Var { .. })
-            | (SymbolClass::Vec { .. }, SymbolClass::Vec { .. })
-            | (SymbolClass::Lit, SymbolClass::Lit) => true,
-            _ => false,
-        }
+                | (SymbolClass::Var, SymbolClass::Var)
+                | (SymbolClass::Vec, SymbolClass::Vec)
+                | (SymbolClass::Enum, SymbolClass::Enum)
+                | (SymbolClass::Enumerator, SymbolClass::Enumerator)
+        )
     }
 }
 
@@ -532,20 +579,181 @@ impl SymbolClass {
     pub fn to_str(&self) -> &str {
         match self {
             SymbolClass::Fn(_) => "function",
-            SymbolClass::Var { .. } => "variable",
-            SymbolClass::Vec { .. } => "vector",
-            SymbolClass::Lit => "literal",
+            SymbolClass::Var => "variable",
+            SymbolClass::Vec => "vector",
+            SymbolClass::Enum => "enum",
+            SymbolClass::Enumerator => "enumerator",
+        }
+    }
+}
+
+// What the checker resolved, handed over to code generation. Symbols are keyed
+// by the span of each name occurrence, types by the expression node and the
+// enclosing function's return type by the span of each return command.
+#[derive(Default)]
+pub struct Annotations {
+    types: HashMap<NodeId, SymbolType>,
+    symbols: HashMap<(usize, usize), DefSymbol>,
+    return_types: HashMap<(usize, usize), SymbolType>,
+    scopes: Vec<ScopeRecord>,
+    // Expressions converted with a loss, and the type they were converted to.
+    casts: Vec<(NodeId, String)>,
+    // Built once the checker found no errors.
+    call_graph: CallGraph,
+}
+
+// A scope as the checker left it, in the order scopes were opened. The
+// definition is the span of the enclosing function, None for the global scope,
+// and depth 1 is a function's own scope.
+#[derive(Clone, Debug)]
+pub struct ScopeRecord {
+    pub definition: Option<Span>,
+    pub depth: usize,
+    pub symbols: Vec<DefSymbol>,
+}
+
+impl ScopeRecord {
+    fn new(
+        definition: Option<Span>,
+        depth: usize,
+        scope: &HashMap<String, DefSymbol>,
+    ) -> ScopeRecord {
+        // Vector elements share the span of their vector.
+        let mut symbols: Vec<DefSymbol> = scope.values().cloned().collect();
+        symbols.sort_by(|a, b| {
+            (a.span.start(), a.offset, a.id.len(), &a.id).cmp(&(
+                b.span.start(),
+                b.offset,
+                b.id.len(),
+                &b.id,
+            ))
+        });
+        ScopeRecord {
+            definition,
+            depth,
+            symbols,
         }
     }
 }
 
+impl Annotations {
+    pub fn scopes(&self) -> &[ScopeRecord] {
+        &self.scopes
+    }
+
+    pub fn casts(&self) -> &[(NodeId, String)] {
+        &self.casts
+    }
+
+    pub fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
+    pub fn set_call_graph(&mut self, call_graph: CallGraph) {
+        self.call_graph = call_graph;
+    }
+
+    // The literal the checker folded an expression into, if it did.
+    pub fn folded(&self, id: NodeId) -> Option<SymbolType> {
+        match self.types.get(&id)? {
+            folded @ SymbolType::Int(IntValue::Literal(_))
+            | folded @ SymbolType::Float(FloatValue::Literal(_))
+            | folded @ SymbolType::Bool(BoolValue::Literal(_)) => Some(folded.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self, span: Span) -> Result<&DefSymbol, CompilerError> {
+        match self.symbols.get(&(span.start(), span.end())) {
+            Some(symbol) => Ok(symbol),
+            None => Err(CompilerError::SanityError(format!(
+                "no symbol was resolved for span {:?}",
+                span
+            ))),
+        }
+    }
+
+    // The label the generated code gives a function, told apart from its
+    // overloads by the types its parameters were resolved to.
+    pub fn fn_signature(&self, symbol: &DefSymbol) -> Result<String, CompilerError> {
+        let mut param_types = vec![];
+        if let SymbolClass::Fn(params) = &symbol.class {
+            for param in params {
+                param_types.push(self.symbol(param.node_id)?.type_value.clone());
+            }
+        }
+        Ok(signature(&symbol.id, &param_types))
+    }
+
+    pub fn return_type(&self, span: Span) -> Result<&SymbolType, CompilerError> {
+        match self.return_types.get(&(span.start(), span.end())) {
+            Some(type_value) => Ok(type_value),
+            None => Err(CompilerError::SanityError(format!(
+                "no return type was resolved for span {:?}",
+                span
+            ))),
+        }
+    }
+
+    pub fn resolve_type(
+        &self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<SymbolType, CompilerError> {
+        match lexer.span_str(span) {
+            "int" | "float" | "bool" | "char" | "string" => {
+                SymbolType::from_str(lexer.span_str(span))
+            }
+            _ => {
+                self.symbol(span)?;
+                Ok(SymbolType::Int(IntValue::Undefined))
+            }
+        }
+    }
+}
+
+// What a constant int expression that doesn't fit in 32 bits turns into.
+// Wrapping matches what the machine does with the same values at run time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OverflowMode {
+    #[default]
+    Wrap,
+    Error,
+}
+
+// What the checker knows about the paths reaching the current command.
+// Branches work on copies of it and join them back afterwards.
+#[derive(Clone, Default)]
+pub struct FlowState {
+    // Locals that some path reaches without assigning, by definition span.
+    unassigned: HashSet<(usize, usize)>,
+    returned: bool,
+}
+
+// Definitions by name, the return type of the enclosing function and the
+// symbols pushed while checking expressions.
+type Scope = (HashMap<String, DefSymbol>, Option<SymbolType>, Vec<CallSymbol>);
+
 pub struct ScopeStack {
-    stack: Vec<(
-        HashMap<String, DefSymbol>,
-        Option<SymbolType>,
-        Vec<CallSymbol>,
-    )>,
+    stack: Vec<Scope>,
     offsets: Vec<u32>,
+    current_fn: String,
+    current_definition: Option<Span>,
+    warnings: Vec<CompilerWarning>,
+    errors: Vec<CompilerError>,
+    // Messages of the errors above, to drop repeats.
+    error_messages: HashSet<String>,
+    // Definitions that were looked up from somewhere other than themselves.
+    used: HashSet<(usize, usize)>,
+    flow: FlowState,
+    reported_unassigned: HashSet<(usize, usize)>,
+    // Loops around the command being checked, for break and continue, and
+    // whether a break leaves each of them.
+    loops: Vec<bool>,
+    // Where each open scope goes in annotations.scopes.
+    open_records: Vec<usize>,
+    overflow: OverflowMode,
+    annotations: Annotations,
 }
 
 impl ScopeStack {
@@ -553,19 +761,106 @@ impl ScopeStack {
         ScopeStack {
             stack: vec![(HashMap::new(), None, vec![])],
             offsets: vec![0],
+            current_fn: String::new(),
+            current_definition: None,
+            warnings: vec![],
+            errors: vec![],
+            error_messages: HashSet::new(),
+            used: HashSet::new(),
+            flow: FlowState::default(),
+            reported_unassigned: HashSet::new(),
+            loops: vec![],
+            open_records: vec![],
+            overflow: OverflowMode::default(),
+            annotations: Annotations::default(),
+        }
+    }
+
+    pub fn set_overflow(&mut self, overflow: OverflowMode) {
+        self.overflow = overflow;
+    }
+
+    pub fn push_warning(&mut self, warning: CompilerWarning) {
+        self.warnings.push(warning)
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<CompilerWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    // Errors about the program are collected so checking can go on, internal
+    // ones still abort. Re-checking a name after a failed child would report
+    // the same error twice, so repeats are dropped.
+    pub fn report_error(&mut self, error: CompilerError) -> Result<(), CompilerError> {
+        if !error.is_recoverable() {
+            return Err(error);
+        }
+        if self.error_messages.insert(error.to_string()) {
+            self.errors.push(error);
+        }
+        Ok(())
+    }
+
+    // Sorted by where they occur, so the exit code follows the first error in
+    // the program.
+    pub fn take_errors(&mut self) -> Vec<CompilerError> {
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.sort_by_key(|error| error.position().unwrap_or((usize::MAX, usize::MAX)));
+        errors
+    }
+
+    pub fn annotate_type(&mut self, id: NodeId, type_value: SymbolType) {
+        self.annotations.types.insert(id, type_value);
+    }
+
+    fn annotate_symbol(&mut self, span: Span, symbol: DefSymbol) {
+        self.annotations
+            .symbols
+            .insert((span.start(), span.end()), symbol);
+    }
+
+    pub fn annotate_return_type(&mut self, span: Span, type_value: SymbolType) {
+        self.annotations
+            .return_types
+            .insert((span.start(), span.end()), type_value);
+    }
+
+    // The global scope is never removed, so it's only recorded here.
+    pub fn take_annotations(&mut self) -> Annotations {
+        if let Some((scope, _scope_type, _symbols)) = self.stack.first() {
+            let globals = ScopeRecord::new(None, 0, scope);
+            self.annotations.scopes.insert(0, globals);
         }
+        std::mem::take(&mut self.annotations)
     }
 
+    // A block inside a function keeps allocating after the locals around it,
+    // so it can't reuse their slots or the frame header.
     pub fn add_scope(&mut self, scope_type: Option<SymbolType>) {
-        self.offsets.push(0);
-        self.stack.push((HashMap::new(), scope_type, vec![]))
+        let offset = match self.offsets.len() {
+            1 => 0,
+            _ => self.offsets.last().copied().unwrap_or(0),
+        };
+        self.offsets.push(offset);
+        self.stack.push((HashMap::new(), scope_type, vec![]));
+        self.open_records.push(self.annotations.scopes.len());
+        let record = ScopeRecord::new(
+            self.current_definition,
+            self.stack.len() - 1,
+            &HashMap::new(),
+        );
+        self.annotations.scopes.push(record);
     }
 
     pub fn remove_scope(&mut self) -> Result<HashMap<String, DefSymbol>, CompilerError> {
         self.offsets.pop();
-        match self.stack.pop() {
-            Some((def_table, _scope_type, _symbols)) => Ok(def_table),
-            None => Err(CompilerError::FailedScoping),
+        match (self.stack.pop(), self.open_records.pop()) {
+            (Some((def_table, _scope_type, _symbols)), Some(index)) => {
+                let record = &mut self.annotations.scopes[index];
+                *record = ScopeRecord::new(record.definition, record.depth, &def_table);
+                Ok(def_table)
+            }
+            _ => Err(CompilerError::FailedScoping),
         }
     }
 
@@ -602,6 +897,115 @@ impl ScopeStack {
         }
     }
 
+    // A function may share its name with others as long as the parameter types
+    // tell them apart.
+    pub fn check_fn_duplicate(
+        &mut self,
+        span: Span,
+        params: &[Parameter],
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        let overloads = self.get_overloads(lexer.span_str(span));
+        if overloads.is_empty() {
+            return self.check_duplicate(span, lexer);
+        }
+        let signature = self.signature(lexer.span_str(span), params, lexer)?;
+        for older_symbol in overloads {
+            let older_params = match &older_symbol.class {
+                SymbolClass::Fn(older_params) => older_params.clone(),
+                _ => continue,
+            };
+            if self.signature(&older_symbol.id, &older_params, lexer)? != signature {
+                continue;
+            }
+            let ((second_line, second_col), (_, _)) = lexer.line_col(span);
+            return Err(CompilerError::SemanticErrorDeclared {
+                id: older_symbol.id.clone(),
+                first_line: older_symbol.line,
+                first_col: older_symbol.col,
+                first_highlight: ScopeStack::form_string_highlight(older_symbol.span, lexer),
+                second_line,
+                second_col,
+                second_highlight: ScopeStack::form_string_highlight(span, lexer),
+            });
+        }
+        Ok(())
+    }
+
+    // Every function called id, in source order, unless something closer
+    // hides them.
+    pub fn get_overloads(&self, id: &str) -> Vec<DefSymbol> {
+        let innermost = self
+            .stack
+            .iter()
+            .rev()
+            .find_map(|(scope, _scope_type, _symbols)| scope.get(id));
+        let globals = match (innermost, self.stack.first()) {
+            (Some(symbol), Some((scope, _scope_type, _symbols)))
+                if matches!(symbol.class, SymbolClass::Fn(_)) =>
+            {
+                scope
+            }
+            _ => return vec![],
+        };
+        let mut overloads: Vec<DefSymbol> = globals
+            .values()
+            .filter(|symbol| symbol.id == id && matches!(symbol.class, SymbolClass::Fn(_)))
+            .cloned()
+            .collect();
+        overloads.sort_by_key(|symbol| symbol.span.start());
+        overloads
+    }
+
+    pub fn signature(
+        &mut self,
+        id: &str,
+        params: &[Parameter],
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<String, CompilerError> {
+        let mut param_types = vec![];
+        for param in params {
+            param_types.push(self.resolve_type(param.param_type, lexer)?);
+        }
+        Ok(signature(id, &param_types))
+    }
+
+    // The first definition of a function goes by its name, later overloads
+    // by their signature, which no identifier can spell.
+    pub fn add_fn_symbol(
+        &mut self,
+        addition: DefSymbol,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        if self.get_overloads(&addition.id).is_empty() {
+            return self.add_def_symbol(addition);
+        }
+        let params = match &addition.class {
+            SymbolClass::Fn(params) => params.clone(),
+            _ => vec![],
+        };
+        let key = self.signature(&addition.id, &params, lexer)?;
+        self.annotate_symbol(addition.span, addition.clone());
+        match self.stack.last_mut() {
+            Some((scope, _scope_type, _symbols)) => {
+                scope.insert(key, addition);
+                Ok(())
+            }
+            None => Err(CompilerError::FailedScoping),
+        }
+    }
+
+    // The symbol a definition was declared with, if declaring it worked.
+    pub fn declaration(&self, span: Span) -> Option<DefSymbol> {
+        self.annotations.symbol(span).ok().cloned()
+    }
+
+    // The overload a call settled on is the one it uses.
+    pub fn resolve_call(&mut self, span: Span, symbol: &DefSymbol) {
+        self.used.insert((symbol.span.start(), symbol.span.end()));
+        self.annotate_symbol(span, symbol.clone());
+    }
+
     pub fn get_current_scope_type(&self) -> Result<SymbolType, CompilerError> {
         for (_scope, scope_type, _symbols) in self.stack.iter().rev() {
             match scope_type {
@@ -615,7 +1019,22 @@ impl ScopeStack {
         )))
     }
 
+    // Every lookup is recorded, so code generation finds the same symbol again.
     pub fn get_previous_def(
+        &mut self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+        expected_class: SymbolClass,
+    ) -> Result<DefSymbol, CompilerError> {
+        let symbol = self.find_previous_def(span, lexer, expected_class)?.clone();
+        if symbol.span != span {
+            self.used.insert((symbol.span.start(), symbol.span.end()));
+        }
+        self.annotate_symbol(span, symbol.clone());
+        Ok(symbol)
+    }
+
+    fn find_previous_def(
         &self,
         span: Span,
         lexer: &dyn NonStreamingLexer<u32>,
@@ -626,74 +1045,121 @@ impl ScopeStack {
         for (scope, _scope_type, _symbols) in self.stack.iter().rev() {
             if let Some(older_symbol) = scope.get(&id) {
                 if older_symbol.class == expected_class {
-                    return Ok(&older_symbol);
+                    return Ok(older_symbol);
                 } else {
-                    let first_line = older_symbol.line;
-                    let first_col = older_symbol.col;
-                    let first_highlight =
-                        ScopeStack::form_string_highlight(older_symbol.span, lexer);
-                    let second_class = expected_class.to_str().to_owned();
                     let ((second_line, second_col), (_, _)) = lexer.line_col(span);
-                    let second_highlight = ScopeStack::form_string_highlight(span, lexer);
-                    return Err(match older_symbol.class {
-                        SymbolClass::Var{ .. } => CompilerError::SemanticErrorVariable {
-                            id,
-                            first_line,
-                            first_col,
-                            first_highlight,
-                            second_class,
-                            second_line,
-                            second_col,
-                            second_highlight,
-                        },
-                        SymbolClass::Vec{ .. } => CompilerError::SemanticErrorVector {
-                            id,
-                            first_line,
-                            first_col,
-                            first_highlight,
-                            second_class,
-                            second_line,
-                            second_col,
-                            second_highlight,
-                        },
-                        SymbolClass::Fn(_) => CompilerError::SemanticErrorFunction {
-                            id,
-                            first_line,
-                            first_col,
-                            first_highlight,
-                            second_class,
-                            second_line,
-                            second_col,
-                            second_highlight,
-                        },
-                        SymbolClass::Lit => CompilerError::SanityError(
-                            format!(
-                                "get_previous_def() with \"{}\" matched a a literal: ({}, {}, {}) => ({}, {}, {}, {})",
-                                id,
-                                first_line,
-                                first_col,
-                                first_highlight,
-                                second_class,
-                                second_line,
-                                second_col,
-                                second_highlight
-                            )
+                    let mismatch = Box::new(ClassMismatch {
+                        id,
+                        first_line: older_symbol.line,
+                        first_col: older_symbol.col,
+                        first_highlight: ScopeStack::form_string_highlight(
+                            older_symbol.span,
+                            lexer,
                         ),
+                        second_class: expected_class.to_str().to_owned(),
+                        second_line,
+                        second_col,
+                        second_highlight: ScopeStack::form_string_highlight(span, lexer),
+                    });
+                    return Err(match older_symbol.class {
+                        SymbolClass::Var => CompilerError::SemanticErrorVariable(mismatch),
+                        SymbolClass::Vec => CompilerError::SemanticErrorVector(mismatch),
+                        SymbolClass::Fn(_) => CompilerError::SemanticErrorFunction(mismatch),
+                        SymbolClass::Enum => CompilerError::SemanticErrorEnum(mismatch),
+                        SymbolClass::Enumerator => CompilerError::SemanticErrorEnumerator(mismatch),
                     });
                 }
             }
         }
         let ((line, col), (_, _)) = lexer.line_col(span);
         let highlight = ScopeStack::form_string_highlight(span, lexer);
+        let suggestion = self.suggest(&id);
         Err(CompilerError::SemanticErrorUndeclared {
             id,
             line,
             col,
             highlight,
+            suggestion,
         })
     }
 
+    // Names visible from here that are a typo away from id, closest first.
+    // Mangled statics can't be spelled, so they're never offered.
+    fn suggest(&self, id: &str) -> String {
+        let limit = std::cmp::max(id.chars().count(), 3) / 3;
+        let mut seen = HashSet::new();
+        let mut candidates = vec![];
+        for (scope, _scope_type, _symbols) in self.stack.iter().rev() {
+            for symbol in scope.values() {
+                if symbol.id.contains('$') || !seen.insert(symbol.id.as_str()) {
+                    continue;
+                }
+                let distance = edit_distance(id, &symbol.id);
+                if distance <= limit {
+                    candidates.push((distance, symbol));
+                }
+            }
+        }
+        candidates.sort_by(|(a_distance, a), (b_distance, b)| {
+            a_distance.cmp(b_distance).then(a.id.cmp(&b.id))
+        });
+
+        let names: Vec<String> = candidates
+            .iter()
+            .take(3)
+            .map(|(_distance, symbol)| format!("\"{}\" ({})", symbol.id, symbol.class.to_str()))
+            .collect();
+        match names.split_last() {
+            None => String::new(),
+            Some((last, [])) => format!("\nDid you mean {}?", last),
+            Some((last, rest)) => format!("\nDid you mean {} or {}?", rest.join(", "), last),
+        }
+    }
+
+    // Enumerators may be read wherever a variable is, but never written to.
+    pub fn get_value_def(
+        &mut self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<DefSymbol, CompilerError> {
+        let id = lexer.span_str(span);
+        for (scope, _scope_type, _symbols) in self.stack.iter().rev() {
+            if let Some(older_symbol) = scope.get(id) {
+                if older_symbol.class == SymbolClass::Enumerator {
+                    let symbol = older_symbol.clone();
+                    self.annotate_symbol(span, symbol.clone());
+                    return Ok(symbol);
+                }
+                break;
+            }
+        }
+        self.get_previous_def(span, lexer, SymbolClass::default_var())
+    }
+
+    // type_rule yields the keyword span for builtin types and the name span for enums.
+    pub fn resolve_type(
+        &mut self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<SymbolType, CompilerError> {
+        match lexer.span_str(span) {
+            "int" | "float" | "bool" | "char" | "string" => {
+                SymbolType::from_str(lexer.span_str(span))
+            }
+            // A missing enum is reported here, so whatever is declared with it
+            // still exists, only with an error type.
+            _ => match self.get_previous_def(span, lexer, SymbolClass::default_enum()) {
+                Ok(_) => Ok(SymbolType::Int(IntValue::Undefined)),
+                Err(error) => {
+                    self.report_error(error)?;
+                    Ok(SymbolType::Error)
+                }
+            },
+        }
+    }
+
     pub fn add_def_symbol(&mut self, addition: DefSymbol) -> Result<(), CompilerError> {
+        self.annotate_symbol(addition.span, addition.clone());
         match self.stack.last_mut() {
             Some((scope, _scope_type, _symbols)) => {
                 scope.insert(addition.id.clone(), addition);
@@ -703,6 +1169,365 @@ impl ScopeStack {
         }
     }
 
+    // Conversions lose data only into int or bool, and there is no cast into
+    // bool, so only the ones into int get a fix. Initializers only take
+    // identifiers and literals, so those can't be fixed with a cast either.
+    pub fn warn_lossy(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId,
+        from: &SymbolType,
+        to: &SymbolType,
+        castable: bool,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) {
+        if !from.is_lossy_to(to) {
+            return;
+        }
+        let (start, end) = arena.extent(&[id]);
+        let span = Span::new(start, end);
+        let ((line, col), (_, _)) = lexer.line_col(span);
+        self.push_warning(CompilerWarning::LossyConversion {
+            from_type: from.to_str().to_string(),
+            to_type: to.to_str().to_string(),
+            line,
+            col,
+            highlight: ScopeStack::form_string_highlight(span, lexer),
+        });
+        if castable && matches!(to, SymbolType::Int(_)) {
+            self.annotations.casts.push((id, to.to_str().to_string()));
+        }
+    }
+
+    pub fn warn_shadowing(&mut self, addition: &DefSymbol, lexer: &dyn NonStreamingLexer<u32>) {
+        let outer_scopes = self.stack.iter().rev().skip(1);
+        let shadowed = outer_scopes
+            .filter_map(|(scope, _scope_type, _symbols)| scope.get(&addition.id))
+            .next();
+        if let Some(shadowed) = shadowed {
+            let warning = CompilerWarning::Shadowing {
+                id: addition.id.clone(),
+                first_line: shadowed.line,
+                first_col: shadowed.col,
+                first_highlight: ScopeStack::form_string_highlight(shadowed.span, lexer),
+                second_line: addition.line,
+                second_col: addition.col,
+                second_highlight: ScopeStack::form_string_highlight(addition.span, lexer),
+            };
+            self.push_warning(warning);
+        }
+    }
+
+    // Warns about the variables of a scope that was just removed. Parameters
+    // share the function scope with its body, so they are told apart here.
+    pub fn warn_unused(
+        &mut self,
+        scope: &HashMap<String, DefSymbol>,
+        params: &[Parameter],
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) {
+        let mut unused: Vec<&DefSymbol> = scope
+            .values()
+            .filter(|symbol| matches!(symbol.class, SymbolClass::Var))
+            .filter(|symbol| !self.is_used(symbol))
+            .collect();
+        unused.sort_by_key(|symbol| symbol.span.start());
+        for symbol in unused {
+            let id = symbol.id.clone();
+            let (line, col) = (symbol.line, symbol.col);
+            let highlight = ScopeStack::form_string_highlight(symbol.span, lexer);
+            let warning = if params.iter().any(|param| param.node_id == symbol.span) {
+                CompilerWarning::UnusedParameter {
+                    id,
+                    line,
+                    col,
+                    highlight,
+                }
+            } else {
+                CompilerWarning::UnusedVariable {
+                    id,
+                    line,
+                    col,
+                    highlight,
+                }
+            };
+            self.push_warning(warning);
+        }
+    }
+
+    // Globals are only complete once every definition was checked.
+    pub fn warn_unused_functions(&mut self, lexer: &dyn NonStreamingLexer<u32>) {
+        let mut unused: Vec<DefSymbol> = match self.stack.first() {
+            Some((scope, _scope_type, _symbols)) => scope
+                .values()
+                .filter(|symbol| matches!(symbol.class, SymbolClass::Fn(_)))
+                .filter(|symbol| symbol.id != "main" && !self.is_used(symbol))
+                .cloned()
+                .collect(),
+            None => vec![],
+        };
+        unused.sort_by_key(|symbol| symbol.span.start());
+        for symbol in unused {
+            self.push_warning(CompilerWarning::UnusedFunction {
+                id: symbol.id,
+                line: symbol.line,
+                col: symbol.col,
+                highlight: ScopeStack::form_string_highlight(symbol.span, lexer),
+            });
+        }
+    }
+
+    fn is_used(&self, symbol: &DefSymbol) -> bool {
+        self.used
+            .contains(&(symbol.span.start(), symbol.span.end()))
+    }
+
+    pub fn mark_unassigned(&mut self, symbol: &DefSymbol) {
+        self.flow
+            .unassigned
+            .insert((symbol.span.start(), symbol.span.end()));
+    }
+
+    // Only the innermost definition of a name can be written, and a missing
+    // one was already reported when the target was checked. Enumerators are
+    // constants too, but say so the way an assignment to one does.
+    pub fn check_writable(
+        &self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        let symbol = self
+            .stack
+            .iter()
+            .rev()
+            .find_map(|(scope, _scope_type, _symbols)| scope.get(lexer.span_str(span)));
+        match symbol {
+            Some(symbol) if symbol.is_const => {
+                let ((second_line, second_col), (_, _)) = lexer.line_col(span);
+                Err(CompilerError::SemanticErrorConstWrite {
+                    id: symbol.id.clone(),
+                    first_line: symbol.line,
+                    first_col: symbol.col,
+                    first_highlight: ScopeStack::form_string_highlight(symbol.span, lexer),
+                    second_line,
+                    second_col,
+                    second_highlight: ScopeStack::form_string_highlight(span, lexer),
+                })
+            }
+            Some(symbol) if symbol.class == SymbolClass::Enumerator => {
+                let ((second_line, second_col), (_, _)) = lexer.line_col(span);
+                Err(CompilerError::SemanticErrorEnumerator(Box::new(ClassMismatch {
+                    id: symbol.id.clone(),
+                    first_line: symbol.line,
+                    first_col: symbol.col,
+                    first_highlight: ScopeStack::form_string_highlight(symbol.span, lexer),
+                    second_class: SymbolClass::default_var().to_str().to_owned(),
+                    second_line,
+                    second_col,
+                    second_highlight: ScopeStack::form_string_highlight(span, lexer),
+                })))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // A const initialised from a literal can't change, so every later use
+    // reads the literal instead of loading it.
+    pub fn fold_constant(&mut self, symbol: &DefSymbol, value: i32) {
+        if let Some((scope, _scope_type, _symbols)) = self.stack.last_mut() {
+            if let Some(local) = scope.get_mut(&symbol.id) {
+                local.type_value = SymbolType::Int(IntValue::Literal(value));
+            }
+        }
+    }
+
+    // A string declared without a size takes the one it's initialised with.
+    pub fn set_size(&mut self, symbol: &DefSymbol, size: Option<u32>) {
+        if let Some((scope, _scope_type, _symbols)) = self.stack.last_mut() {
+            if let Some(local) = scope.get_mut(&symbol.id) {
+                local.size = size;
+            }
+        }
+    }
+
+    pub fn mark_assigned(&mut self, symbol: &DefSymbol) {
+        self.flow
+            .unassigned
+            .remove(&(symbol.span.start(), symbol.span.end()));
+    }
+
+    pub fn flow(&self) -> FlowState {
+        self.flow.clone()
+    }
+
+    pub fn restore_flow(&mut self, flow: FlowState) {
+        self.flow = flow;
+    }
+
+    // A variable is only assigned after a branch if every path assigned it,
+    // and the same goes for having returned.
+    pub fn join_flow(&mut self, other: FlowState) {
+        self.flow.unassigned.extend(other.unassigned);
+        self.flow.returned = self.flow.returned && other.returned;
+    }
+
+    // Nothing after a return is reached, so it can't read anything unassigned.
+    pub fn end_path(&mut self) {
+        self.flow.unassigned.clear();
+        self.flow.returned = true;
+    }
+
+    // Falling off the end of a function returns whatever was left in its frame.
+    // main may end without a return, as in C.
+    pub fn check_returns(
+        &mut self,
+        fn_span: Span,
+        body_span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) {
+        if self.flow.returned || lexer.span_str(fn_span) == "main" {
+            return;
+        }
+        let end_span = Span::new(body_span.end() - 1, body_span.end());
+        let ((first_line, first_col), (_, _)) = lexer.line_col(fn_span);
+        let ((second_line, second_col), (_, _)) = lexer.line_col(end_span);
+        let id = lexer.span_str(fn_span).to_string();
+        let first_highlight = ScopeStack::form_string_highlight(fn_span, lexer);
+        let second_highlight = ScopeStack::form_string_highlight(end_span, lexer);
+        self.push_warning(CompilerWarning::MissingReturn {
+            id,
+            first_line,
+            first_col,
+            first_highlight,
+            second_line,
+            second_col,
+            second_highlight,
+        });
+    }
+
+    // Only the first unsafe read of each variable is reported. The read still
+    // has its type, so checking the expression around it goes on as usual.
+    pub fn check_assigned(
+        &mut self,
+        symbol: &DefSymbol,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) {
+        let key = (symbol.span.start(), symbol.span.end());
+        if !self.flow.unassigned.contains(&key) || !self.reported_unassigned.insert(key) {
+            return;
+        }
+        let ((second_line, second_col), (_, _)) = lexer.line_col(span);
+        let id = lexer.span_str(span).to_string();
+        let first_highlight = ScopeStack::form_string_highlight(symbol.span, lexer);
+        let second_highlight = ScopeStack::form_string_highlight(span, lexer);
+        self.push_warning(CompilerWarning::MaybeUnassigned {
+            id,
+            first_line: symbol.line,
+            first_col: symbol.col,
+            first_highlight,
+            second_line,
+            second_col,
+            second_highlight,
+        });
+    }
+
+    pub fn enter_loop(&mut self) {
+        self.loops.push(false);
+    }
+
+    // Whether a break left the loop, so the code after it can be reached.
+    pub fn leave_loop(&mut self) -> bool {
+        self.loops.pop().unwrap_or(false)
+    }
+
+    pub fn mark_break(&mut self) {
+        if let Some(broken) = self.loops.last_mut() {
+            *broken = true;
+        }
+    }
+
+    pub fn check_in_loop(
+        &self,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<(), CompilerError> {
+        if !self.loops.is_empty() {
+            return Ok(());
+        }
+        let ((line, col), (_, _)) = lexer.line_col(span);
+        Err(CompilerError::SemanticErrorOutsideLoop {
+            keyword: lexer.span_str(span).to_string(),
+            line,
+            col,
+            highlight: ScopeStack::form_string_highlight(span, lexer),
+        })
+    }
+
+    // folded is the checked result of a constant int operation, None when it
+    // overflowed, and wrapped the same operation in two's complement.
+    pub fn fold_int(
+        &mut self,
+        folded: Option<i32>,
+        wrapped: i32,
+        span: Span,
+        lexer: &dyn NonStreamingLexer<u32>,
+    ) -> Result<i32, CompilerError> {
+        if let Some(value) = folded {
+            return Ok(value);
+        }
+        let ((line, col), (_, _)) = lexer.line_col(span);
+        let highlight = ScopeStack::form_string_highlight(span, lexer);
+        match self.overflow {
+            OverflowMode::Wrap => {
+                self.push_warning(CompilerWarning::Overflow {
+                    value: wrapped,
+                    line,
+                    col,
+                    highlight,
+                });
+                Ok(wrapped)
+            }
+            OverflowMode::Error => Err(CompilerError::SemanticErrorOverflow {
+                line,
+                col,
+                highlight,
+            }),
+        }
+    }
+
+    pub fn add_global_def_symbol(&mut self, addition: DefSymbol) -> Result<(), CompilerError> {
+        self.annotate_symbol(addition.span, addition.clone());
+        match self.stack.first_mut() {
+            Some((scope, _scope_type, _symbols)) => {
+                scope.insert(addition.id.clone(), addition);
+                Ok(())
+            }
+            None => Err(CompilerError::FailedScoping),
+        }
+    }
+
+    pub fn set_current_fn(&mut self, symbol: &DefSymbol) {
+        self.current_fn = symbol.id.clone();
+        self.current_definition = Some(symbol.span);
+    }
+
+    // Static locals live among the globals under a name no identifier can spell.
+    pub fn mangle_static(&self, id: &str) -> Result<String, CompilerError> {
+        let globals = match self.stack.first() {
+            Some((scope, _scope_type, _symbols)) => scope,
+            None => return Err(CompilerError::FailedScoping),
+        };
+        let base = format!("{}${}", self.current_fn, id);
+        let mut mangled = base.clone();
+        let mut count = 1;
+        while globals.contains_key(&mangled) {
+            count += 1;
+            mangled = format!("{}${}", base, count);
+        }
+        Ok(mangled)
+    }
+
     pub fn push_symbol(&mut self, addition: CallSymbol) -> Result<(), CompilerError> {
         match self.stack.last_mut() {
             Some((_scope, _scope_type, symbols)) => {
@@ -737,6 +1562,23 @@ impl ScopeStack {
         }
     }
 
+    pub fn add_global_offset(&mut self, extra_offset: u32) -> Result<(), CompilerError> {
+        match self.offsets.first_mut() {
+            Some(num) => {
+                *num += extra_offset;
+                Ok(())
+            }
+            None => Err(CompilerError::FailedScoping),
+        }
+    }
+
+    pub fn get_global_offset(&self) -> Result<u32, CompilerError> {
+        match self.offsets.first() {
+            Some(num) => Ok(*num),
+            None => Err(CompilerError::FailedScoping),
+        }
+    }
+
     pub fn form_string_highlight(span: Span, lexer: &dyn NonStreamingLexer<u32>) -> String {
         let ((_start_line, start_column), (_end_line, end_column)) = lexer.line_col(span);
         let mut lines = lexer.span_lines_str(span).lines().peekable();
@@ -744,7 +1586,7 @@ impl ScopeStack {
         let mut output = String::new();
 
         let first_line = lines.next().unwrap_or("");
-        output.push_str(&first_line);
+        output.push_str(first_line);
         output.push('\n');
 
         for _i in 0..start_column - 1 {
@@ -758,18 +1600,15 @@ impl ScopeStack {
             output.push('^');
         }
 
-        loop {
-            let next_line = match lines.next() {
-                Some(string) => string,
-                None => break,
-            };
+        while let Some(next_line) = lines.next() {
+            output.push('\n');
+            output.push_str(next_line);
             output.push('\n');
-            output.push_str(&next_line);
             let end_of_next_line = match lines.peek() {
                 Some(_) => next_line.len() + 1,
                 None => end_column,
             };
-            for _i in 0..end_of_next_line {
+            for _i in 1..end_of_next_line {
                 output.push('^');
             }
         }
@@ -777,3 +1616,35 @@ impl ScopeStack {
         output
     }
 }
+
+// Labels and overloads are told apart by name and parameter types alike.
+pub fn signature(id: &str, param_types: &[SymbolType]) -> String {
+    let names: Vec<&str> = param_types
+        .iter()
+        .map(|type_value| type_value.to_str())
+        .collect();
+    format!("{}({})", id, names.join(", "))
+}
+
+// Optimal string alignment distance: swapping two neighbouring characters
+// counts as one edit, like inserting, deleting or replacing one.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (previous[j] + 1)
+                .min(row[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, row);
+    }
+    previous[b.len()]
+}
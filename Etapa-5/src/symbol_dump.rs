@@ -0,0 +1,144 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use super::error::CompilerError;
+use super::instructions::Register;
+use super::lexical_structures::{FN_OFFSET, RETURN_VAL_OFFSET};
+use super::semantic_structures::{Annotations, DefSymbol, ScopeRecord, SymbolClass};
+
+// Every scope the checker saw, nested as in the source, and the frame layout
+// of each function right before its own scope. Blocks that declare nothing
+// are left out, and functions are listed with the signature their code is
+// labelled by.
+pub fn dump_symbols(annotations: &Annotations) -> Result<String, CompilerError> {
+    let scopes = annotations.scopes();
+    let mut out = String::new();
+    for (index, record) in scopes.iter().enumerate() {
+        let function = match record.definition {
+            Some(definition) => Some(annotations.symbol(definition)?),
+            None => None,
+        };
+        match (record.depth, function) {
+            (0, _) => out.push_str("scope global\n"),
+            (1, Some(function)) => {
+                out.push('\n');
+                dump_frame(annotations, function, scopes, index, &mut out)?;
+                out.push('\n');
+                let signature = annotations.fn_signature(function)?;
+                out.push_str(&format!("scope {}\n", signature));
+            }
+            _ if record.symbols.is_empty() => (),
+            (depth, _) => out.push_str(&format!("{}block\n", "  ".repeat(depth - 1))),
+        }
+        if record.symbols.is_empty() {
+            continue;
+        }
+        let indent = "  ".repeat(std::cmp::max(record.depth, 1));
+        let mut rows = vec![columns(&["name", "type", "class", "size", "location"])];
+        for symbol in &record.symbols {
+            let size = match symbol.size {
+                Some(size) => size.to_string(),
+                None => "-".to_string(),
+            };
+            let location = match symbol.class {
+                SymbolClass::Fn(_) => annotations.fn_signature(symbol)?,
+                _ => location(symbol.offset_source, symbol.offset as i32),
+            };
+            rows.push(vec![
+                symbol.id.clone(),
+                symbol.type_value.to_str().to_string(),
+                symbol.class.to_str().to_string(),
+                size,
+                location,
+            ]);
+        }
+        push_table(&rows, &indent, &mut out);
+    }
+    Ok(out)
+}
+
+// The header matches what FnDef's epilogue loads back, the rest are the
+// parameters and locals of every scope in the function that live on rfp.
+fn dump_frame(
+    annotations: &Annotations,
+    function: &DefSymbol,
+    scopes: &[ScopeRecord],
+    index: usize,
+    out: &mut String,
+) -> Result<(), CompilerError> {
+    let params = match &function.class {
+        SymbolClass::Fn(params) => params.as_slice(),
+        _ => &[],
+    };
+
+    let mut slots = vec![
+        (0, "return address".to_string()),
+        (4, "saved rsp".to_string()),
+        (8, "saved rfp".to_string()),
+        (RETURN_VAL_OFFSET, "return value".to_string()),
+    ];
+    let nested = scopes[index + 1..]
+        .iter()
+        .take_while(|record| record.depth > 1);
+    let locals: Vec<&DefSymbol> = std::iter::once(&scopes[index])
+        .chain(nested)
+        .flat_map(|record| record.symbols.iter())
+        .filter(|symbol| matches!(symbol.offset_source, Register::Rfp))
+        .collect();
+    for symbol in locals {
+        let kind = if params.iter().any(|param| param.node_id == symbol.span) {
+            "parameter"
+        } else {
+            "local"
+        };
+        let contents = format!("{} {} ({})", kind, symbol.id, symbol.type_value.to_str());
+        slots.push((symbol.offset as i32, contents));
+    }
+    slots.sort_by_key(|(offset, _contents)| *offset);
+
+    // Facts about the calls that a different frame layout could rely on.
+    let signature = annotations.fn_signature(function)?;
+    let mut notes = format!("parameters from rfp+{}", FN_OFFSET);
+    if annotations.call_graph().is_leaf(&signature) {
+        notes.push_str(", leaf");
+    }
+    if annotations.call_graph().is_recursive(&signature) {
+        notes.push_str(", recursive");
+    }
+    out.push_str(&format!("frame {} ({})\n", signature, notes));
+    let mut rows = vec![columns(&["location", "contents"])];
+    for (offset, contents) in slots {
+        rows.push(vec![location(Register::Rfp, offset), contents]);
+    }
+    push_table(&rows, "  ", out);
+    Ok(())
+}
+
+fn columns(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn location(register: Register, offset: i32) -> String {
+    format!("{}+{}", register, offset)
+}
+
+fn push_table(rows: &[Vec<String>], indent: &str, out: &mut String) {
+    let mut widths = vec![0; rows[0].len()];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, cell.len());
+        }
+    }
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(indent);
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+}
@@ -0,0 +1,302 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use lrpar::{NonStreamingLexer, Span};
+use std::collections::HashMap;
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::ast_node::{AstNode, NodeKind};
+use super::lexical_structures::{
+    Asm, CommandBlock, FnCall, LiteralChar, LiteralString, LocalVarDef, Ternary, VecAccess,
+};
+use super::visitor::{walk_fn_call, walk_node, walk_ternary, walk_vec_access, Visitor};
+
+// Prints the tree in the Etapa 3 format: every edge as "parent, child", then
+// one label line per node. Nodes are identified by their address in the arena.
+pub fn print_tree(arena: &AstArena, top_level: &[NodeId], lexer: &dyn NonStreamingLexer<u32>) {
+    let mut edges = vec![];
+    collect_list_edges(arena, top_level, None, &mut edges);
+    for (parent, child, _) in edges {
+        println!("{:p}, {:p}", arena.address(parent), arena.address(child));
+    }
+    for (id, label) in collect_labels(arena, top_level, lexer) {
+        println!("{:p} [label=\"{}\"];", arena.address(id), label);
+    }
+}
+
+// Same tree as print_tree, as a Graphviz digraph. Nodes are numbered in
+// preorder so the output doesn't change between runs, and each function
+// gets its own cluster.
+pub fn dot_tree(
+    arena: &AstArena,
+    top_level: &[NodeId],
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> String {
+    let mut out = String::from("digraph ast {\n");
+    out.push_str("  node [fontname=\"monospace\"];\n");
+    out.push_str("  edge [fontname=\"monospace\", fontsize=10];\n");
+
+    let mut dot_ids = HashMap::new();
+    for (cluster, id) in top_level
+        .iter()
+        .filter(|id| is_tree_member(arena, **id))
+        .enumerate()
+    {
+        let labels = collect_labels(arena, &[*id], lexer);
+        let is_cluster = arena.kind(*id) == NodeKind::FnDef;
+        let indent = if is_cluster { "    " } else { "  " };
+        if is_cluster {
+            let name = lexer.span_str(arena.node(*id).get_span());
+            out.push_str(&format!("  subgraph cluster_{} {{\n", cluster));
+            out.push_str(&format!("    label=\"{}\";\n", dot_escape(name)));
+        }
+        for (node, label) in labels {
+            let dot_id = dot_ids.len();
+            dot_ids.insert(node, dot_id);
+            out.push_str(&format!(
+                "{}n{} [label=\"{}\", {}];\n",
+                indent,
+                dot_id,
+                dot_escape(&label),
+                dot_shape(arena.kind(node))
+            ));
+        }
+        if is_cluster {
+            out.push_str("  }\n");
+        }
+    }
+
+    let mut edges = vec![];
+    collect_list_edges(arena, top_level, None, &mut edges);
+    for (parent, child, edge) in edges {
+        let attributes = match edge {
+            Edge::Child => "label=\"child\"",
+            Edge::Next => "label=\"next\", style=dashed",
+        };
+        out.push_str(&format!(
+            "  n{} -> n{} [{}];\n",
+            dot_ids[&parent], dot_ids[&child], attributes
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_shape(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::GlobalVarDef
+        | NodeKind::GlobalVecDef
+        | NodeKind::EnumDef
+        | NodeKind::FnDef
+        | NodeKind::LocalVarDef
+        | NodeKind::VarDefInitId
+        | NodeKind::VarDefInitLit => "shape=box",
+        NodeKind::If
+        | NodeKind::IfElse
+        | NodeKind::For
+        | NodeKind::While
+        | NodeKind::Return
+        | NodeKind::Continue
+        | NodeKind::Break => "shape=diamond",
+        NodeKind::VarLeftShift
+        | NodeKind::VarRightShift
+        | NodeKind::VecLeftShift
+        | NodeKind::VecRightShift
+        | NodeKind::VarSet
+        | NodeKind::VecSet
+        | NodeKind::Input
+        | NodeKind::OutputId
+        | NodeKind::OutputLit
+        | NodeKind::Asm
+        | NodeKind::CommandBlock => "shape=box, style=rounded",
+        _ => "shape=ellipse",
+    }
+}
+
+pub fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Definitions without initialization and blocks are left out of the tree.
+fn is_tree_member(arena: &AstArena, id: NodeId) -> bool {
+    match arena.kind(id) {
+        NodeKind::GlobalVarDef
+        | NodeKind::GlobalVecDef
+        | NodeKind::EnumDef
+        | NodeKind::CommandBlock => false,
+        NodeKind::LocalVarDef => arena.get(id.cast::<LocalVarDef>()).is_tree_node(),
+        _ => true,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Edge {
+    Child,
+    Next,
+}
+
+// Each list member is linked to the next one, and the first one to the list
+// owner, when there is one.
+fn collect_list_edges(
+    arena: &AstArena,
+    list: &[NodeId],
+    owner: Option<NodeId>,
+    edges: &mut Vec<(NodeId, NodeId, Edge)>,
+) {
+    let mut previous = owner.map(|owner| (owner, Edge::Child));
+    for id in list.iter().filter(|id| is_tree_member(arena, **id)) {
+        if let Some((previous, edge)) = previous {
+            edges.push((previous, *id, edge));
+        }
+        collect_edges(arena, *id, edges);
+        previous = Some((*id, Edge::Next));
+    }
+}
+
+fn collect_edges(arena: &AstArena, id: NodeId, edges: &mut Vec<(NodeId, NodeId, Edge)>) {
+    let mut children = ChildCollector::default();
+    walk_node(&mut children, arena, id);
+
+    let operands: Vec<NodeId> = children
+        .operands
+        .into_iter()
+        .filter(|id| is_tree_member(arena, *id))
+        .collect();
+
+    for child in &operands {
+        edges.push((id, *child, Edge::Child));
+    }
+    for child in &operands {
+        collect_edges(arena, *child, edges);
+    }
+    for block in children.blocks {
+        collect_list_edges(arena, arena.get(block).commands(), Some(id), edges);
+    }
+}
+
+// Gathers the direct children of a node without descending any further.
+#[derive(Default)]
+struct ChildCollector {
+    operands: Vec<NodeId>,
+    blocks: Vec<NodeId<CommandBlock>>,
+}
+
+impl Visitor for ChildCollector {
+    fn visit_node(&mut self, arena: &AstArena, id: NodeId) {
+        match arena.kind(id) {
+            NodeKind::CommandBlock => self.blocks.push(id.cast()),
+            _ => self.operands.push(id),
+        }
+    }
+}
+
+// Labels of every tree node under the list, in preorder.
+fn collect_labels(
+    arena: &AstArena,
+    list: &[NodeId],
+    lexer: &dyn NonStreamingLexer<u32>,
+) -> Vec<(NodeId, String)> {
+    let mut labels = LabelCollector {
+        lexer,
+        labels: vec![],
+    };
+    for id in list.iter().filter(|id| is_tree_member(arena, **id)) {
+        labels.visit_node(arena, *id);
+    }
+    labels.labels
+}
+
+struct LabelCollector<'a, 'input> {
+    lexer: &'a dyn NonStreamingLexer<'input, u32>,
+    labels: Vec<(NodeId, String)>,
+}
+
+impl LabelCollector<'_, '_> {
+    fn label(&mut self, id: NodeId, label: &str) {
+        self.labels.push((id, label.to_string()));
+    }
+
+    fn span_label(&mut self, arena: &AstArena, id: NodeId) {
+        let span = arena.node(id).get_span();
+        self.label(id, self.lexer.span_str(span));
+    }
+
+    fn quoted_label(&mut self, arena: &AstArena, id: NodeId) {
+        let text = self.lexer.span_str(arena.node(id).get_span());
+        self.label(id, &text[1..(text.len() - 1)]);
+    }
+}
+
+impl Visitor for LabelCollector<'_, '_> {
+    fn visit_node(&mut self, arena: &AstArena, id: NodeId) {
+        match arena.kind(id) {
+            // Blocks have no label, and these nodes print a custom one.
+            NodeKind::CommandBlock
+            | NodeKind::FnCall
+            | NodeKind::Ternary
+            | NodeKind::VecAccess
+            | NodeKind::Asm
+            | NodeKind::LiteralChar
+            | NodeKind::LiteralString => {}
+            _ if !is_tree_member(arena, id) => {}
+            _ => self.span_label(arena, id),
+        }
+        walk_node(self, arena, id);
+    }
+
+    fn visit_command_block(
+        &mut self,
+        arena: &AstArena,
+        _id: NodeId<CommandBlock>,
+        node: &CommandBlock,
+    ) {
+        for command in node.commands() {
+            if is_tree_member(arena, *command) {
+                self.visit_node(arena, *command);
+            }
+        }
+    }
+
+    fn visit_fn_call(&mut self, arena: &AstArena, id: NodeId<FnCall>, node: &FnCall) {
+        let name = self.lexer.span_str(node.get_span());
+        self.label(id.erase(), &format!("call {}", name));
+        walk_fn_call(self, arena, id, node);
+    }
+
+    fn visit_ternary(&mut self, arena: &AstArena, id: NodeId<Ternary>, node: &Ternary) {
+        self.label(id.erase(), "?:");
+        walk_ternary(self, arena, id, node);
+    }
+
+    fn visit_vec_access(&mut self, arena: &AstArena, id: NodeId<VecAccess>, node: &VecAccess) {
+        self.label(id.erase(), "[]");
+        walk_vec_access(self, arena, id, node);
+    }
+
+    fn visit_asm(&mut self, _arena: &AstArena, id: NodeId<Asm>, node: &Asm) {
+        // Only the keyword, the body spans several lines.
+        let start = node.get_span().start();
+        let keyword = Span::new(start, start + "asm".len());
+        self.label(id.erase(), self.lexer.span_str(keyword));
+    }
+
+    fn visit_literal_char(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<LiteralChar>,
+        _node: &LiteralChar,
+    ) {
+        self.quoted_label(arena, id.erase());
+    }
+
+    fn visit_literal_string(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<LiteralString>,
+        _node: &LiteralString,
+    ) {
+        self.quoted_label(arena, id.erase());
+    }
+}
@@ -0,0 +1,81 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use lrlex::LRNonStreamingLexer;
+use lrpar::{LexError, Lexeme, Lexer, NonStreamingLexer, Span};
+
+// A comment and the start of the token it comes before, or usize::MAX when
+// it ends the source.
+#[derive(Clone, Copy, Debug)]
+pub struct Comment {
+    pub span: Span,
+    pub token: usize,
+}
+
+// The scanner lexes comments as TK_COMMENT, which no grammar rule accepts.
+// They are set aside here as trivia of the token that follows them, and the
+// parser only sees the other lexemes.
+pub struct TriviaLexer<'lexer, 'input> {
+    lexer: LRNonStreamingLexer<'lexer, 'input, u32>,
+    lexemes: Vec<Result<Lexeme<u32>, LexError>>,
+    comments: Vec<Comment>,
+}
+
+impl<'lexer, 'input> TriviaLexer<'lexer, 'input> {
+    pub fn new(lexer: LRNonStreamingLexer<'lexer, 'input, u32>) -> Self {
+        let mut lexemes = vec![];
+        let mut comments = vec![];
+        let mut pending = vec![];
+        for lexeme in lexer.iter() {
+            match lexeme {
+                Ok(lexeme) if is_comment(lexer.span_str(lexeme.span())) => {
+                    pending.push(lexeme.span());
+                }
+                Ok(lexeme) => {
+                    let token = lexeme.span().start();
+                    comments.extend(pending.drain(..).map(|span| Comment { span, token }));
+                    lexemes.push(Ok(lexeme));
+                }
+                Err(error) => lexemes.push(Err(error)),
+            }
+        }
+        let token = usize::MAX;
+        comments.extend(pending.into_iter().map(|span| Comment { span, token }));
+        TriviaLexer {
+            lexer,
+            lexemes,
+            comments,
+        }
+    }
+
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+}
+
+// Longest match makes these the only lexemes that start like a comment.
+fn is_comment(text: &str) -> bool {
+    text.starts_with("//") || text.starts_with("/*")
+}
+
+impl Lexer<u32> for TriviaLexer<'_, '_> {
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Result<Lexeme<u32>, LexError>> + 'a> {
+        Box::new(self.lexemes.iter().cloned())
+    }
+}
+
+impl<'input> NonStreamingLexer<'input, u32> for TriviaLexer<'_, 'input> {
+    fn span_str(&self, span: Span) -> &'input str {
+        self.lexer.span_str(span)
+    }
+
+    fn span_lines_str(&self, span: Span) -> &'input str {
+        self.lexer.span_lines_str(span)
+    }
+
+    fn line_col(&self, span: Span) -> ((usize, usize), (usize, usize)) {
+        self.lexer.line_col(span)
+    }
+}
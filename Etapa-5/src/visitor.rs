@@ -0,0 +1,593 @@
+// Grupo L
+
+// Guilherme de Oliveira (00278301)
+// Jean Pierre Comerlatto Darricarrere (00182408)
+
+use super::abstract_syntax_tree::{AstArena, NodeId};
+use super::ast_node::NodeKind;
+use super::lexical_structures::{
+    Asm, Binary, Break, Cast, CommandBlock, Continue, EnumDef, FnCall, FnDef, For, GlobalVarDef,
+    GlobalVecDef, If, IfElse, Input, LiteralBool, LiteralChar, LiteralFloat, LiteralInt,
+    LiteralString, LocalVarDef, OutputId, OutputLit, Return, Ternary, Unary, VarDefInitId,
+    VarDefInitLit, VarInvoke, VarLeftShift, VarRightShift, VarSet, VecAccess, VecInvoke,
+    VecLeftShift, VecRightShift, VecSet, While,
+};
+
+// Read-only traversal. Every visit_* method defaults to walking the node's
+// children in source order, so implementors only override what they need
+// and call the matching walk_* function to keep descending.
+pub trait Visitor {
+    fn visit_node(&mut self, arena: &AstArena, id: NodeId) {
+        walk_node(self, arena, id)
+    }
+    fn visit_global_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<GlobalVarDef>,
+        _node: &GlobalVarDef,
+    ) {
+    }
+    fn visit_global_vec_def(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<GlobalVecDef>,
+        node: &GlobalVecDef,
+    ) {
+        walk_global_vec_def(self, arena, id, node)
+    }
+    fn visit_enum_def(&mut self, _arena: &AstArena, _id: NodeId<EnumDef>, _node: &EnumDef) {}
+    fn visit_fn_def(&mut self, arena: &AstArena, id: NodeId<FnDef>, node: &FnDef) {
+        walk_fn_def(self, arena, id, node)
+    }
+    fn visit_local_var_def(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LocalVarDef>,
+        _node: &LocalVarDef,
+    ) {
+    }
+    fn visit_var_def_init_id(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VarDefInitId>,
+        node: &VarDefInitId,
+    ) {
+        walk_var_def_init_id(self, arena, id, node)
+    }
+    fn visit_var_def_init_lit(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VarDefInitLit>,
+        node: &VarDefInitLit,
+    ) {
+        walk_var_def_init_lit(self, arena, id, node)
+    }
+    fn visit_var_left_shift(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VarLeftShift>,
+        node: &VarLeftShift,
+    ) {
+        walk_var_left_shift(self, arena, id, node)
+    }
+    fn visit_var_right_shift(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VarRightShift>,
+        node: &VarRightShift,
+    ) {
+        walk_var_right_shift(self, arena, id, node)
+    }
+    fn visit_vec_left_shift(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VecLeftShift>,
+        node: &VecLeftShift,
+    ) {
+        walk_vec_left_shift(self, arena, id, node)
+    }
+    fn visit_vec_right_shift(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<VecRightShift>,
+        node: &VecRightShift,
+    ) {
+        walk_vec_right_shift(self, arena, id, node)
+    }
+    fn visit_var_set(&mut self, arena: &AstArena, id: NodeId<VarSet>, node: &VarSet) {
+        walk_var_set(self, arena, id, node)
+    }
+    fn visit_vec_set(&mut self, arena: &AstArena, id: NodeId<VecSet>, node: &VecSet) {
+        walk_vec_set(self, arena, id, node)
+    }
+    fn visit_input(&mut self, arena: &AstArena, id: NodeId<Input>, node: &Input) {
+        walk_input(self, arena, id, node)
+    }
+    fn visit_output_id(&mut self, arena: &AstArena, id: NodeId<OutputId>, node: &OutputId) {
+        walk_output_id(self, arena, id, node)
+    }
+    fn visit_output_lit(&mut self, arena: &AstArena, id: NodeId<OutputLit>, node: &OutputLit) {
+        walk_output_lit(self, arena, id, node)
+    }
+    fn visit_continue(&mut self, _arena: &AstArena, _id: NodeId<Continue>, _node: &Continue) {}
+    fn visit_break(&mut self, _arena: &AstArena, _id: NodeId<Break>, _node: &Break) {}
+    fn visit_asm(&mut self, _arena: &AstArena, _id: NodeId<Asm>, _node: &Asm) {}
+    fn visit_return(&mut self, arena: &AstArena, id: NodeId<Return>, node: &Return) {
+        walk_return(self, arena, id, node)
+    }
+    fn visit_fn_call(&mut self, arena: &AstArena, id: NodeId<FnCall>, node: &FnCall) {
+        walk_fn_call(self, arena, id, node)
+    }
+    fn visit_if(&mut self, arena: &AstArena, id: NodeId<If>, node: &If) {
+        walk_if(self, arena, id, node)
+    }
+    fn visit_if_else(&mut self, arena: &AstArena, id: NodeId<IfElse>, node: &IfElse) {
+        walk_if_else(self, arena, id, node)
+    }
+    fn visit_for(&mut self, arena: &AstArena, id: NodeId<For>, node: &For) {
+        walk_for(self, arena, id, node)
+    }
+    fn visit_while(&mut self, arena: &AstArena, id: NodeId<While>, node: &While) {
+        walk_while(self, arena, id, node)
+    }
+    fn visit_command_block(
+        &mut self,
+        arena: &AstArena,
+        id: NodeId<CommandBlock>,
+        node: &CommandBlock,
+    ) {
+        walk_command_block(self, arena, id, node)
+    }
+    fn visit_ternary(&mut self, arena: &AstArena, id: NodeId<Ternary>, node: &Ternary) {
+        walk_ternary(self, arena, id, node)
+    }
+    fn visit_binary(&mut self, arena: &AstArena, id: NodeId<Binary>, node: &Binary) {
+        walk_binary(self, arena, id, node)
+    }
+    fn visit_unary(&mut self, arena: &AstArena, id: NodeId<Unary>, node: &Unary) {
+        walk_unary(self, arena, id, node)
+    }
+    fn visit_cast(&mut self, arena: &AstArena, id: NodeId<Cast>, node: &Cast) {
+        walk_cast(self, arena, id, node)
+    }
+    fn visit_vec_access(&mut self, arena: &AstArena, id: NodeId<VecAccess>, node: &VecAccess) {
+        walk_vec_access(self, arena, id, node)
+    }
+    fn visit_var_invoke(&mut self, _arena: &AstArena, _id: NodeId<VarInvoke>, _node: &VarInvoke) {}
+    fn visit_vec_invoke(&mut self, _arena: &AstArena, _id: NodeId<VecInvoke>, _node: &VecInvoke) {}
+    fn visit_literal_int(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LiteralInt>,
+        _node: &LiteralInt,
+    ) {
+    }
+    fn visit_literal_float(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LiteralFloat>,
+        _node: &LiteralFloat,
+    ) {
+    }
+    fn visit_literal_bool(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LiteralBool>,
+        _node: &LiteralBool,
+    ) {
+    }
+    fn visit_literal_char(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LiteralChar>,
+        _node: &LiteralChar,
+    ) {
+    }
+    fn visit_literal_string(
+        &mut self,
+        _arena: &AstArena,
+        _id: NodeId<LiteralString>,
+        _node: &LiteralString,
+    ) {
+    }
+}
+
+pub fn walk_node<V: Visitor + ?Sized>(visitor: &mut V, arena: &AstArena, id: NodeId) {
+    match arena.kind(id) {
+        NodeKind::GlobalVarDef => {
+            let id = id.cast();
+            visitor.visit_global_var_def(arena, id, arena.get(id))
+        }
+        NodeKind::GlobalVecDef => {
+            let id = id.cast();
+            visitor.visit_global_vec_def(arena, id, arena.get(id))
+        }
+        NodeKind::EnumDef => {
+            let id = id.cast();
+            visitor.visit_enum_def(arena, id, arena.get(id))
+        }
+        NodeKind::FnDef => {
+            let id = id.cast();
+            visitor.visit_fn_def(arena, id, arena.get(id))
+        }
+        NodeKind::LocalVarDef => {
+            let id = id.cast();
+            visitor.visit_local_var_def(arena, id, arena.get(id))
+        }
+        NodeKind::VarDefInitId => {
+            let id = id.cast();
+            visitor.visit_var_def_init_id(arena, id, arena.get(id))
+        }
+        NodeKind::VarDefInitLit => {
+            let id = id.cast();
+            visitor.visit_var_def_init_lit(arena, id, arena.get(id))
+        }
+        NodeKind::VarLeftShift => {
+            let id = id.cast();
+            visitor.visit_var_left_shift(arena, id, arena.get(id))
+        }
+        NodeKind::VarRightShift => {
+            let id = id.cast();
+            visitor.visit_var_right_shift(arena, id, arena.get(id))
+        }
+        NodeKind::VecLeftShift => {
+            let id = id.cast();
+            visitor.visit_vec_left_shift(arena, id, arena.get(id))
+        }
+        NodeKind::VecRightShift => {
+            let id = id.cast();
+            visitor.visit_vec_right_shift(arena, id, arena.get(id))
+        }
+        NodeKind::VarSet => {
+            let id = id.cast();
+            visitor.visit_var_set(arena, id, arena.get(id))
+        }
+        NodeKind::VecSet => {
+            let id = id.cast();
+            visitor.visit_vec_set(arena, id, arena.get(id))
+        }
+        NodeKind::Input => {
+            let id = id.cast();
+            visitor.visit_input(arena, id, arena.get(id))
+        }
+        NodeKind::OutputId => {
+            let id = id.cast();
+            visitor.visit_output_id(arena, id, arena.get(id))
+        }
+        NodeKind::OutputLit => {
+            let id = id.cast();
+            visitor.visit_output_lit(arena, id, arena.get(id))
+        }
+        NodeKind::Continue => {
+            let id = id.cast();
+            visitor.visit_continue(arena, id, arena.get(id))
+        }
+        NodeKind::Break => {
+            let id = id.cast();
+            visitor.visit_break(arena, id, arena.get(id))
+        }
+        NodeKind::Asm => {
+            let id = id.cast();
+            visitor.visit_asm(arena, id, arena.get(id))
+        }
+        NodeKind::Return => {
+            let id = id.cast();
+            visitor.visit_return(arena, id, arena.get(id))
+        }
+        NodeKind::FnCall => {
+            let id = id.cast();
+            visitor.visit_fn_call(arena, id, arena.get(id))
+        }
+        NodeKind::If => {
+            let id = id.cast();
+            visitor.visit_if(arena, id, arena.get(id))
+        }
+        NodeKind::IfElse => {
+            let id = id.cast();
+            visitor.visit_if_else(arena, id, arena.get(id))
+        }
+        NodeKind::For => {
+            let id = id.cast();
+            visitor.visit_for(arena, id, arena.get(id))
+        }
+        NodeKind::While => {
+            let id = id.cast();
+            visitor.visit_while(arena, id, arena.get(id))
+        }
+        NodeKind::CommandBlock => {
+            let id = id.cast();
+            visitor.visit_command_block(arena, id, arena.get(id))
+        }
+        NodeKind::Ternary => {
+            let id = id.cast();
+            visitor.visit_ternary(arena, id, arena.get(id))
+        }
+        NodeKind::Binary => {
+            let id = id.cast();
+            visitor.visit_binary(arena, id, arena.get(id))
+        }
+        NodeKind::Unary => {
+            let id = id.cast();
+            visitor.visit_unary(arena, id, arena.get(id))
+        }
+        NodeKind::Cast => {
+            let id = id.cast();
+            visitor.visit_cast(arena, id, arena.get(id))
+        }
+        NodeKind::VecAccess => {
+            let id = id.cast();
+            visitor.visit_vec_access(arena, id, arena.get(id))
+        }
+        NodeKind::VarInvoke => {
+            let id = id.cast();
+            visitor.visit_var_invoke(arena, id, arena.get(id))
+        }
+        NodeKind::VecInvoke => {
+            let id = id.cast();
+            visitor.visit_vec_invoke(arena, id, arena.get(id))
+        }
+        NodeKind::LiteralInt => {
+            let id = id.cast();
+            visitor.visit_literal_int(arena, id, arena.get(id))
+        }
+        NodeKind::LiteralFloat => {
+            let id = id.cast();
+            visitor.visit_literal_float(arena, id, arena.get(id))
+        }
+        NodeKind::LiteralBool => {
+            let id = id.cast();
+            visitor.visit_literal_bool(arena, id, arena.get(id))
+        }
+        NodeKind::LiteralChar => {
+            let id = id.cast();
+            visitor.visit_literal_char(arena, id, arena.get(id))
+        }
+        NodeKind::LiteralString => {
+            let id = id.cast();
+            visitor.visit_literal_string(arena, id, arena.get(id))
+        }
+    }
+}
+
+pub fn walk_global_vec_def<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<GlobalVecDef>,
+    node: &GlobalVecDef,
+) {
+    visitor.visit_node(arena, node.vec_size().erase());
+}
+
+pub fn walk_fn_def<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<FnDef>,
+    node: &FnDef,
+) {
+    visitor.visit_node(arena, node.body().erase());
+}
+
+pub fn walk_var_def_init_id<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VarDefInitId>,
+    node: &VarDefInitId,
+) {
+    visitor.visit_node(arena, node.var_def().erase());
+    visitor.visit_node(arena, node.var_value());
+}
+
+pub fn walk_var_def_init_lit<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VarDefInitLit>,
+    node: &VarDefInitLit,
+) {
+    visitor.visit_node(arena, node.var_def().erase());
+    visitor.visit_node(arena, node.var_value());
+}
+
+pub fn walk_var_left_shift<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VarLeftShift>,
+    node: &VarLeftShift,
+) {
+    visitor.visit_node(arena, node.var_name().erase());
+    visitor.visit_node(arena, node.shift_amount().erase());
+}
+
+pub fn walk_var_right_shift<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VarRightShift>,
+    node: &VarRightShift,
+) {
+    visitor.visit_node(arena, node.var_name().erase());
+    visitor.visit_node(arena, node.shift_amount().erase());
+}
+
+pub fn walk_vec_left_shift<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VecLeftShift>,
+    node: &VecLeftShift,
+) {
+    visitor.visit_node(arena, node.vec_access().erase());
+    visitor.visit_node(arena, node.shift_amount());
+}
+
+pub fn walk_vec_right_shift<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VecRightShift>,
+    node: &VecRightShift,
+) {
+    visitor.visit_node(arena, node.vec_access().erase());
+    visitor.visit_node(arena, node.shift_amount());
+}
+
+pub fn walk_var_set<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VarSet>,
+    node: &VarSet,
+) {
+    visitor.visit_node(arena, node.var_name().erase());
+    visitor.visit_node(arena, node.new_value());
+}
+
+pub fn walk_vec_set<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VecSet>,
+    node: &VecSet,
+) {
+    visitor.visit_node(arena, node.vec_access().erase());
+    visitor.visit_node(arena, node.new_value());
+}
+
+pub fn walk_input<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Input>,
+    node: &Input,
+) {
+    visitor.visit_node(arena, node.var_name());
+}
+
+pub fn walk_output_id<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<OutputId>,
+    node: &OutputId,
+) {
+    visitor.visit_node(arena, node.var_name());
+}
+
+pub fn walk_output_lit<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<OutputLit>,
+    node: &OutputLit,
+) {
+    visitor.visit_node(arena, node.lit_value());
+}
+
+pub fn walk_return<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Return>,
+    node: &Return,
+) {
+    visitor.visit_node(arena, node.ret_value());
+}
+
+pub fn walk_fn_call<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<FnCall>,
+    node: &FnCall,
+) {
+    for child in node.args() {
+        visitor.visit_node(arena, *child);
+    }
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, arena: &AstArena, _id: NodeId<If>, node: &If) {
+    visitor.visit_node(arena, node.condition());
+    visitor.visit_node(arena, node.consequence().erase());
+}
+
+pub fn walk_if_else<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<IfElse>,
+    node: &IfElse,
+) {
+    visitor.visit_node(arena, node.condition());
+    visitor.visit_node(arena, node.if_true().erase());
+    visitor.visit_node(arena, node.if_false().erase());
+}
+
+pub fn walk_for<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<For>,
+    node: &For,
+) {
+    visitor.visit_node(arena, node.count_init());
+    visitor.visit_node(arena, node.count_check());
+    visitor.visit_node(arena, node.count_iter());
+    visitor.visit_node(arena, node.actions().erase());
+}
+
+pub fn walk_while<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<While>,
+    node: &While,
+) {
+    visitor.visit_node(arena, node.condition());
+    visitor.visit_node(arena, node.consequence().erase());
+}
+
+pub fn walk_command_block<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<CommandBlock>,
+    node: &CommandBlock,
+) {
+    for child in node.commands() {
+        visitor.visit_node(arena, *child);
+    }
+}
+
+pub fn walk_ternary<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Ternary>,
+    node: &Ternary,
+) {
+    visitor.visit_node(arena, node.condition());
+    visitor.visit_node(arena, node.if_true());
+    visitor.visit_node(arena, node.if_false());
+}
+
+pub fn walk_binary<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Binary>,
+    node: &Binary,
+) {
+    visitor.visit_node(arena, node.lhs());
+    visitor.visit_node(arena, node.rhs());
+}
+
+pub fn walk_unary<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Unary>,
+    node: &Unary,
+) {
+    visitor.visit_node(arena, node.operand());
+}
+
+pub fn walk_cast<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<Cast>,
+    node: &Cast,
+) {
+    visitor.visit_node(arena, node.operand());
+}
+
+pub fn walk_vec_access<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    arena: &AstArena,
+    _id: NodeId<VecAccess>,
+    node: &VecAccess,
+) {
+    visitor.visit_node(arena, node.vec_name().erase());
+    visitor.visit_node(arena, node.vec_index());
+}